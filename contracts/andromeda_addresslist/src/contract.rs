@@ -1,33 +1,45 @@
+use andromeda_protocol::error::ContractError;
 use andromeda_protocol::{
-    address_list::{AddressList, ExecuteMsg, IncludesAddressResponse, InstantiateMsg, QueryMsg},
-    ownership::{execute_update_owner, query_contract_owner, CONTRACT_OWNER},
+    address_list::{
+        AddressList, ExecuteMsg, IncludesAddressResponse, InstantiateMsg, MigrateMsg, QueryMsg,
+    },
+    ownership::{
+        execute_accept_ownership, execute_cancel_ownership_proposal, execute_propose_owner,
+        execute_renounce_ownership, query_contract_owner, query_pending_owner, CONTRACT_OWNER,
+    },
     require,
+    roles::{
+        execute_grant_role, execute_revoke_role, grant_role, has_role, query_has_role,
+        query_role_members, ROLE_MODERATOR,
+    },
+    version::{migrate_response, migrate_version, query_version, set_contract_version},
 };
 use cosmwasm_std::{
-    attr, entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError,
-    StdResult,
+    attr, entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response,
 };
 
-use crate::{
-    error::ContractError,
-    state::{State, STATE},
-};
+use crate::state::{migrate_moderators, State, STATE};
+
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     let state = State {
         owner: info.sender.to_string(),
-        address_list: AddressList {
-            moderators: msg.moderators,
-        },
+        address_list: AddressList {},
     };
 
     CONTRACT_OWNER.save(deps.storage, &info.sender.to_string())?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    for moderator in msg.moderators {
+        grant_role(deps.storage, &env.block, ROLE_MODERATOR, &moderator, None)?;
+    }
     STATE.save(deps.storage, &state)?;
 
     Ok(Response::default().add_attributes(vec![
@@ -39,23 +51,45 @@ pub fn instantiate(
 #[entry_point]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::AddAddress { address } => execute_add_address(deps, info, address),
-        ExecuteMsg::RemoveAddress { address } => execute_remove_address(deps, info, address),
-        ExecuteMsg::UpdateOwner { address } => execute_update_owner(deps, info, address),
+        ExecuteMsg::AddAddress { address } => execute_add_address(deps, env, info, address),
+        ExecuteMsg::RemoveAddress { address } => execute_remove_address(deps, env, info, address),
+        ExecuteMsg::ProposeOwner {
+            address,
+            expiration,
+        } => execute_propose_owner(deps, env, info, address, expiration),
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, env, info),
+        ExecuteMsg::CancelOwnershipProposal {} => execute_cancel_ownership_proposal(deps, info),
+        ExecuteMsg::RenounceOwnership {} => execute_renounce_ownership(deps, info),
+        ExecuteMsg::GrantRole {
+            role,
+            address,
+            expiration,
+        } => execute_grant_role(deps, env, info, role, address, expiration),
+        ExecuteMsg::RevokeRole { role, address } => execute_revoke_role(deps, info, role, address),
     }
 }
 
-fn execute_add_address(deps: DepsMut, info: MessageInfo, address: String) -> StdResult<Response> {
+fn execute_add_address(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
     let state = STATE.load(deps.storage)?;
 
     require(
-        state.address_list.is_moderator(&info.sender.to_string()),
-        StdError::generic_err("Only a moderator can add an address to the address list"),
+        has_role(
+            deps.storage,
+            &env.block,
+            ROLE_MODERATOR,
+            info.sender.as_str(),
+        )?,
+        ContractError::AddAddressUnauthorized {},
     )?;
 
     state
@@ -73,13 +107,19 @@ fn execute_add_address(deps: DepsMut, info: MessageInfo, address: String) -> Std
 
 fn execute_remove_address(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     address: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let state = STATE.load(deps.storage)?;
     require(
-        state.address_list.is_moderator(&info.sender.to_string()),
-        StdError::generic_err("Only a moderator can remove an address from the address list"),
+        has_role(
+            deps.storage,
+            &env.block,
+            ROLE_MODERATOR,
+            info.sender.as_str(),
+        )?,
+        ContractError::RemoveAddressUnauthorized {},
     )?;
 
     state.address_list.remove_address(deps.storage, &address);
@@ -92,14 +132,38 @@ fn execute_remove_address(
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
-        QueryMsg::IncludesAddress { address } => to_binary(&query_address(deps, &address)?),
-        QueryMsg::ContractOwner {} => to_binary(&query_contract_owner(deps)?),
+        QueryMsg::IncludesAddress { address } => Ok(to_binary(&query_address(deps, &address)?)?),
+        QueryMsg::ContractOwner {} => Ok(to_binary(&query_contract_owner(deps)?)?),
+        QueryMsg::PendingOwner {} => Ok(to_binary(&query_pending_owner(deps)?)?),
+        QueryMsg::Version {} => Ok(to_binary(&query_version(deps)?)?),
+        QueryMsg::HasRole { role, address } => {
+            Ok(to_binary(&query_has_role(deps, env, role, address)?)?)
+        }
+        QueryMsg::RoleMembers {
+            role,
+            start_after,
+            limit,
+        } => Ok(to_binary(&query_role_members(
+            deps,
+            env,
+            role,
+            start_after,
+            limit,
+        )?)?),
     }
 }
 
-fn query_address(deps: Deps, address: &String) -> StdResult<IncludesAddressResponse> {
+#[entry_point]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let previous = migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    migrate_moderators(deps.storage, &env.block)?;
+
+    Ok(migrate_response(previous, CONTRACT_VERSION))
+}
+
+fn query_address(deps: Deps, address: &String) -> Result<IncludesAddressResponse, ContractError> {
     let state = STATE.load(deps.storage)?;
 
     Ok(IncludesAddressResponse {
@@ -110,8 +174,14 @@ fn query_address(deps: Deps, address: &String) -> StdResult<IncludesAddressRespo
 #[cfg(test)]
 mod tests {
     use super::*;
-    use andromeda_protocol::address_list::ADDRESS_LIST;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use andromeda_protocol::{
+        address_list::ADDRESS_LIST, roles::HasRoleResponse, version::ContractVersion,
+    };
+    use cosmwasm_std::{
+        from_binary,
+        testing::{mock_dependencies, mock_env, mock_info},
+        Storage,
+    };
 
     #[test]
     fn test_instantiate() {
@@ -121,8 +191,20 @@ mod tests {
         let msg = InstantiateMsg {
             moderators: vec!["11".to_string(), "22".to_string()],
         };
-        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+        let res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
         assert_eq!(0, res.messages.len());
+
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::HasRole {
+                role: ROLE_MODERATOR.to_string(),
+                address: "11".to_string(),
+            },
+        )
+        .unwrap();
+        let res: HasRoleResponse = from_binary(&res).unwrap();
+        assert!(res.has_role);
     }
 
     #[test]
@@ -139,12 +221,18 @@ mod tests {
 
         let state = State {
             owner: moderator.to_string(),
-            address_list: AddressList {
-                moderators: vec![moderator.to_string()],
-            },
+            address_list: AddressList {},
         };
 
         STATE.save(deps.as_mut().storage, &state).unwrap();
+        grant_role(
+            deps.as_mut().storage,
+            &env.block,
+            ROLE_MODERATOR,
+            moderator,
+            None,
+        )
+        .unwrap();
 
         let msg = ExecuteMsg::AddAddress {
             address: address.to_string(),
@@ -180,10 +268,7 @@ mod tests {
         //add address for unregistered moderator
         let unauth_info = mock_info("anyone", &[]);
         let res = execute(deps.as_mut(), env, unauth_info, msg).unwrap_err();
-        assert_eq!(
-            StdError::generic_err("Only a moderator can add an address to the address list"),
-            res
-        );
+        assert_eq!(ContractError::AddAddressUnauthorized {}, res);
     }
 
     #[test]
@@ -200,12 +285,18 @@ mod tests {
 
         let state = State {
             owner: moderator.to_string(),
-            address_list: AddressList {
-                moderators: vec![moderator.to_string()],
-            },
+            address_list: AddressList {},
         };
 
         STATE.save(deps.as_mut().storage, &state).unwrap();
+        grant_role(
+            deps.as_mut().storage,
+            &env.block,
+            ROLE_MODERATOR,
+            moderator,
+            None,
+        )
+        .unwrap();
 
         let msg = ExecuteMsg::RemoveAddress {
             address: address.to_string(),
@@ -227,9 +318,49 @@ mod tests {
         //add address for unregistered moderator
         let unauth_info = mock_info("anyone", &[]);
         let res = execute(deps.as_mut(), env, unauth_info.clone(), msg).unwrap_err();
+        assert_eq!(ContractError::RemoveAddressUnauthorized {}, res);
+    }
+
+    #[test]
+    fn test_migrate() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+
+        // State stored before moderators were moved to the moderator role
+        deps.storage.set(
+            b"state",
+            br#"{"owner":"creator","address_list":{"moderators":["moderator"]}}"#,
+        );
+
+        let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+        assert_eq!(
+            res,
+            Response::new().add_attributes(vec![
+                attr("action", "migrate"),
+                attr("from_version", "none"),
+                attr("to_version", CONTRACT_VERSION),
+            ])
+        );
+        assert!(has_role(&deps.storage, &env.block, ROLE_MODERATOR, "moderator").unwrap());
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Version {}).unwrap();
+        let version: ContractVersion = from_binary(&res).unwrap();
         assert_eq!(
-            StdError::generic_err("Only a moderator can remove an address from the address list"),
-            res
+            version,
+            ContractVersion {
+                contract: CONTRACT_NAME.to_string(),
+                version: CONTRACT_VERSION.to_string(),
+            }
+        );
+
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "999.0.0").unwrap();
+        let res = migrate(deps.as_mut(), env, MigrateMsg {}).unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::CannotMigrateToOlderVersion {
+                current: String::from("999.0.0"),
+                new: CONTRACT_VERSION.to_string(),
+            }
         );
     }
 }
@@ -1,15 +0,0 @@
-use cosmwasm_std::{OverflowError, StdError};
-use thiserror::Error;
-
-#[derive(Error, Debug, PartialEq)]
-pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
-    #[error("{0}")]
-    OverflowError(#[from] OverflowError),
-    #[error("unauthorized")]
-    Unauthorized {},
-    #[error("not existed address")]
-    NotExisted {},
-
-}
\ No newline at end of file
@@ -1,5 +1,4 @@
 pub mod contract;
-mod error;
 pub mod state;
 #[cfg(test)]
-mod testing;
\ No newline at end of file
+mod testing;
@@ -1,4 +1,7 @@
 use andromeda_protocol::address_list::AddressList;
+use andromeda_protocol::error::ContractError;
+use andromeda_protocol::roles::{grant_role, ROLE_MODERATOR};
+use cosmwasm_std::{from_slice, BlockInfo, Storage};
 use cw_storage_plus::Item;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -10,3 +13,32 @@ pub struct State {
     pub owner: String,
     pub address_list: AddressList,
 }
+
+#[derive(Deserialize)]
+/// The stored format of `State` before moderators were moved to the `moderator` role
+struct LegacyState {
+    address_list: LegacyAddressList,
+}
+
+#[derive(Deserialize)]
+struct LegacyAddressList {
+    #[serde(default)]
+    moderators: Vec<String>,
+}
+
+/// Grants the `moderator` role to any moderators stored in the legacy state format
+pub fn migrate_moderators(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+) -> Result<(), ContractError> {
+    let legacy: LegacyState = match storage.get(b"state") {
+        Some(data) => from_slice(&data)?,
+        None => return Ok(()),
+    };
+    for moderator in legacy.address_list.moderators {
+        grant_role(storage, block, ROLE_MODERATOR, &moderator, None)?;
+    }
+    // Re-save the state to drop the legacy field
+    let state = STATE.load(storage)?;
+    Ok(STATE.save(storage, &state)?)
+}
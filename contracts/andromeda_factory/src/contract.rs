@@ -1,13 +1,19 @@
+use andromeda_protocol::error::ContractError;
 use andromeda_protocol::{
-    factory::{AddressResponse, CodeIdsResponse, ExecuteMsg, InstantiateMsg, QueryMsg},
+    factory::{AddressResponse, CodeIdsResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg},
     modules::ModuleDefinition,
-    ownership::{execute_update_owner, is_contract_owner, query_contract_owner, CONTRACT_OWNER},
+    ownership::{
+        execute_accept_ownership, execute_cancel_ownership_proposal, execute_propose_owner,
+        execute_renounce_ownership, is_contract_owner, query_contract_owner, query_pending_owner,
+        CONTRACT_OWNER,
+    },
     require,
     token::InstantiateMsg as TokenInstantiateMsg,
+    version::{migrate_response, migrate_version, query_version, set_contract_version},
 };
 use cosmwasm_std::{
     attr, entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, ReplyOn,
-    Response, StdError, StdResult, SubMsg, WasmMsg,
+    Response, StdError, SubMsg, WasmMsg,
 };
 
 use crate::{
@@ -18,13 +24,16 @@ use crate::{
     },
 };
 
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     store_config(
         deps.storage,
         &Config {
@@ -35,25 +44,31 @@ pub fn instantiate(
     )?;
 
     CONTRACT_OWNER.save(deps.storage, &info.sender.to_string())?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::default()
         .add_attributes(vec![attr("action", "instantiate"), attr("type", "factory")]))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
     if msg.result.is_err() {
-        return Err(StdError::generic_err(msg.result.unwrap_err()));
+        return Err(StdError::generic_err(msg.result.unwrap_err()).into());
     }
 
     match msg.id {
         REPLY_CREATE_TOKEN => on_token_creation_reply(deps, msg),
-        _ => Err(StdError::generic_err("reply id is invalid")),
+        _ => Err(ContractError::InvalidReplyId {}),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Create {
             symbol,
@@ -64,7 +79,13 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             symbol,
             new_address,
         } => update_address(deps, env, info, symbol, new_address),
-        ExecuteMsg::UpdateOwner { address } => execute_update_owner(deps, info, address),
+        ExecuteMsg::ProposeOwner {
+            address,
+            expiration,
+        } => execute_propose_owner(deps, env, info, address, expiration),
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, env, info),
+        ExecuteMsg::CancelOwnershipProposal {} => execute_cancel_ownership_proposal(deps, info),
+        ExecuteMsg::RenounceOwnership {} => execute_renounce_ownership(deps, info),
         ExecuteMsg::UpdateCodeId {
             address_list_code_id,
             receipt_code_id,
@@ -87,12 +108,12 @@ pub fn create(
     name: String,
     symbol: String,
     modules: Vec<ModuleDefinition>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let config = read_config(deps.storage)?;
 
     require(
         !is_address_defined(deps.storage, symbol.to_string())?,
-        StdError::generic_err("Symbol is in use"),
+        ContractError::SymbolInUse {},
     )?;
 
     //Assign Code IDs to Modules
@@ -135,6 +156,10 @@ pub fn create(
         symbol: symbol.to_string(),
         minter: info.sender.to_string(),
         modules: updated_modules,
+        max_supply: None,
+        mint_start: None,
+        mint_end: None,
+        metadata_update_policy: None,
     };
     // [TOK-01 Validation Process]
     let validation = token_inst_msg.validate();
@@ -172,11 +197,11 @@ pub fn update_address(
     info: MessageInfo,
     symbol: String,
     new_address: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     require(
         is_creator(&deps, symbol.clone(), info.sender.to_string())?
             || is_contract_owner(deps.storage, info.sender.to_string())?,
-        StdError::generic_err("Cannot update address for ADO that you did not create"),
+        ContractError::UpdateAddressUnauthorized {},
     )?;
 
     store_address(deps.storage, symbol, &new_address)?;
@@ -191,11 +216,14 @@ pub fn update_code_id(
     receipt_code_id: Option<u64>,
     address_list_code_id: Option<u64>,
     token_code_id: Option<u64>,
-) -> StdResult<Response> {
-    require(receipt_code_id.is_some() || address_list_code_id.is_some() || token_code_id.is_some(), StdError::generic_err("Must provide one of the following: \"receipt_code_id\", \"token_code_id\", \"address_list_code_id\""))?;
+) -> Result<Response, ContractError> {
+    require(
+        receipt_code_id.is_some() || address_list_code_id.is_some() || token_code_id.is_some(),
+        ContractError::MissingCodeId {},
+    )?;
     require(
         is_contract_owner(deps.storage, info.sender.to_string())?,
-        StdError::generic_err("Can only be used by the contract owner"),
+        ContractError::Unauthorized {},
     )?;
     let mut config = read_config(deps.storage)?;
 
@@ -228,20 +256,29 @@ pub fn update_code_id(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
-        QueryMsg::GetAddress { symbol } => to_binary(&query_address(deps, symbol)?),
-        QueryMsg::ContractOwner {} => to_binary(&query_contract_owner(deps)?),
-        QueryMsg::CodeIds {} => to_binary(&query_code_ids(deps)?),
+        QueryMsg::GetAddress { symbol } => Ok(to_binary(&query_address(deps, symbol)?)?),
+        QueryMsg::ContractOwner {} => Ok(to_binary(&query_contract_owner(deps)?)?),
+        QueryMsg::PendingOwner {} => Ok(to_binary(&query_pending_owner(deps)?)?),
+        QueryMsg::Version {} => Ok(to_binary(&query_version(deps)?)?),
+        QueryMsg::CodeIds {} => Ok(to_binary(&query_code_ids(deps)?)?),
     }
 }
 
-fn query_address(deps: Deps, symbol: String) -> StdResult<AddressResponse> {
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let previous = migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(migrate_response(previous, CONTRACT_VERSION))
+}
+
+fn query_address(deps: Deps, symbol: String) -> Result<AddressResponse, ContractError> {
     let address = read_address(deps.storage, symbol)?;
     Ok(AddressResponse { address })
 }
 
-fn query_code_ids(deps: Deps) -> StdResult<CodeIdsResponse> {
+fn query_code_ids(deps: Deps) -> Result<CodeIdsResponse, ContractError> {
     let config = read_config(deps.storage)?;
 
     Ok(CodeIdsResponse {
@@ -310,6 +347,10 @@ mod tests {
             symbol: TOKEN_SYMBOL.to_string(),
             minter: info.sender.to_string(),
             modules: vec![],
+            max_supply: None,
+            mint_start: None,
+            mint_end: None,
+            metadata_update_policy: None,
         };
         // [TOK-01 Validation Process]
         let validation = token_inst_msg.validate();
@@ -375,10 +416,7 @@ mod tests {
         let unauth_res =
             execute(deps.as_mut(), unauth_env, unauth_info, update_msg.clone()).unwrap_err();
 
-        assert_eq!(
-            unauth_res,
-            StdError::generic_err("Cannot update address for ADO that you did not create"),
-        );
+        assert_eq!(unauth_res, ContractError::UpdateAddressUnauthorized {},);
 
         let update_res = execute(deps.as_mut(), env.clone(), info, update_msg).unwrap();
 
@@ -417,7 +455,7 @@ mod tests {
         };
 
         let resp = execute(deps.as_mut(), env.clone(), info.clone(), invalid_msg).unwrap_err();
-        let expected = StdError::generic_err("Must provide one of the following: \"receipt_code_id\", \"token_code_id\", \"address_list_code_id\"");
+        let expected = ContractError::MissingCodeId {};
 
         assert_eq!(resp, expected);
 
@@ -429,7 +467,7 @@ mod tests {
         };
 
         let resp = execute(deps.as_mut(), env.clone(), unauth_info, msg.clone()).unwrap_err();
-        let expected = StdError::generic_err("Can only be used by the contract owner");
+        let expected = ContractError::Unauthorized {};
 
         assert_eq!(resp, expected);
 
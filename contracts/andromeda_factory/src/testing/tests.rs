@@ -4,7 +4,7 @@ use crate::reply::REPLY_CREATE_TOKEN;
 use andromeda_protocol::{
     factory::{ExecuteMsg, InstantiateMsg},
     modules::ModuleDefinition,
-    modules::Rate,
+    modules::{AddressWeight, Rate},
     token::InstantiateMsg as TokenInstantiateMsg,
 };
 use cosmwasm_std::{
@@ -42,9 +42,9 @@ fn test_create() {
 
     let whitelist_moderators = vec!["whitelist_moderator1".to_string()];
     let tax_fee: Rate = Rate::Percent(1u64);
-    let tax_receivers = vec!["tax_recever1".to_string()];
+    let tax_receivers = vec![AddressWeight::new("tax_recever1", 1)];
     let royality_fee: Rate = Rate::Percent(1u64);
-    let royality_receivers = vec!["royality_recever1".to_string()];
+    let royality_receivers = vec![AddressWeight::new("royality_recever1", 1)];
     let modules = vec![
         ModuleDefinition::Whitelist {
             moderators: Some(whitelist_moderators),
@@ -55,11 +55,13 @@ fn test_create() {
             rate: tax_fee,
             receivers: tax_receivers,
             description: None,
+            rounding: None,
         },
         ModuleDefinition::Royalties {
             rate: royality_fee,
             receivers: royality_receivers,
             description: None,
+            rounding: None,
         },
     ];
 
@@ -83,6 +85,10 @@ fn test_create() {
         symbol: TOKEN_SYMBOL.to_string(),
         minter: info.sender.to_string(),
         modules,
+        max_supply: None,
+        mint_start: None,
+        mint_end: None,
+        metadata_update_policy: None,
     };
 
     let inst_msg = WasmMsg::Instantiate {
@@ -160,6 +166,6 @@ fn test_create() {
 
 //     assert_eq!(
 //         unauth_res,
-//         StdError::generic_err("Cannot update address for ADO that you did not create"),
+//         ContractError::UpdateAddressUnauthorized {},
 //     );
 // }
@@ -1,37 +1,44 @@
 use crate::state::{
-    can_mint_receipt, increment_num_receipt, read_receipt, store_config, store_receipt, CONFIG,
+    can_mint_receipt, increment_num_receipt, migrate_moderators, read_receipt, store_config,
+    store_receipt, CONFIG,
 };
+use andromeda_protocol::error::ContractError;
 use andromeda_protocol::{
-    ownership::{execute_update_owner, query_contract_owner, CONTRACT_OWNER},
+    ownership::{
+        execute_accept_ownership, execute_cancel_ownership_proposal, execute_propose_owner,
+        execute_renounce_ownership, query_contract_owner, query_pending_owner, CONTRACT_OWNER,
+    },
     receipt::{
-        Config, ContractInfoResponse, ExecuteMsg, InstantiateMsg, QueryMsg, Receipt,
+        Config, ContractInfoResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, Receipt,
         ReceiptResponse,
     },
     require,
+    roles::{
+        execute_grant_role, execute_revoke_role, grant_role, query_has_role, query_role_members,
+        ROLE_MODERATOR,
+    },
+    version::{migrate_response, migrate_version, query_version, set_contract_version},
 };
 use cosmwasm_std::{
-    attr, entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError,
-    StdResult, Uint128,
+    attr, entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, Uint128,
 };
 
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
-    store_config(
-        deps.storage,
-        &Config {
-            minter: msg.minter,
-            moderators: match msg.moderators {
-                Some(moderators) => moderators,
-                None => vec![],
-            },
-        },
-    )?;
+) -> Result<Response, ContractError> {
+    store_config(deps.storage, &Config { minter: msg.minter })?;
     CONTRACT_OWNER.save(deps.storage, &info.sender.to_string())?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    for moderator in msg.moderators.unwrap_or_default() {
+        grant_role(deps.storage, &env.block, ROLE_MODERATOR, &moderator, None)?;
+    }
     Ok(Response::default()
         .add_attributes(vec![attr("action", "instantiate"), attr("type", "receipt")]))
 }
@@ -39,30 +46,41 @@ pub fn instantiate(
 #[entry_point]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::StoreReceipt { receipt } => execute_store_receipt(deps, info, receipt),
+        ExecuteMsg::StoreReceipt { receipt } => execute_store_receipt(deps, env, info, receipt),
         ExecuteMsg::EditReceipt {
             receipt,
             receipt_id,
-        } => execute_edit_receipt(deps, info, receipt_id, receipt),
-        ExecuteMsg::UpdateOwner { address } => execute_update_owner(deps, info, address),
+        } => execute_edit_receipt(deps, env, info, receipt_id, receipt),
+        ExecuteMsg::ProposeOwner {
+            address,
+            expiration,
+        } => execute_propose_owner(deps, env, info, address, expiration),
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, env, info),
+        ExecuteMsg::CancelOwnershipProposal {} => execute_cancel_ownership_proposal(deps, info),
+        ExecuteMsg::RenounceOwnership {} => execute_renounce_ownership(deps, info),
+        ExecuteMsg::GrantRole {
+            role,
+            address,
+            expiration,
+        } => execute_grant_role(deps, env, info, role, address, expiration),
+        ExecuteMsg::RevokeRole { role, address } => execute_revoke_role(deps, info, role, address),
     }
 }
 
 fn execute_store_receipt(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     receipt: Receipt,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     require(
-        can_mint_receipt(deps.storage, &info.sender.to_string())?,
-        StdError::generic_err(
-            "Only the contract owner, the assigned minter or a moderator can mint a receipt",
-        ),
+        can_mint_receipt(deps.storage, &env.block, &info.sender.to_string())?,
+        ContractError::MintReceiptUnauthorized {},
     )?;
     let receipt_id = increment_num_receipt(deps.storage)?;
     store_receipt(deps.storage, receipt_id, &receipt)?;
@@ -74,15 +92,14 @@ fn execute_store_receipt(
 
 fn execute_edit_receipt(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     receipt_id: Uint128,
     receipt: Receipt,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     require(
-        can_mint_receipt(deps.storage, &info.sender.to_string())?,
-        StdError::generic_err(
-            "Only the contract owner, the assigned minter or a moderator can edit a receipt",
-        ),
+        can_mint_receipt(deps.storage, &env.block, &info.sender.to_string())?,
+        ContractError::EditReceiptUnauthorized {},
     )?;
     read_receipt(deps.storage, receipt_id)?;
     store_receipt(deps.storage, receipt_id, &receipt)?;
@@ -95,20 +112,44 @@ fn execute_edit_receipt(
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
-        QueryMsg::Receipt { receipt_id } => to_binary(&query_receipt(deps, receipt_id)?),
-        QueryMsg::ContractInfo {} => to_binary(&query_config(deps)?),
-        QueryMsg::ContractOwner {} => to_binary(&query_contract_owner(deps)?),
+        QueryMsg::Receipt { receipt_id } => Ok(to_binary(&query_receipt(deps, receipt_id)?)?),
+        QueryMsg::ContractInfo {} => Ok(to_binary(&query_config(deps)?)?),
+        QueryMsg::ContractOwner {} => Ok(to_binary(&query_contract_owner(deps)?)?),
+        QueryMsg::PendingOwner {} => Ok(to_binary(&query_pending_owner(deps)?)?),
+        QueryMsg::Version {} => Ok(to_binary(&query_version(deps)?)?),
+        QueryMsg::HasRole { role, address } => {
+            Ok(to_binary(&query_has_role(deps, env, role, address)?)?)
+        }
+        QueryMsg::RoleMembers {
+            role,
+            start_after,
+            limit,
+        } => Ok(to_binary(&query_role_members(
+            deps,
+            env,
+            role,
+            start_after,
+            limit,
+        )?)?),
     }
 }
 
-fn query_receipt(deps: Deps, receipt_id: Uint128) -> StdResult<ReceiptResponse> {
+#[entry_point]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let previous = migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    migrate_moderators(deps.storage, &env.block)?;
+
+    Ok(migrate_response(previous, CONTRACT_VERSION))
+}
+
+fn query_receipt(deps: Deps, receipt_id: Uint128) -> Result<ReceiptResponse, ContractError> {
     let receipt = read_receipt(deps.storage, receipt_id)?;
     Ok(ReceiptResponse { receipt })
 }
 
-fn query_config(deps: Deps) -> StdResult<ContractInfoResponse> {
+fn query_config(deps: Deps) -> Result<ContractInfoResponse, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
     Ok(ContractInfoResponse { config })
@@ -145,7 +186,6 @@ mod tests {
         let unauth_info = mock_info("anyone", &[]);
         let config = Config {
             minter: owner.to_string(),
-            moderators: vec![],
         };
         store_config(deps.as_mut().storage, &config).unwrap();
         CONTRACT_OWNER
@@ -158,12 +198,7 @@ mod tests {
 
         let res_unauth =
             execute(deps.as_mut(), env.clone(), unauth_info.clone(), msg.clone()).unwrap_err();
-        assert_eq!(
-            res_unauth,
-            StdError::generic_err(
-                "Only the contract owner, the assigned minter or a moderator can mint a receipt"
-            )
-        );
+        assert_eq!(res_unauth, ContractError::MintReceiptUnauthorized {});
 
         //add address for registered moderator
         let res = execute(deps.as_mut(), env, info, msg).unwrap();
@@ -185,7 +220,6 @@ mod tests {
         let unauth_info = mock_info("anyone", &[]);
         let config = Config {
             minter: owner.to_string(),
-            moderators: vec![],
         };
 
         CONTRACT_OWNER
@@ -217,12 +251,7 @@ mod tests {
         };
 
         let res_unauth = execute(deps.as_mut(), env.clone(), unauth_info, msg.clone()).unwrap_err();
-        assert_eq!(
-            res_unauth,
-            StdError::generic_err(
-                "Only the contract owner, the assigned minter or a moderator can edit a receipt"
-            )
-        );
+        assert_eq!(res_unauth, ContractError::EditReceiptUnauthorized {});
 
         let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
         let expected = Response::default().add_attributes(vec![
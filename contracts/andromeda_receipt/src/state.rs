@@ -1,9 +1,12 @@
+use andromeda_protocol::error::ContractError;
 use andromeda_protocol::{
     ownership::is_contract_owner,
     receipt::{Config, Receipt},
+    roles::{grant_role, has_role, ROLE_MODERATOR},
 };
-use cosmwasm_std::{StdResult, Storage, Uint128};
+use cosmwasm_std::{from_slice, BlockInfo, StdResult, Storage, Uint128};
 use cw_storage_plus::{Item, Map, U128Key};
+use serde::Deserialize;
 
 pub const CONFIG: Item<Config> = Item::new("config");
 const RECEIPT: Map<U128Key, Receipt> = Map::new("receipt");
@@ -13,23 +16,51 @@ pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()>
     CONFIG.save(storage, config)
 }
 
-pub fn can_mint_receipt(storage: &dyn Storage, addr: &String) -> StdResult<bool> {
+#[derive(Deserialize)]
+/// The stored format of `Config` before moderators were moved to the `moderator` role
+struct LegacyModerators {
+    #[serde(default)]
+    moderators: Vec<String>,
+}
+
+/// Grants the `moderator` role to any moderators stored in the legacy config format
+pub fn migrate_moderators(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+) -> Result<(), ContractError> {
+    let legacy: LegacyModerators = match storage.get(b"config") {
+        Some(data) => from_slice(&data)?,
+        None => return Ok(()),
+    };
+    for moderator in legacy.moderators {
+        grant_role(storage, block, ROLE_MODERATOR, &moderator, None)?;
+    }
+    // Re-save the config to drop the legacy field
+    let config = CONFIG.load(storage)?;
+    Ok(CONFIG.save(storage, &config)?)
+}
+
+pub fn can_mint_receipt(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    addr: &String,
+) -> Result<bool, ContractError> {
     let config = CONFIG.load(storage)?;
     Ok(is_contract_owner(storage, addr.to_string())?
         || addr.eq(&config.minter)
-        || config.moderators.contains(addr))
+        || has_role(storage, block, ROLE_MODERATOR, addr)?)
 }
 
 // increase receipt ID
-pub fn increment_num_receipt(storage: &mut dyn Storage) -> StdResult<Uint128> {
+pub fn increment_num_receipt(storage: &mut dyn Storage) -> Result<Uint128, ContractError> {
     let mut receipt_count = NUM_RECEIPT.load(storage).unwrap_or_default();
     //Changed type conversion from explicit to implicit. [AKP-01] (Delete when reviewed)
     //Added checked_add function to make sure that no overflow occurs [ACP-02] (Delete when reviewed)
     let res = receipt_count.checked_add(Uint128::from(1u128));
-    //Check that no overflow, else panic. 
+    //Check that no overflow, else panic.
     let _res = match res {
         Err(error) => panic!("Problem adding: {:?}", error),
-        _ => {receipt_count = res.unwrap()}
+        _ => receipt_count = res.unwrap(),
     };
     Ok(receipt_count)
 }
@@ -47,8 +78,8 @@ pub fn read_receipt(storage: &dyn Storage, receipt_id: Uint128) -> StdResult<Rec
 
 #[cfg(test)]
 mod tests {
-    use andromeda_protocol::ownership::CONTRACT_OWNER;
-    use cosmwasm_std::testing::mock_dependencies;
+    use andromeda_protocol::{ownership::CONTRACT_OWNER, roles::grant_role};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
 
     use super::*;
 
@@ -61,25 +92,54 @@ mod tests {
 
         let config = Config {
             minter: minter.clone(),
-            moderators: vec![moderator.clone()],
         };
         let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
 
         CONTRACT_OWNER
             .save(deps.as_mut().storage, &owner.to_string())
             .unwrap();
         CONFIG.save(deps.as_mut().storage, &config).unwrap();
+        grant_role(
+            deps.as_mut().storage,
+            &env.block,
+            ROLE_MODERATOR,
+            &moderator,
+            None,
+        )
+        .unwrap();
 
-        let anyone_resp = can_mint_receipt(deps.as_ref().storage, &anyone).unwrap();
+        let anyone_resp = can_mint_receipt(deps.as_ref().storage, &env.block, &anyone).unwrap();
         assert!(!anyone_resp);
 
-        let owner_resp = can_mint_receipt(deps.as_ref().storage, &owner).unwrap();
+        let owner_resp = can_mint_receipt(deps.as_ref().storage, &env.block, &owner).unwrap();
         assert!(owner_resp);
 
-        let minter_resp = can_mint_receipt(deps.as_ref().storage, &minter).unwrap();
+        let minter_resp = can_mint_receipt(deps.as_ref().storage, &env.block, &minter).unwrap();
         assert!(minter_resp);
 
-        let moderator_resp = can_mint_receipt(deps.as_ref().storage, &moderator).unwrap();
+        let moderator_resp =
+            can_mint_receipt(deps.as_ref().storage, &env.block, &moderator).unwrap();
         assert!(moderator_resp);
     }
+
+    #[test]
+    fn test_migrate_moderators() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+
+        deps.storage.set(
+            b"config",
+            br#"{"minter":"minter","moderators":["moderator"]}"#,
+        );
+        migrate_moderators(deps.as_mut().storage, &env.block).unwrap();
+
+        assert!(has_role(&deps.storage, &env.block, ROLE_MODERATOR, "moderator").unwrap());
+        assert_eq!(
+            CONFIG.load(&deps.storage).unwrap(),
+            Config {
+                minter: String::from("minter"),
+            }
+        );
+    }
 }
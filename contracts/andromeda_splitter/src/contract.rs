@@ -1,43 +1,72 @@
-use crate::state::SPLITTER;
+use crate::state::{add_balance, BALANCES, SPLITTER};
+use andromeda_protocol::error::ContractError;
 use andromeda_protocol::{
     modules::{
         address_list::{on_address_list_reply, AddressListModule, REPLY_ADDRESS_LIST},
+        common::{Funds, Payment},
         generate_instantiate_msgs,
         hooks::{HookResponse, MessageHooks},
         Module,
     },
-    ownership::{execute_update_owner, is_contract_owner, query_contract_owner, CONTRACT_OWNER},
+    ownership::{
+        execute_accept_ownership, execute_cancel_ownership_proposal, execute_propose_owner,
+        execute_renounce_ownership, query_contract_owner, query_pending_owner, CONTRACT_OWNER,
+    },
     require,
+    roles::{
+        execute_grant_role, execute_revoke_role, is_owner_or_operator, query_has_role,
+        query_role_members,
+    },
     splitter::GetSplitterConfigResponse,
     splitter::{
-        validate_recipient_list, AddressPercent, ExecuteMsg, InstantiateMsg, QueryMsg, Splitter,
+        validate_recipient_list, AddressPercent, BalanceResponse, BalancesResponse, Cw20HookMsg,
+        ExecuteMsg, InstantiateMsg, LockStatusResponse, MigrateMsg, QueryMsg, RemainderPolicy,
+        Splitter,
     },
+    version::{migrate_response, migrate_version, query_version, set_contract_version},
 };
 use cosmwasm_std::{
-    attr, entry_point, to_binary, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env,
-    MessageInfo, Reply, Response, StdError, StdResult, SubMsg, Uint128,
+    attr, entry_point, from_binary, to_binary, Attribute, BankMsg, Binary, Coin, CosmosMsg, Deps,
+    DepsMut, Env, MessageInfo, Order, Reply, Response, StdError, StdResult, SubMsg, Uint128,
+    WasmMsg,
 };
+use cw20::{Cw20Coin, Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw721::Expiration;
+use cw_storage_plus::Bound;
 // use std::collections::HashMap;
 
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     msg.validate()?;
+    if let Some(remainder) = &msg.remainder {
+        remainder.validate_address(deps.api)?;
+    }
 
     let splitter = Splitter {
         recipients: msg.recipients,
         locked: false,
+        lock_expiration: None,
         address_list: msg.address_list.clone(),
+        pull_payments: msg.pull_payments.unwrap_or(false),
+        remainder: msg.remainder.clone().unwrap_or_default(),
     };
 
     let inst_msgs = generate_instantiate_msgs(&deps, info.clone(), env, vec![msg.address_list])?;
 
     SPLITTER.save(deps.storage, &splitter)?;
     CONTRACT_OWNER.save(deps.storage, &info.sender.to_string())?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::new()
         .add_attributes(vec![
@@ -49,108 +78,355 @@ pub fn instantiate(
 }
 
 #[entry_point]
-pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
     let splitter = SPLITTER.load(deps.storage)?;
 
     // [GLOBAL-02] Changing is_some() + .unwrap() to if let Some()
     if let Some(addr_list) = splitter.address_list {
-        addr_list.on_execute(&deps, info.clone(), env.clone())?;
+        // CW20 sends are authorized by the sender of the tokens rather than the token contract
+        let sender_info = match &msg {
+            ExecuteMsg::Receive(receive_msg) => MessageInfo {
+                sender: deps.api.addr_validate(&receive_msg.sender)?,
+                funds: vec![],
+            },
+            _ => info.clone(),
+        };
+        addr_list.on_execute(&deps, sender_info, env.clone())?;
     }
 
     match msg {
         ExecuteMsg::UpdateRecipients { recipients } => {
-            execute_update_recipients(deps, info, recipients)
+            execute_update_recipients(deps, env, info, recipients)
+        }
+        ExecuteMsg::UpdateRemainder { remainder } => {
+            execute_update_remainder(deps, env, info, remainder)
+        }
+        ExecuteMsg::UpdateLock { lock, expiration } => {
+            execute_update_lock(deps, env, info, lock, expiration)
         }
-        ExecuteMsg::UpdateLock { lock } => execute_update_lock(deps, info, lock),
         ExecuteMsg::UpdateAddressList { address_list } => {
             execute_update_address_list(deps, info, env, address_list)
         }
+        ExecuteMsg::UpdatePullPayments { pull_payments } => {
+            execute_update_pull_payments(deps, env, info, pull_payments)
+        }
         ExecuteMsg::Send {} => execute_send(deps, info),
-        ExecuteMsg::UpdateOwner { address } => execute_update_owner(deps, info, address),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, info, msg),
+        ExecuteMsg::Withdraw { denoms } => execute_withdraw(deps, info, denoms),
+        ExecuteMsg::ProposeOwner {
+            address,
+            expiration,
+        } => execute_propose_owner(deps, env, info, address, expiration),
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, env, info),
+        ExecuteMsg::CancelOwnershipProposal {} => execute_cancel_ownership_proposal(deps, info),
+        ExecuteMsg::RenounceOwnership {} => execute_renounce_ownership(deps, info),
+        ExecuteMsg::GrantRole {
+            role,
+            address,
+            expiration,
+        } => execute_grant_role(deps, env, info, role, address, expiration),
+        ExecuteMsg::RevokeRole { role, address } => execute_revoke_role(deps, info, role, address),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
     if msg.result.is_err() {
-        return Err(StdError::generic_err(msg.result.unwrap_err()));
+        return Err(StdError::generic_err(msg.result.unwrap_err()).into());
     }
 
     match msg.id {
         REPLY_ADDRESS_LIST => on_address_list_reply(deps, msg),
-        _ => Err(StdError::generic_err("reply id is invalid")),
+        _ => Err(ContractError::InvalidReplyId {}),
     }
 }
 
-fn execute_send(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+fn execute_send(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
     let sent_funds: Vec<Coin> = info.funds.clone();
-    require(sent_funds.len() > 0, StdError::generic_err("No coin sent"))?;
+    require(sent_funds.len() > 0, ContractError::NoFunds {})?;
+    // [ACK-04] Limit number of coins sent to 5.
+    require(info.funds.len() < 5, ContractError::ExceedsMaxCoins {})?;
+
+    let funds: Vec<Funds> = sent_funds.into_iter().map(Funds::Native).collect();
+    let (submsg, remainder_attrs) = split_funds(deps, info.sender.as_str(), funds)?;
+
+    Ok(Response::new()
+        .add_submessages(submsg)
+        .add_attributes(vec![
+            attr("action", "send"),
+            attr("sender", info.sender.to_string()),
+        ])
+        .add_attributes(remainder_attrs))
+}
 
+fn execute_receive(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&msg.msg)? {
+        Cw20HookMsg::Send {} => execute_send_cw20(deps, info, msg.sender, msg.amount),
+    }
+}
+
+/// Divides CW20 tokens sent by `sender` amongst the recipients list. `info.sender` is the CW20 token contract.
+fn execute_send_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    sender: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    require(!amount.is_zero(), ContractError::NoFunds {})?;
+
+    let funds = vec![Funds::Cw20(Cw20Coin {
+        address: info.sender.to_string(),
+        amount,
+    })];
+    let (submsg, remainder_attrs) = split_funds(deps, &sender, funds)?;
+
+    Ok(Response::new()
+        .add_submessages(submsg)
+        .add_attributes(vec![
+            attr("action", "send"),
+            attr("sender", sender),
+            attr("token", info.sender.to_string()),
+        ])
+        .add_attributes(remainder_attrs))
+}
+
+/// Divides the given funds amongst the recipients list, returning the messages required to pay each recipient
+/// along with attributes describing the remainder. When pull payments are enabled each share is credited to the
+/// recipient's balance instead. Any funds not allocated to a recipient are handled by the splitter's remainder policy.
+fn split_funds(
+    deps: DepsMut,
+    sender: &str,
+    funds: Vec<Funds>,
+) -> Result<(Vec<SubMsg>, Vec<Attribute>), ContractError> {
     let splitter = SPLITTER.load(deps.storage)?;
     let mut submsg: Vec<SubMsg> = Vec::new();
 
-    let mut remainder_funds = info.funds.clone();
+    let mut remainder_funds = funds.clone();
+    let mut shares: Vec<Vec<Funds>> = Vec::new();
     // Looking at this nested for loop, we could find a way to reduce time/memory complexity to avoid DoS.
     // Would like to understand more about why we loop through funds and what it exactly stored in it.
     // From there we could look into HashMaps, or other methods to break the nested loops and avoid Denial of Service.
-    // [ACK-04] Limit number of coins sent to 5.
-    require(
-        info.funds.len() < 5,
-        StdError::generic_err("Exceeds max amount of coins allowed."),
-    )?;
     for recipient_addr in &splitter.recipients {
         let recipient_percent = recipient_addr.percent;
-        let mut vec_coin: Vec<Coin> = Vec::new();
-        for (i, coin) in sent_funds.iter().enumerate() {
-            let mut recip_coin: Coin = coin.clone();
-            recip_coin.amount = coin.amount.multiply_ratio(recipient_percent, 100u128);
-            remainder_funds[i].amount -= recip_coin.amount;
-            vec_coin.push(recip_coin);
+        let mut vec_funds: Vec<Funds> = Vec::new();
+        for (i, fund) in funds.iter().enumerate() {
+            let amount = fund.amount().multiply_ratio(recipient_percent, 100u128);
+            remainder_funds[i] =
+                remainder_funds[i].with_amount(remainder_funds[i].amount() - amount);
+            vec_funds.push(fund.with_amount(amount));
         }
-        submsg.push(SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
-            to_address: recipient_addr.addr.clone(),
-            amount: vec_coin,
-        })));
+        shares.push(vec_funds);
     }
-    remainder_funds = remainder_funds
-        .into_iter()
-        .filter(|x| x.amount > Uint128::from(0u128))
+
+    let remainder: Vec<String> = remainder_funds
+        .iter()
+        .filter(|fund| !fund.amount().is_zero())
+        .map(|fund| fund.to_string())
         .collect();
-    // Who is the sender of this function?
-    // Why does the remaining funds go the the sender of the executor of the splitter?
-    // Is it considered tax(fee) or mistake?
-    // Discussion around caller of splitter function in andromeda_splitter smart contract.
-    // From tests, it looks like owner of smart contract (Andromeda) will recieve the rest of funds.
-    // If so, should be documented
-    if remainder_funds.len() > 0 {
-        submsg.push(SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
-            to_address: info.sender.to_string(),
-            amount: remainder_funds,
-        })));
+    let attributes = vec![
+        attr("remainder_policy", splitter.remainder.to_string()),
+        attr(
+            "remainder",
+            if remainder.is_empty() {
+                String::from("none")
+            } else {
+                remainder.join(",")
+            },
+        ),
+    ];
+
+    // Dust is added to the recipient's share so that they receive a single payment
+    let dust_recipient = match splitter.remainder {
+        RemainderPolicy::DustToFirst => Some(0),
+        RemainderPolicy::DustToLargest => Some(largest_recipient(&splitter.recipients)),
+        _ => None,
+    };
+    if let Some(i) = dust_recipient {
+        for (share, dust) in shares[i].iter_mut().zip(&remainder_funds) {
+            *share = share.with_amount(share.amount() + dust.amount());
+        }
     }
 
-    Ok(Response::new().add_submessages(submsg).add_attributes(vec![
-        attr("action", "send"),
-        attr("sender", info.sender.to_string()),
-    ]))
+    for (recipient_addr, vec_funds) in splitter.recipients.iter().zip(shares) {
+        if splitter.pull_payments && !recipient_addr.is_contract() {
+            add_balance(deps.storage, &recipient_addr.addr, &vec_funds)?;
+        } else {
+            submsg.append(&mut recipient_payment_msgs(recipient_addr, vec_funds)?);
+        }
+    }
+
+    match &splitter.remainder {
+        RemainderPolicy::RefundSender => {
+            submsg.append(&mut payment_msgs(sender, None, None, remainder_funds)?);
+        }
+        RemainderPolicy::Address { address } => {
+            submsg.append(&mut payment_msgs(address, None, None, remainder_funds)?);
+        }
+        RemainderPolicy::KeepForOwner => match CONTRACT_OWNER.may_load(deps.storage)? {
+            Some(owner) => add_balance(deps.storage, &owner, &remainder_funds)?,
+            // Once ownership is renounced there is nobody to keep the remainder for
+            None => submsg.append(&mut payment_msgs(sender, None, None, remainder_funds)?),
+        },
+        RemainderPolicy::DustToFirst | RemainderPolicy::DustToLargest => {}
+    }
+
+    Ok((submsg, attributes))
+}
+
+/// The index of the recipient with the largest percentage, the first such recipient if there are several.
+fn largest_recipient(recipients: &[AddressPercent]) -> usize {
+    recipients
+        .iter()
+        .enumerate()
+        .fold(0, |largest, (i, recipient)| {
+            if recipient.percent > recipients[largest].percent {
+                i
+            } else {
+                largest
+            }
+        })
+}
+
+/// Generates the messages paying a recipient its share, using the recipient's messages if it is a contract.
+/// Errors if the recipient is a contract without a message for the kind of funds in its share.
+fn recipient_payment_msgs(
+    recipient: &AddressPercent,
+    funds: Vec<Funds>,
+) -> Result<Vec<SubMsg>, ContractError> {
+    if recipient.is_contract() {
+        for fund in funds.iter().filter(|fund| !fund.amount().is_zero()) {
+            let has_msg = match fund {
+                Funds::Native(_) => recipient.msg.is_some(),
+                Funds::Cw20(_) => recipient.cw20_msg.is_some(),
+            };
+            require(
+                has_msg,
+                ContractError::MissingRecipientMsg {
+                    addr: recipient.addr.clone(),
+                },
+            )?;
+        }
+    }
+
+    payment_msgs(
+        &recipient.addr,
+        recipient.msg.clone(),
+        recipient.cw20_msg.clone(),
+        funds,
+    )
+}
+
+/// Generates the messages paying the given funds to an address, skipping any zero amounts.
+/// Native funds are paid with a single `BankMsg`, CW20 funds are paid with a `Cw20ExecuteMsg::Transfer` per token.
+/// If `msg` is provided the address is executed with the native funds attached instead, and if `cw20_msg` is provided CW20 funds are sent to it with `Cw20ExecuteMsg::Send`.
+fn payment_msgs(
+    to: &str,
+    msg: Option<Binary>,
+    cw20_msg: Option<Binary>,
+    funds: Vec<Funds>,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let mut coins: Vec<Coin> = Vec::new();
+    let mut msgs: Vec<SubMsg> = Vec::new();
+    for fund in funds.into_iter().filter(|fund| !fund.amount().is_zero()) {
+        match fund {
+            Funds::Native(coin) => coins.push(coin),
+            Funds::Cw20(coin) => {
+                let cw20_msg = match &cw20_msg {
+                    Some(msg) => Cw20ExecuteMsg::Send {
+                        contract: to.to_string(),
+                        amount: coin.amount,
+                        msg: msg.clone(),
+                    },
+                    None => Cw20ExecuteMsg::Transfer {
+                        recipient: to.to_string(),
+                        amount: coin.amount,
+                    },
+                };
+                let payment = Payment::Cw20 {
+                    contract_addr: coin.address,
+                    msg: cw20_msg,
+                };
+                msgs.push(SubMsg::new(payment.to_msg()?));
+            }
+        }
+    }
+    if !coins.is_empty() {
+        let native_msg = match msg {
+            Some(msg) => CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: to.to_string(),
+                msg,
+                funds: coins,
+            }),
+            None => CosmosMsg::Bank(BankMsg::Send {
+                to_address: to.to_string(),
+                amount: coins,
+            }),
+        };
+        msgs.insert(0, SubMsg::new(native_msg));
+    }
+
+    Ok(msgs)
+}
+
+fn execute_withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    denoms: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let address = info.sender.to_string();
+    let balance = BALANCES
+        .may_load(deps.storage, address.clone())?
+        .unwrap_or_default();
+    let (withdrawn, remaining): (Vec<Funds>, Vec<Funds>) =
+        balance.into_iter().partition(|fund| match &denoms {
+            None => true,
+            Some(denoms) => denoms.contains(&fund.denom()),
+        });
+    require(!withdrawn.is_empty(), ContractError::NoBalance {})?;
+
+    if remaining.is_empty() {
+        BALANCES.remove(deps.storage, address.clone());
+    } else {
+        BALANCES.save(deps.storage, address.clone(), &remaining)?;
+    }
+
+    let amount: Vec<String> = withdrawn.iter().map(|fund| fund.to_string()).collect();
+    Ok(Response::new()
+        .add_submessages(payment_msgs(&address, None, None, withdrawn)?)
+        .add_attributes(vec![
+            attr("action", "withdraw"),
+            attr("recipient", address),
+            attr("amount", amount.join(",")),
+        ]))
 }
 
 fn execute_update_recipients(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     recipients: Vec<AddressPercent>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     require(
-        is_contract_owner(deps.storage, info.sender.to_string())?,
-        StdError::generic_err("May only be used by the contract owner"),
+        is_owner_or_operator(deps.storage, &env.block, info.sender.as_str())?,
+        ContractError::Unauthorized {},
     )?;
 
-    validate_recipient_list(recipients.clone())?;
-
     let mut splitter = SPLITTER.load(deps.storage)?;
-    if splitter.locked == true {
-        StdError::generic_err("The splitter is currently locked");
-    }
+    validate_recipient_list(recipients.clone(), &splitter.remainder)?;
+
+    require(
+        !splitter.is_locked(&env.block),
+        ContractError::SplitterLocked {},
+    )?;
 
     splitter.recipients = recipients.clone();
     SPLITTER.save(deps.storage, &splitter)?;
@@ -158,18 +434,95 @@ fn execute_update_recipients(
     Ok(Response::default().add_attributes(vec![attr("action", "update_recipients")]))
 }
 
-fn execute_update_lock(deps: DepsMut, info: MessageInfo, lock: bool) -> StdResult<Response> {
+fn execute_update_remainder(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    remainder: RemainderPolicy,
+) -> Result<Response, ContractError> {
+    require(
+        is_owner_or_operator(deps.storage, &env.block, info.sender.as_str())?,
+        ContractError::Unauthorized {},
+    )?;
+
+    let mut splitter = SPLITTER.load(deps.storage)?;
+    require(
+        !splitter.is_locked(&env.block),
+        ContractError::SplitterLocked {},
+    )?;
+    validate_recipient_list(splitter.recipients.clone(), &remainder)?;
+    remainder.validate_address(deps.api)?;
+
+    splitter.remainder = remainder;
+    SPLITTER.save(deps.storage, &splitter)?;
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "update_remainder"),
+        attr("remainder_policy", splitter.remainder.to_string()),
+    ]))
+}
+
+fn execute_update_lock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lock: bool,
+    expiration: Option<Expiration>,
+) -> Result<Response, ContractError> {
     require(
-        is_contract_owner(deps.storage, info.sender.to_string())?,
-        StdError::generic_err("May only be used by the contract owner"),
+        is_owner_or_operator(deps.storage, &env.block, info.sender.as_str())?,
+        ContractError::Unauthorized {},
     )?;
     let mut splitter = SPLITTER.load(deps.storage)?;
+    // A lock with an expiration is a commitment to payees and may not be lifted early
+    require(
+        splitter.lock_expiration.is_none() || !splitter.is_locked(&env.block),
+        ContractError::SplitterLocked {},
+    )?;
+    if let Some(expiration) = expiration {
+        require(
+            !expiration.is_expired(&env.block),
+            ContractError::ExpirationInPast {},
+        )?;
+    }
+
     splitter.locked = lock;
+    splitter.lock_expiration = if lock { expiration } else { None };
     SPLITTER.save(deps.storage, &splitter)?;
 
     Ok(Response::default().add_attributes(vec![
         attr("action", "update_lock"),
         attr("locked", lock.to_string()),
+        attr(
+            "expiration",
+            splitter
+                .lock_expiration
+                .map_or(String::from("none"), |e| e.to_string()),
+        ),
+    ]))
+}
+
+fn execute_update_pull_payments(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pull_payments: bool,
+) -> Result<Response, ContractError> {
+    require(
+        is_owner_or_operator(deps.storage, &env.block, info.sender.as_str())?,
+        ContractError::Unauthorized {},
+    )?;
+    let mut splitter = SPLITTER.load(deps.storage)?;
+    require(
+        !splitter.is_locked(&env.block),
+        ContractError::SplitterLocked {},
+    )?;
+    splitter.pull_payments = pull_payments;
+    SPLITTER.save(deps.storage, &splitter)?;
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "update_pull_payments"),
+        attr("pull_payments", pull_payments.to_string()),
     ]))
 }
 
@@ -178,16 +531,17 @@ fn execute_update_address_list(
     info: MessageInfo,
     env: Env,
     address_list: Option<AddressListModule>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     require(
-        is_contract_owner(deps.storage, info.sender.to_string())?,
-        StdError::generic_err("May only be used by the contract owner"),
+        is_owner_or_operator(deps.storage, &env.block, info.sender.as_str())?,
+        ContractError::Unauthorized {},
     )?;
 
     let mut splitter = SPLITTER.load(deps.storage)?;
-    if splitter.locked == true {
-        StdError::generic_err("The splitter is currently locked");
-    }
+    require(
+        !splitter.is_locked(&env.block),
+        ContractError::SplitterLocked {},
+    )?;
 
     let mod_resp = match address_list.clone() {
         None => HookResponse::default(),
@@ -204,14 +558,42 @@ fn execute_update_address_list(
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
-        QueryMsg::GetSplitterConfig {} => to_binary(&query_splitter(deps)?),
-        QueryMsg::ContractOwner {} => to_binary(&query_contract_owner(deps)?),
+        QueryMsg::GetSplitterConfig {} => Ok(to_binary(&query_splitter(deps)?)?),
+        QueryMsg::ContractOwner {} => Ok(to_binary(&query_contract_owner(deps)?)?),
+        QueryMsg::PendingOwner {} => Ok(to_binary(&query_pending_owner(deps)?)?),
+        QueryMsg::LockStatus {} => Ok(to_binary(&query_lock_status(deps, env)?)?),
+        QueryMsg::Version {} => Ok(to_binary(&query_version(deps)?)?),
+        QueryMsg::Balance { address } => Ok(to_binary(&query_balance(deps, address)?)?),
+        QueryMsg::Balances { start_after, limit } => {
+            Ok(to_binary(&query_balances(deps, start_after, limit)?)?)
+        }
+        QueryMsg::HasRole { role, address } => {
+            Ok(to_binary(&query_has_role(deps, env, role, address)?)?)
+        }
+        QueryMsg::RoleMembers {
+            role,
+            start_after,
+            limit,
+        } => Ok(to_binary(&query_role_members(
+            deps,
+            env,
+            role,
+            start_after,
+            limit,
+        )?)?),
     }
 }
 
-fn query_splitter(deps: Deps) -> StdResult<GetSplitterConfigResponse> {
+#[entry_point]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let previous = migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(migrate_response(previous, CONTRACT_VERSION))
+}
+
+fn query_splitter(deps: Deps) -> Result<GetSplitterConfigResponse, ContractError> {
     let splitter = SPLITTER.load(deps.storage)?;
     let address_list_contract = match splitter.clone().address_list {
         Some(addr_list) => addr_list.get_contract_address(deps.storage),
@@ -223,10 +605,56 @@ fn query_splitter(deps: Deps) -> StdResult<GetSplitterConfigResponse> {
         address_list_contract,
     })
 }
+
+fn query_lock_status(deps: Deps, env: Env) -> Result<LockStatusResponse, ContractError> {
+    let splitter = SPLITTER.load(deps.storage)?;
+    let locked = splitter.is_locked(&env.block);
+
+    Ok(LockStatusResponse {
+        locked,
+        expiration: if locked {
+            splitter.lock_expiration
+        } else {
+            None
+        },
+    })
+}
+
+fn query_balance(deps: Deps, address: String) -> Result<BalanceResponse, ContractError> {
+    let balance = BALANCES
+        .may_load(deps.storage, address.clone())?
+        .unwrap_or_default();
+
+    Ok(BalanceResponse { address, balance })
+}
+
+fn query_balances(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<BalancesResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let balances = BALANCES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|r| {
+            let (k, balance) = r?;
+            Ok(BalanceResponse {
+                address: String::from_utf8(k)?,
+                balance,
+            })
+        })
+        .collect::<StdResult<Vec<BalanceResponse>>>()?;
+
+    Ok(BalancesResponse { balances })
+}
 #[cfg(test)]
 mod tests {
     use super::*;
     use andromeda_protocol::modules::address_list::AddressListModule;
+    use andromeda_protocol::roles::{grant_role, ROLE_OPERATOR};
+    use andromeda_protocol::timelock::ExecuteMsg as TimelockExecuteMsg;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
     use cosmwasm_std::{from_binary, Coin, Uint128};
 
@@ -237,13 +665,26 @@ mod tests {
         let info = mock_info("creator", &[]);
         let msg = InstantiateMsg {
             address_list: None,
+            pull_payments: None,
+            remainder: None,
             recipients: vec![AddressPercent {
                 addr: String::from("Some Address"),
                 percent: Uint128::from(100_u128),
+                msg: None,
+                cw20_msg: None,
             }],
         };
-        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
         assert_eq!(0, res.messages.len());
+
+        let msg = InstantiateMsg {
+            remainder: Some(RemainderPolicy::Address {
+                address: String::from("xy"),
+            }),
+            ..msg
+        };
+        let res = instantiate(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidRemainderAddress {});
     }
 
     #[test]
@@ -255,7 +696,10 @@ mod tests {
         let info = mock_info(owner.clone(), &[]);
 
         let lock = true;
-        let msg = ExecuteMsg::UpdateLock { lock: lock };
+        let msg = ExecuteMsg::UpdateLock {
+            lock: lock,
+            expiration: None,
+        };
 
         CONTRACT_OWNER
             .save(deps.as_mut().storage, &String::from("incorrect_owner"))
@@ -273,16 +717,20 @@ mod tests {
         let splitter = Splitter {
             recipients: vec![],
             locked: false,
+            lock_expiration: None,
             address_list: None,
+            pull_payments: false,
+            remainder: RemainderPolicy::RefundSender,
         };
 
         SPLITTER.save(deps.as_mut().storage, &splitter).unwrap();
 
-        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
         assert_eq!(
             Response::default().add_attributes(vec![
                 attr("action", "update_lock"),
-                attr("locked", lock.to_string())
+                attr("locked", lock.to_string()),
+                attr("expiration", "none"),
             ]),
             res
         );
@@ -290,6 +738,32 @@ mod tests {
         //check result
         let splitter = SPLITTER.load(deps.as_ref().storage).unwrap();
         assert_eq!(splitter.locked, lock);
+
+        // Operators may also update the lock
+        let operator_info = mock_info("operator", &[]);
+        let msg = ExecuteMsg::UpdateLock {
+            lock: false,
+            expiration: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            operator_info.clone(),
+            msg.clone(),
+        );
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+        grant_role(
+            deps.as_mut().storage,
+            &env.block,
+            ROLE_OPERATOR,
+            "operator",
+            None,
+        )
+        .unwrap();
+        execute(deps.as_mut(), env, operator_info, msg).unwrap();
+        let splitter = SPLITTER.load(deps.as_ref().storage).unwrap();
+        assert!(!splitter.locked);
     }
 
     #[test]
@@ -305,7 +779,10 @@ mod tests {
         let splitter = Splitter {
             recipients: vec![],
             locked: false,
+            lock_expiration: None,
             address_list: None,
+            pull_payments: false,
+            remainder: RemainderPolicy::RefundSender,
         };
         SPLITTER.save(deps.as_mut().storage, &splitter).unwrap();
 
@@ -322,10 +799,7 @@ mod tests {
         let unauth_info = mock_info("anyone", &[]);
         let err_res =
             execute(deps.as_mut(), env.clone(), unauth_info.clone(), msg.clone()).unwrap_err();
-        assert_eq!(
-            err_res,
-            StdError::generic_err("May only be used by the contract owner")
-        );
+        assert_eq!(err_res, ContractError::Unauthorized {});
 
         let info = mock_info(owner.clone(), &[]);
         let resp = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
@@ -357,10 +831,14 @@ mod tests {
             AddressPercent {
                 addr: "address1".to_string(),
                 percent: Uint128::from(40_u128),
+                msg: None,
+                cw20_msg: None,
             },
             AddressPercent {
                 addr: "address1".to_string(),
                 percent: Uint128::from(60_u128),
+                msg: None,
+                cw20_msg: None,
             },
         ];
         let msg = ExecuteMsg::UpdateRecipients {
@@ -384,7 +862,10 @@ mod tests {
         let splitter = Splitter {
             recipients: vec![],
             locked: false,
+            lock_expiration: None,
             address_list: None,
+            pull_payments: false,
+            remainder: RemainderPolicy::RefundSender,
         };
 
         SPLITTER.save(deps.as_mut().storage, &splitter).unwrap();
@@ -422,10 +903,14 @@ mod tests {
             AddressPercent {
                 addr: recip_address1.clone(),
                 percent: Uint128::from(recip_percent1),
+                msg: None,
+                cw20_msg: None,
             },
             AddressPercent {
                 addr: recip_address2.clone(),
                 percent: Uint128::from(recip_percent2),
+                msg: None,
+                cw20_msg: None,
             },
         ];
         let msg = ExecuteMsg::Send {};
@@ -447,7 +932,10 @@ mod tests {
         let splitter = Splitter {
             recipients: recipient,
             locked: false,
+            lock_expiration: None,
             address_list: None,
+            pull_payments: false,
+            remainder: RemainderPolicy::RefundSender,
         };
 
         SPLITTER.save(deps.as_mut().storage, &splitter).unwrap();
@@ -472,7 +960,12 @@ mod tests {
                     }),
                 ),
             ])
-            .add_attributes(vec![attr("action", "send"), attr("sender", "creator")]);
+            .add_attributes(vec![
+                attr("action", "send"),
+                attr("sender", "creator"),
+                attr("remainder_policy", "refund_sender"),
+                attr("remainder", "7000uluna"),
+            ]);
 
         assert_eq!(res, expected_res);
     }
@@ -484,12 +977,15 @@ mod tests {
         let splitter = Splitter {
             recipients: vec![],
             locked: false,
+            lock_expiration: None,
             address_list: Some(AddressListModule {
                 address: Some(String::from("somecontractaddress")),
                 code_id: None,
                 moderators: None,
                 inclusive: false,
             }),
+            pull_payments: false,
+            remainder: RemainderPolicy::RefundSender,
         };
 
         SPLITTER
@@ -537,10 +1033,14 @@ mod tests {
             AddressPercent {
                 addr: recip_address1,
                 percent: Uint128::from(recip_percent1),
+                msg: None,
+                cw20_msg: None,
             },
             AddressPercent {
                 addr: recip_address2,
                 percent: Uint128::from(recip_percent2),
+                msg: None,
+                cw20_msg: None,
             },
         ];
         let msg = ExecuteMsg::Send {};
@@ -562,15 +1062,551 @@ mod tests {
         let splitter = Splitter {
             recipients: recipient,
             locked: false,
+            lock_expiration: None,
             address_list: None,
+            pull_payments: false,
+            remainder: RemainderPolicy::RefundSender,
         };
 
         SPLITTER.save(deps.as_mut().storage, &splitter).unwrap();
 
         let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
 
-        let expected_res = StdError::generic_err("Exceeds max amount of coins allowed.");
+        let expected_res = ContractError::ExceedsMaxCoins {};
 
         assert_eq!(res, expected_res);
     }
+
+    #[test]
+    fn test_execute_send_pull_payments() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let owner = "creator";
+
+        CONTRACT_OWNER
+            .save(deps.as_mut().storage, &owner.to_string())
+            .unwrap();
+        let splitter = Splitter {
+            recipients: vec![
+                AddressPercent {
+                    addr: String::from("address1"),
+                    percent: Uint128::from(40_u128),
+                    msg: None,
+                    cw20_msg: None,
+                },
+                AddressPercent {
+                    addr: String::from("address2"),
+                    percent: Uint128::from(60_u128),
+                    msg: None,
+                    cw20_msg: None,
+                },
+            ],
+            locked: false,
+            lock_expiration: None,
+            address_list: None,
+            pull_payments: true,
+            remainder: RemainderPolicy::RefundSender,
+        };
+        SPLITTER.save(deps.as_mut().storage, &splitter).unwrap();
+
+        let info = mock_info(owner, &[Coin::new(1000, "uluna"), Coin::new(500, "uusd")]);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Send {},
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            Response::new().add_attributes(vec![
+                attr("action", "send"),
+                attr("sender", owner),
+                attr("remainder_policy", "refund_sender"),
+                attr("remainder", "none"),
+            ])
+        );
+        // Balances accumulate across sends
+        execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Send {}).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Balance {
+                address: String::from("address1"),
+            },
+        )
+        .unwrap();
+        let balance: BalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            balance.balance,
+            vec![
+                Funds::Native(Coin::new(800, "uluna")),
+                Funds::Native(Coin::new(400, "uusd"))
+            ]
+        );
+
+        let msg = ExecuteMsg::Withdraw {
+            denoms: Some(vec![String::from("uluna")]),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("address1", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            Response::new()
+                .add_message(BankMsg::Send {
+                    to_address: String::from("address1"),
+                    amount: vec![Coin::new(800, "uluna")],
+                })
+                .add_attributes(vec![
+                    attr("action", "withdraw"),
+                    attr("recipient", "address1"),
+                    attr("amount", "800uluna"),
+                ])
+        );
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("address1", &[]), msg).unwrap_err();
+        assert_eq!(res, ContractError::NoBalance {});
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("address2", &[]),
+            ExecuteMsg::Withdraw { denoms: None },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: String::from("address2"),
+                amount: vec![Coin::new(1200, "uluna"), Coin::new(600, "uusd")],
+            })]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::Balances {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let balances: BalancesResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            balances.balances,
+            vec![BalanceResponse {
+                address: String::from("address1"),
+                balance: vec![Funds::Native(Coin::new(400, "uusd"))],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_execute_receive_cw20() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+
+        let mut splitter = Splitter {
+            recipients: vec![
+                AddressPercent {
+                    addr: String::from("address1"),
+                    percent: Uint128::from(10_u128),
+                    msg: None,
+                    cw20_msg: None,
+                },
+                AddressPercent {
+                    addr: String::from("address2"),
+                    percent: Uint128::from(20_u128),
+                    msg: None,
+                    cw20_msg: None,
+                },
+            ],
+            locked: false,
+            lock_expiration: None,
+            address_list: None,
+            pull_payments: false,
+            remainder: RemainderPolicy::RefundSender,
+        };
+        SPLITTER.save(deps.as_mut().storage, &splitter).unwrap();
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("sender"),
+            amount: Uint128::from(10000_u128),
+            msg: to_binary(&Cw20HookMsg::Send {}).unwrap(),
+        });
+        let token_info = mock_info("cw20_token", &[]);
+        let transfer = |recipient: &str, amount: u128| {
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("cw20_token"),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount: Uint128::from(amount),
+                })
+                .unwrap(),
+                funds: vec![],
+            }))
+        };
+
+        let res = execute(deps.as_mut(), env.clone(), token_info.clone(), msg.clone()).unwrap();
+        let expected_res = Response::new()
+            .add_submessages(vec![
+                transfer("address1", 1000),
+                transfer("address2", 2000),
+                // refunds remainder to the sender of the tokens
+                transfer("sender", 7000),
+            ])
+            .add_attributes(vec![
+                attr("action", "send"),
+                attr("sender", "sender"),
+                attr("token", "cw20_token"),
+                attr("remainder_policy", "refund_sender"),
+                attr("remainder", "7000cw20_token"),
+            ]);
+        assert_eq!(res, expected_res);
+
+        // With pull payments the CW20 shares are credited and withdrawn by denom
+        splitter.pull_payments = true;
+        SPLITTER.save(deps.as_mut().storage, &splitter).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), token_info, msg).unwrap();
+        assert_eq!(res.messages, vec![transfer("sender", 7000)]);
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("address2", &[]),
+            ExecuteMsg::Withdraw {
+                denoms: Some(vec![String::from("cw20_token")]),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![transfer("address2", 2000)]);
+    }
+
+    #[test]
+    fn test_execute_send_remainder_policy() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let owner = "creator";
+
+        CONTRACT_OWNER
+            .save(deps.as_mut().storage, &owner.to_string())
+            .unwrap();
+        let mut splitter = Splitter {
+            recipients: vec![
+                AddressPercent {
+                    addr: String::from("address1"),
+                    percent: Uint128::from(30_u128),
+                    msg: None,
+                    cw20_msg: None,
+                },
+                AddressPercent {
+                    addr: String::from("address2"),
+                    percent: Uint128::from(30_u128),
+                    msg: None,
+                    cw20_msg: None,
+                },
+            ],
+            locked: false,
+            lock_expiration: None,
+            address_list: None,
+            pull_payments: false,
+            remainder: RemainderPolicy::Address {
+                address: String::from("treasury"),
+            },
+        };
+        SPLITTER.save(deps.as_mut().storage, &splitter).unwrap();
+
+        let info = mock_info("sender", &[Coin::new(101, "uluna")]);
+        let send = |to: &str, amount: u128| {
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: to.to_string(),
+                amount: vec![Coin::new(amount, "uluna")],
+            }))
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Send {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                send("address1", 30),
+                send("address2", 30),
+                send("treasury", 41)
+            ]
+        );
+        assert_eq!(
+            res.attributes[2..],
+            [
+                attr("remainder_policy", "address:treasury"),
+                attr("remainder", "41uluna"),
+            ]
+        );
+
+        // The remainder may be kept for the owner to withdraw
+        splitter.remainder = RemainderPolicy::KeepForOwner;
+        SPLITTER.save(deps.as_mut().storage, &splitter).unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Send {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![send("address1", 30), send("address2", 30)]
+        );
+        assert_eq!(
+            BALANCES.load(&deps.storage, owner.to_string()).unwrap(),
+            vec![Funds::Native(Coin::new(41, "uluna"))]
+        );
+
+        let msg = ExecuteMsg::UpdateRemainder {
+            remainder: RemainderPolicy::Address {
+                address: String::from("xy"),
+            },
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidRemainderAddress {});
+
+        // Dust policies require the full amount to be allocated
+        let msg = ExecuteMsg::UpdateRemainder {
+            remainder: RemainderPolicy::DustToLargest,
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(owner, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::IncompleteSplitForDustPolicy {});
+
+        splitter.recipients[1].percent = Uint128::from(70_u128);
+        SPLITTER.save(deps.as_mut().storage, &splitter).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap();
+        assert_eq!(
+            res,
+            Response::default().add_attributes(vec![
+                attr("action", "update_remainder"),
+                attr("remainder_policy", "dust_to_largest"),
+            ])
+        );
+
+        // 101 * 0.3 = 30.3 and 101 * 0.7 = 70.7, the dust goes to the largest recipient
+        let res = execute(deps.as_mut(), env, info, ExecuteMsg::Send {}).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![send("address1", 30), send("address2", 71)]
+        );
+        assert_eq!(res.attributes[3], attr("remainder", "1uluna"));
+    }
+
+    #[test]
+    fn test_execute_send_recipient_msg() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+
+        let hold_funds = to_binary(&TimelockExecuteMsg::HoldFunds {
+            expiration: None,
+            recipient: Some(String::from("beneficiary")),
+        })
+        .unwrap();
+        let splitter = Splitter {
+            recipients: vec![
+                AddressPercent {
+                    addr: String::from("timelock_contract"),
+                    percent: Uint128::from(50_u128),
+                    msg: Some(hold_funds.clone()),
+                    cw20_msg: None,
+                },
+                AddressPercent {
+                    addr: String::from("address1"),
+                    percent: Uint128::from(50_u128),
+                    msg: None,
+                    cw20_msg: None,
+                },
+            ],
+            locked: false,
+            lock_expiration: None,
+            address_list: None,
+            pull_payments: true,
+            remainder: RemainderPolicy::RefundSender,
+        };
+        SPLITTER.save(deps.as_mut().storage, &splitter).unwrap();
+
+        // Contract recipients are paid directly even with pull payments enabled
+        let info = mock_info("sender", &[Coin::new(1000, "uluna")]);
+        let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Send {}).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("timelock_contract"),
+                msg: hold_funds.clone(),
+                funds: vec![Coin::new(500, "uluna")],
+            }))]
+        );
+        assert_eq!(
+            BALANCES
+                .load(&deps.storage, String::from("address1"))
+                .unwrap(),
+            vec![Funds::Native(Coin::new(500, "uluna"))]
+        );
+
+        // The timelock has no message for CW20 tokens, so it cannot receive a CW20 split
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("sender"),
+            amount: Uint128::from(1000_u128),
+            msg: to_binary(&Cw20HookMsg::Send {}).unwrap(),
+        });
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("cw20_token", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::MissingRecipientMsg {
+                addr: String::from("timelock_contract")
+            }
+        );
+
+        // CW20 shares are sent to the recipient contract with its `Receive` hook message attached
+        let cw20_hook = to_binary(&Cw20HookMsg::Send {}).unwrap();
+        let mut splitter = splitter;
+        splitter.recipients[0] = AddressPercent {
+            addr: String::from("splitter_contract"),
+            percent: Uint128::from(50_u128),
+            msg: Some(to_binary(&ExecuteMsg::Send {}).unwrap()),
+            cw20_msg: Some(cw20_hook.clone()),
+        };
+        SPLITTER.save(deps.as_mut().storage, &splitter).unwrap();
+
+        let res = execute(deps.as_mut(), env, mock_info("cw20_token", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("cw20_token"),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: String::from("splitter_contract"),
+                    amount: Uint128::from(500_u128),
+                    msg: cw20_hook,
+                })
+                .unwrap(),
+                funds: vec![],
+            }))]
+        );
+    }
+
+    #[test]
+    fn test_execute_update_lock_expiration() {
+        let mut deps = mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+
+        CONTRACT_OWNER
+            .save(deps.as_mut().storage, &String::from("creator"))
+            .unwrap();
+        let splitter = Splitter {
+            recipients: vec![AddressPercent {
+                addr: String::from("address1"),
+                percent: Uint128::from(100_u128),
+                msg: None,
+                cw20_msg: None,
+            }],
+            locked: false,
+            lock_expiration: None,
+            address_list: None,
+            pull_payments: false,
+            remainder: RemainderPolicy::RefundSender,
+        };
+        SPLITTER.save(deps.as_mut().storage, &splitter).unwrap();
+
+        let msg = ExecuteMsg::UpdateLock {
+            lock: true,
+            expiration: Some(Expiration::AtHeight(env.block.height)),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(res, ContractError::ExpirationInPast {});
+
+        let expiration = Expiration::AtHeight(env.block.height + 10);
+        let msg = ExecuteMsg::UpdateLock {
+            lock: true,
+            expiration: Some(expiration),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res,
+            Response::default().add_attributes(vec![
+                attr("action", "update_lock"),
+                attr("locked", "true"),
+                attr("expiration", expiration.to_string()),
+            ])
+        );
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::LockStatus {}).unwrap();
+        let status: LockStatusResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            status,
+            LockStatusResponse {
+                locked: true,
+                expiration: Some(expiration),
+            }
+        );
+
+        // The config cannot be updated and the lock cannot be lifted until it expires
+        let update_recipients = ExecuteMsg::UpdateRecipients {
+            recipients: vec![AddressPercent {
+                addr: String::from("address2"),
+                percent: Uint128::from(100_u128),
+                msg: None,
+                cw20_msg: None,
+            }],
+        };
+        let locked_msgs = vec![
+            update_recipients.clone(),
+            ExecuteMsg::UpdateAddressList { address_list: None },
+            ExecuteMsg::UpdateRemainder {
+                remainder: RemainderPolicy::KeepForOwner,
+            },
+            ExecuteMsg::UpdatePullPayments {
+                pull_payments: true,
+            },
+            ExecuteMsg::UpdateLock {
+                lock: false,
+                expiration: None,
+            },
+        ];
+        for msg in locked_msgs {
+            let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+            assert_eq!(res, ContractError::SplitterLocked {});
+        }
+
+        env.block.height += 10;
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::LockStatus {}).unwrap();
+        let status: LockStatusResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            status,
+            LockStatusResponse {
+                locked: false,
+                expiration: None,
+            }
+        );
+        execute(deps.as_mut(), env, info, update_recipients).unwrap();
+        let splitter = SPLITTER.load(deps.as_ref().storage).unwrap();
+        assert_eq!(splitter.recipients[0].addr, "address2");
+    }
 }
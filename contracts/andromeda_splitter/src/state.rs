@@ -1,13 +1,40 @@
-use andromeda_protocol::splitter::Splitter;
-use cosmwasm_std::Addr;
-use cw_storage_plus::Item;
+use andromeda_protocol::{modules::common::Funds, splitter::Splitter};
+use cosmwasm_std::{Addr, StdResult, Storage};
+use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::mem::discriminant;
 
 pub const STATE: Item<State> = Item::new("state");
 pub const SPLITTER: Item<Splitter> = Item::new("splitter");
+/// Funds credited to each recipient when the splitter is using pull payments
+pub const BALANCES: Map<String, Vec<Funds>> = Map::new("balances");
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
     pub owner: Addr, // owner address
 }
+
+/// Adds the given funds to the balance credited to an address
+pub fn add_balance(storage: &mut dyn Storage, address: &str, funds: &[Funds]) -> StdResult<()> {
+    let funds: Vec<&Funds> = funds.iter().filter(|f| !f.amount().is_zero()).collect();
+    if funds.is_empty() {
+        return Ok(());
+    }
+    BALANCES.update(storage, address.to_string(), |balance| -> StdResult<_> {
+        let mut balance = balance.unwrap_or_default();
+        for fund in funds {
+            match balance
+                .iter_mut()
+                .find(|b| discriminant(*b) == discriminant(fund) && b.denom() == fund.denom())
+            {
+                Some(existing) => {
+                    *existing = existing.with_amount(existing.amount() + fund.amount())
+                }
+                None => balance.push(fund.clone()),
+            }
+        }
+        Ok(balance)
+    })?;
+    Ok(())
+}
@@ -20,9 +20,13 @@ fn test_instantiate() {
     });
     let msg = InstantiateMsg {
         address_list,
+        pull_payments: None,
+        remainder: None,
         recipients: vec![AddressPercent {
             addr: String::from("Some Address"),
             percent: Uint128::from(100_u128),
+            msg: None,
+            cw20_msg: None,
         }],
     };
     let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
@@ -1,6 +1,7 @@
+use andromeda_protocol::error::ContractError;
 use cosmwasm_std::{
     attr, entry_point, to_binary, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Reply,
-    Response, StdError, StdResult,
+    Response, StdError,
 };
 
 use cw721::Expiration;
@@ -13,21 +14,32 @@ use andromeda_protocol::{
         hooks::HookResponse,
     },
     modules::{hooks::MessageHooks, Module},
-    ownership::{execute_update_owner, is_contract_owner, query_contract_owner, CONTRACT_OWNER},
+    ownership::{
+        execute_accept_ownership, execute_cancel_ownership_proposal, execute_propose_owner,
+        execute_renounce_ownership, query_contract_owner, query_pending_owner, CONTRACT_OWNER,
+    },
     require,
+    roles::{
+        execute_grant_role, execute_revoke_role, is_owner_or_operator, query_has_role,
+        query_role_members,
+    },
     timelock::{
         get_funds, hold_funds, release_funds, Escrow, ExecuteMsg, GetLockedFundsResponse,
-        GetTimelockConfigResponse, InstantiateMsg, QueryMsg,
+        GetTimelockConfigResponse, InstantiateMsg, MigrateMsg, QueryMsg,
     },
+    version::{migrate_response, migrate_version, query_version, set_contract_version},
 };
 
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let state = State {
         address_list: msg.address_list.clone(),
     };
@@ -36,6 +48,7 @@ pub fn instantiate(
 
     STATE.save(deps.storage, &state)?;
     CONTRACT_OWNER.save(deps.storage, &info.sender.to_string())?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     Ok(Response::new()
         .add_attributes(vec![
             attr("action", "instantiate"),
@@ -46,19 +59,24 @@ pub fn instantiate(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
     if msg.result.is_err() {
-        return Err(StdError::generic_err(msg.result.unwrap_err()));
+        return Err(StdError::generic_err(msg.result.unwrap_err()).into());
     }
 
     match msg.id {
         REPLY_ADDRESS_LIST => on_address_list_reply(deps, msg),
-        _ => Err(StdError::generic_err("reply id is invalid")),
+        _ => Err(ContractError::InvalidReplyId {}),
     }
 }
 
 #[entry_point]
-pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
     let state = STATE.load(deps.storage)?;
 
     // [GLOBAL-02] Changing is_some() + .unwrap() to if let Some()
@@ -73,7 +91,19 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             recipient,
         } => execute_hold_funds(deps, info, expiration, recipient, env),
         ExecuteMsg::ReleaseFunds {} => execute_release_funds(deps, env, info),
-        ExecuteMsg::UpdateOwner { address } => execute_update_owner(deps, info, address),
+        ExecuteMsg::ProposeOwner {
+            address,
+            expiration,
+        } => execute_propose_owner(deps, env, info, address, expiration),
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, env, info),
+        ExecuteMsg::CancelOwnershipProposal {} => execute_cancel_ownership_proposal(deps, info),
+        ExecuteMsg::RenounceOwnership {} => execute_renounce_ownership(deps, info),
+        ExecuteMsg::GrantRole {
+            role,
+            address,
+            expiration,
+        } => execute_grant_role(deps, env, info, role, address, expiration),
+        ExecuteMsg::RevokeRole { role, address } => execute_revoke_role(deps, info, role, address),
         ExecuteMsg::UpdateAddressList { address_list } => {
             execute_update_address_list(deps, info, env, address_list)
         }
@@ -86,7 +116,7 @@ fn execute_hold_funds(
     expiration: Option<Expiration>,
     recipient: Option<String>,
     env: Env,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let rec = recipient.unwrap_or(info.sender.to_string());
     //Validate recipient address
     deps.api.addr_validate(&rec)?;
@@ -112,11 +142,15 @@ fn execute_hold_funds(
     ]))
 }
 
-fn execute_release_funds(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+fn execute_release_funds(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
     let result: Option<Escrow> = get_funds(deps.storage, info.sender.to_string())?;
 
     if result.is_none() {
-        return Err(StdError::generic_err("No locked funds for your address"));
+        return Err(ContractError::NoLockedFunds {});
     }
 
     let funds: Escrow = result.unwrap();
@@ -124,12 +158,12 @@ fn execute_release_funds(deps: DepsMut, env: Env, info: MessageInfo) -> StdResul
         Some(expiration) => match expiration {
             Expiration::AtTime(t) => {
                 if t > env.block.time {
-                    return Err(StdError::generic_err("Your funds are still locked"));
+                    return Err(ContractError::FundsLocked {});
                 }
             }
             Expiration::AtHeight(h) => {
                 if h > env.block.height {
-                    return Err(StdError::generic_err("Your funds are still locked"));
+                    return Err(ContractError::FundsLocked {});
                 }
             }
             _ => {}
@@ -154,11 +188,11 @@ fn execute_update_address_list(
     info: MessageInfo,
     env: Env,
     address_list: Option<AddressListModule>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut state = STATE.load(deps.storage)?;
     require(
-        is_contract_owner(deps.storage, info.sender.to_string())?,
-        StdError::generic_err("May only be used by the contract owner"),
+        is_owner_or_operator(deps.storage, &env.block, info.sender.as_str())?,
+        ContractError::Unauthorized {},
     )?;
 
     let mod_resp = match address_list.clone() {
@@ -176,20 +210,43 @@ fn execute_update_address_list(
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
-        QueryMsg::GetLockedFunds { address } => to_binary(&query_held_funds(deps, address)?),
-        QueryMsg::GetTimelockConfig {} => to_binary(&query_config(deps)?),
-        QueryMsg::ContractOwner {} => to_binary(&query_contract_owner(deps)?),
+        QueryMsg::GetLockedFunds { address } => Ok(to_binary(&query_held_funds(deps, address)?)?),
+        QueryMsg::GetTimelockConfig {} => Ok(to_binary(&query_config(deps)?)?),
+        QueryMsg::ContractOwner {} => Ok(to_binary(&query_contract_owner(deps)?)?),
+        QueryMsg::PendingOwner {} => Ok(to_binary(&query_pending_owner(deps)?)?),
+        QueryMsg::Version {} => Ok(to_binary(&query_version(deps)?)?),
+        QueryMsg::HasRole { role, address } => {
+            Ok(to_binary(&query_has_role(deps, env, role, address)?)?)
+        }
+        QueryMsg::RoleMembers {
+            role,
+            start_after,
+            limit,
+        } => Ok(to_binary(&query_role_members(
+            deps,
+            env,
+            role,
+            start_after,
+            limit,
+        )?)?),
     }
 }
 
-fn query_held_funds(deps: Deps, address: String) -> StdResult<GetLockedFundsResponse> {
+#[entry_point]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let previous = migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(migrate_response(previous, CONTRACT_VERSION))
+}
+
+fn query_held_funds(deps: Deps, address: String) -> Result<GetLockedFundsResponse, ContractError> {
     let hold_funds = get_funds(deps.storage, address)?;
     Ok(GetLockedFundsResponse { funds: hold_funds })
 }
 
-fn query_config(deps: Deps) -> StdResult<GetTimelockConfigResponse> {
+fn query_config(deps: Deps) -> Result<GetTimelockConfigResponse, ContractError> {
     let state = STATE.load(deps.storage)?;
 
     let address_list_contract = match state.address_list.clone() {
@@ -206,6 +263,7 @@ fn query_config(deps: Deps) -> StdResult<GetTimelockConfigResponse> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use andromeda_protocol::roles::{grant_role, ROLE_OPERATOR};
     use cosmwasm_std::{
         coin, from_binary,
         testing::{mock_dependencies, mock_env, mock_info},
@@ -345,7 +403,7 @@ mod tests {
         let msg = ExecuteMsg::ReleaseFunds {};
         let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
 
-        let expected = StdError::generic_err("Your funds are still locked");
+        let expected = ContractError::FundsLocked {};
 
         assert_eq!(res, expected);
     }
@@ -375,9 +433,27 @@ mod tests {
 
         let unauth_info = mock_info("anyone", &[]);
         let err_res = execute(deps.as_mut(), env.clone(), unauth_info, msg.clone()).unwrap_err();
+        assert_eq!(err_res, ContractError::Unauthorized {});
+
+        // Operators may also update the address list
+        grant_role(
+            deps.as_mut().storage,
+            &env.block,
+            ROLE_OPERATOR,
+            "operator",
+            None,
+        )
+        .unwrap();
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("operator", &[]),
+            ExecuteMsg::UpdateAddressList { address_list: None },
+        )
+        .unwrap();
         assert_eq!(
-            err_res,
-            StdError::generic_err("May only be used by the contract owner")
+            resp,
+            Response::default().add_attributes(vec![attr("action", "update_address_list")])
         );
 
         let info = mock_info(owner, &[]);
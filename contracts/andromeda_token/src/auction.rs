@@ -0,0 +1,629 @@
+use andromeda_protocol::error::ContractError;
+use andromeda_protocol::{
+    modules::{
+        auction::{get_auction_module, Auction, AuctionType, Bid, AUCTIONS},
+        common::Funds,
+        read_modules,
+    },
+    require,
+    token::{AuctionResponse, TransferAgreement},
+};
+use cosmwasm_std::{
+    attr, coin, BankMsg, Deps, DepsMut, Env, MessageInfo, OverflowError, OverflowOperation,
+    Response,
+};
+
+use crate::state::{load_token, save_token};
+
+pub fn execute_start_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    auction_type: AuctionType,
+    denom: String,
+    duration: u64,
+) -> Result<Response, ContractError> {
+    require(
+        get_auction_module(deps.storage)?.is_some(),
+        ContractError::AuctionsNotEnabled {},
+    )?;
+    let token = load_token(deps.storage, token_id.clone())?;
+    require(
+        token.owner.eq(&info.sender.to_string()),
+        ContractError::AuctionUnauthorized {},
+    )?;
+    require(!token.archived, ContractError::TokenArchived {})?;
+    require(
+        AUCTIONS.may_load(deps.storage, token_id.clone())?.is_none(),
+        ContractError::TokenAuctioned {},
+    )?;
+
+    let start_time = env.block.time.seconds();
+    let end_time = start_time
+        .checked_add(duration)
+        .ok_or_else(|| OverflowError::new(OverflowOperation::Add, start_time, duration))?;
+    let auction = Auction {
+        seller: token.owner,
+        auction_type,
+        denom,
+        start_time,
+        end_time,
+        highest_bid: None,
+    };
+    auction.validate()?;
+    AUCTIONS.save(deps.storage, token_id.clone(), &auction)?;
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "start_auction"),
+        attr("token_id", token_id),
+        attr("seller", auction.seller),
+        attr("end_time", auction.end_time.to_string()),
+    ]))
+}
+
+pub fn execute_place_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let mut auction = load_auction(deps.as_ref(), token_id.clone())?;
+    require(
+        !auction.is_ended(&env.block),
+        ContractError::AuctionEnded {},
+    )?;
+    require(
+        !auction.seller.eq(&info.sender.to_string()),
+        ContractError::BidOnOwnAuction {},
+    )?;
+    require(
+        info.funds.len() == 1 && info.funds[0].denom == auction.denom,
+        ContractError::InvalidBidFunds {},
+    )?;
+    let amount = info.funds[0].amount;
+    let price = auction.current_price(&env.block)?;
+    require(amount >= price, ContractError::BidTooLow {})?;
+
+    let bidder = info.sender.to_string();
+    let mut res = Response::default();
+    match auction.auction_type {
+        AuctionType::English { .. } => {
+            // Refund the previous highest bidder
+            if let Some(previous_bid) = auction.highest_bid {
+                res = res.add_message(BankMsg::Send {
+                    to_address: previous_bid.bidder,
+                    amount: vec![coin(previous_bid.amount.u128(), auction.denom.clone())],
+                });
+            }
+            auction.highest_bid = Some(Bid {
+                bidder: bidder.clone(),
+                amount,
+            });
+
+            // Bids placed close to the end of the auction extend it to prevent sniping
+            let extension_window =
+                get_auction_module(deps.storage)?.and_then(|m| m.extension_window);
+            if let Some(window) = extension_window {
+                let now = env.block.time.seconds();
+                if auction.end_time - now < window {
+                    auction.end_time = now + window;
+                }
+            }
+            AUCTIONS.save(deps.storage, token_id.clone(), &auction)?;
+
+            Ok(res.add_attributes(vec![
+                attr("action", "place_bid"),
+                attr("token_id", token_id),
+                attr("bidder", bidder),
+                attr("amount", amount.to_string()),
+                attr("end_time", auction.end_time.to_string()),
+            ]))
+        }
+        AuctionType::Dutch { .. } => {
+            // Refund any amount sent in excess of the current price
+            if amount > price {
+                res = res.add_message(BankMsg::Send {
+                    to_address: bidder.clone(),
+                    amount: vec![coin((amount - price).u128(), auction.denom.clone())],
+                });
+            }
+            let bid = Bid {
+                bidder,
+                amount: price,
+            };
+
+            settle_auction(deps, env, info, token_id, auction, bid, res)
+        }
+    }
+}
+
+pub fn execute_settle_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let auction = load_auction(deps.as_ref(), token_id.clone())?;
+    require(
+        auction.is_ended(&env.block),
+        ContractError::AuctionNotEnded {},
+    )?;
+
+    match auction.highest_bid.clone() {
+        Some(bid) => settle_auction(deps, env, info, token_id, auction, bid, Response::default()),
+        None => {
+            AUCTIONS.remove(deps.storage, token_id.clone());
+
+            Ok(Response::default().add_attributes(vec![
+                attr("action", "settle_auction"),
+                attr("token_id", token_id),
+            ]))
+        }
+    }
+}
+
+pub fn execute_cancel_auction(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let auction = load_auction(deps.as_ref(), token_id.clone())?;
+    require(
+        auction.seller.eq(&info.sender.to_string()),
+        ContractError::CancelAuctionUnauthorized {},
+    )?;
+    require(
+        auction.highest_bid.is_none(),
+        ContractError::AuctionHasBids {},
+    )?;
+    AUCTIONS.remove(deps.storage, token_id.clone());
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "cancel_auction"),
+        attr("token_id", token_id),
+    ]))
+}
+
+pub fn query_auction(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+) -> Result<AuctionResponse, ContractError> {
+    let auction = load_auction(deps, token_id)?;
+    let current_price = auction.current_price(&env.block)?;
+
+    Ok(AuctionResponse {
+        auction,
+        current_price,
+    })
+}
+
+fn load_auction(deps: Deps, token_id: String) -> Result<Auction, ContractError> {
+    AUCTIONS
+        .may_load(deps.storage, token_id)?
+        .ok_or(ContractError::TokenNotAuctioned {})
+}
+
+/// Transfers an auctioned token to the winning bidder, paying the seller and any fees from the escrowed bid
+fn settle_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    auction: Auction,
+    bid: Bid,
+    res_in: Response,
+) -> Result<Response, ContractError> {
+    let mut token = load_token(deps.storage, token_id.clone())?;
+    // Auctioned tokens are locked by the auction module, so this only fails if that lock was bypassed
+    require(
+        token.owner == auction.seller,
+        ContractError::AuctionSellerNotOwner {},
+    )?;
+    AUCTIONS.remove(deps.storage, token_id.clone());
+
+    let modules = read_modules(deps.storage)?;
+    let mod_res = modules.on_transfer(
+        &deps,
+        info.clone(),
+        env.clone(),
+        bid.bidder.clone(),
+        token_id.clone(),
+    )?;
+
+    token.owner = bid.bidder.clone();
+    token.approvals = vec![];
+    token.transfer_agreement = None;
+    save_token(deps.storage, token_id.clone(), &token)?;
+
+    let agreement = TransferAgreement {
+        amount: Funds::Native(coin(bid.amount.u128(), auction.denom)),
+        purchaser: bid.bidder.clone(),
+    };
+    let res = res_in
+        .add_submessages(mod_res.msgs)
+        .add_events(mod_res.events)
+        .add_attributes(vec![
+            attr("action", "settle_auction"),
+            attr("token_id", token_id),
+            attr("purchaser", bid.bidder),
+            attr("amount", agreement.amount.to_string()),
+        ]);
+
+    agreement.on_escrowed_transfer(&deps, &info, &env, auction.seller, token.royalty, res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use andromeda_protocol::{
+        modules::{AddressWeight, ModuleDefinition, Rate},
+        token::{ExecuteMsg, InstantiateMsg, MintMsg, QueryMsg},
+    };
+    use cosmwasm_std::{
+        from_binary,
+        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+        OwnedDeps, SubMsg, Uint128,
+    };
+
+    const SELLER: &str = "seller";
+
+    fn setup(modules: Vec<ModuleDefinition>) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info(SELLER, &[]);
+        let msg = InstantiateMsg {
+            name: "test".to_string(),
+            symbol: "T".to_string(),
+            minter: SELLER.to_string(),
+            modules,
+            max_supply: None,
+            mint_start: None,
+            mint_end: None,
+            metadata_update_policy: None,
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mint_msg = ExecuteMsg::Mint(MintMsg {
+            token_id: "1".to_string(),
+            owner: SELLER.to_string(),
+            description: None,
+            name: "TestToken".to_string(),
+            metadata: None,
+            image: None,
+            pricing: None,
+            royalty: None,
+        });
+        execute(deps.as_mut(), env, info, mint_msg).unwrap();
+
+        deps
+    }
+
+    fn start_auction_msg(auction_type: AuctionType) -> ExecuteMsg {
+        ExecuteMsg::StartAuction {
+            token_id: "1".to_string(),
+            auction_type,
+            denom: "uluna".to_string(),
+            duration: 100,
+        }
+    }
+
+    fn bid_msg() -> ExecuteMsg {
+        ExecuteMsg::PlaceBid {
+            token_id: "1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_start_auction() {
+        let english = AuctionType::English {
+            min_bid: Uint128::from(100u128),
+            min_increment: Uint128::from(10u128),
+        };
+
+        let mut deps = setup(vec![]);
+        let resp = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(SELLER, &[]),
+            start_auction_msg(english.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::AuctionsNotEnabled {});
+
+        let mut deps = setup(vec![ModuleDefinition::Auction {
+            extension_window: None,
+        }]);
+        let resp = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            start_auction_msg(english.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::AuctionUnauthorized {});
+
+        let resp = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(SELLER, &[]),
+            ExecuteMsg::StartAuction {
+                token_id: "1".to_string(),
+                auction_type: english.clone(),
+                denom: "uluna".to_string(),
+                duration: u64::MAX,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(resp, ContractError::OverflowError(_)));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(SELLER, &[]),
+            start_auction_msg(english.clone()),
+        )
+        .unwrap();
+
+        // Auctioned tokens are locked by the auction module
+        let resp = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(SELLER, &[]),
+            ExecuteMsg::TransferNft {
+                recipient: "recipient".to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::TokenAuctioned {});
+
+        // The auction module cannot be removed while it is locking auctioned tokens
+        let remove_msg = ExecuteMsg::RemoveModule { module_idx: 0 };
+        let resp = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(SELLER, &[]),
+            remove_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::AuctionsOpen {});
+
+        let resp = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::CancelAuction {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::CancelAuctionUnauthorized {});
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(SELLER, &[]),
+            ExecuteMsg::CancelAuction {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        let resp = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Auction {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::TokenNotAuctioned {});
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(SELLER, &[]),
+            remove_msg,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_english_auction() {
+        let mut deps = setup(vec![
+            ModuleDefinition::Auction {
+                extension_window: Some(30),
+            },
+            ModuleDefinition::Taxable {
+                rate: Rate::Percent(10),
+                receivers: vec![AddressWeight::new("tax_receiver", 1)],
+                description: None,
+                rounding: None,
+            },
+            ModuleDefinition::Royalties {
+                rate: Rate::Percent(10),
+                receivers: vec![AddressWeight::new("royalty_receiver", 1)],
+                description: None,
+                rounding: None,
+            },
+        ]);
+        let mut env = mock_env();
+        let start_time = env.block.time.seconds();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(SELLER, &[]),
+            start_auction_msg(AuctionType::English {
+                min_bid: Uint128::from(100u128),
+                min_increment: Uint128::from(10u128),
+            }),
+        )
+        .unwrap();
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bidder_one", &[coin(99, "uluna")]),
+            bid_msg(),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::BidTooLow {});
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bidder_one", &[coin(100, "uusd")]),
+            bid_msg(),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::InvalidBidFunds {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bidder_one", &[coin(100, "uluna")]),
+            bid_msg(),
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Auction {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        let auction: AuctionResponse = from_binary(&res).unwrap();
+        assert_eq!(auction.current_price, Uint128::from(110u128));
+
+        // A late bid extends the auction and refunds the previous bidder
+        env.block.time = env.block.time.plus_seconds(90);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bidder_two", &[coin(200, "uluna")]),
+            bid_msg(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "bidder_one".to_string(),
+                amount: vec![coin(100, "uluna")],
+            })]
+        );
+        let auction = AUCTIONS
+            .load(deps.as_ref().storage, "1".to_string())
+            .unwrap();
+        assert_eq!(auction.end_time, start_time + 120);
+
+        env.block.time = env.block.time.plus_seconds(20);
+        let settle_msg = ExecuteMsg::SettleAuction {
+            token_id: "1".to_string(),
+        };
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            settle_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::AuctionNotEnded {});
+
+        env.block.time = env.block.time.plus_seconds(10);
+
+        // The token is never taken from anyone other than the seller
+        let mut token = load_token(deps.as_ref().storage, "1".to_string()).unwrap();
+        token.owner = "other".to_string();
+        save_token(deps.as_mut().storage, "1".to_string(), &token).unwrap();
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            settle_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::AuctionSellerNotOwner {});
+        token.owner = SELLER.to_string();
+        save_token(deps.as_mut().storage, "1".to_string(), &token).unwrap();
+
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), settle_msg).unwrap();
+        // The tax is covered by the escrowed bid and so is deducted from the seller's payment
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: SELLER.to_string(),
+                    amount: vec![coin(160, "uluna")],
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "tax_receiver".to_string(),
+                    amount: vec![coin(20, "uluna")],
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "royalty_receiver".to_string(),
+                    amount: vec![coin(20, "uluna")],
+                }),
+            ]
+        );
+        let token = load_token(deps.as_ref().storage, "1".to_string()).unwrap();
+        assert_eq!(token.owner, "bidder_two".to_string());
+        assert!(AUCTIONS
+            .may_load(deps.as_ref().storage, "1".to_string())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_dutch_auction() {
+        let mut deps = setup(vec![ModuleDefinition::Auction {
+            extension_window: None,
+        }]);
+        let mut env = mock_env();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(SELLER, &[]),
+            start_auction_msg(AuctionType::Dutch {
+                start_price: Uint128::from(1000u128),
+                end_price: Uint128::from(500u128),
+            }),
+        )
+        .unwrap();
+
+        env.block.time = env.block.time.plus_seconds(50);
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bidder", &[coin(700, "uluna")]),
+            bid_msg(),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::BidTooLow {});
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("bidder", &[coin(800, "uluna")]),
+            bid_msg(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: "bidder".to_string(),
+                    amount: vec![coin(50, "uluna")],
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: SELLER.to_string(),
+                    amount: vec![coin(750, "uluna")],
+                }),
+            ]
+        );
+        let token = load_token(deps.as_ref().storage, "1".to_string()).unwrap();
+        assert_eq!(token.owner, "bidder".to_string());
+    }
+}
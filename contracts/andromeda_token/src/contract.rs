@@ -1,51 +1,79 @@
+use andromeda_protocol::error::ContractError;
 use andromeda_protocol::{
     modules::{
         address_list::{on_address_list_reply, REPLY_ADDRESS_LIST},
-        read_modules,
-        receipt::{on_receipt_reply, REPLY_RECEIPT},
-        store_modules, Modules,
+        auction::AUCTIONS,
+        common::{add_payment, calculate_rounded_fee, calculate_shares, Funds},
+        hooks::HookResponse,
+        migrate_modules, read_modules,
+        receipt::{add_receipt_message, on_receipt_reply, REPLY_RECEIPT},
+        royalties::{get_royalty_module, Royalty},
+        store_modules, Module, ModuleDefinition, Modules,
+    },
+    ownership::{
+        execute_accept_ownership, execute_cancel_ownership_proposal, execute_propose_owner,
+        execute_renounce_ownership, is_contract_owner, query_contract_owner, query_pending_owner,
+        CONTRACT_OWNER,
     },
-    ownership::{execute_update_owner, query_contract_owner, CONTRACT_OWNER},
     require,
+    roles::{
+        execute_grant_role, execute_revoke_role, grant_role, has_role, query_has_role,
+        query_role_members, revoke_role, role_members, ROLE_MINTER,
+    },
     token::{
-        Approval, ExecuteMsg, InstantiateMsg, MigrateMsg, MintMsg, ModuleContract,
-        ModuleInfoResponse, NftInfoResponseExtension, QueryMsg, Token, TransferAgreement,
+        Approval, CheckRoyaltiesResponse, ContractInfoResponse, Cw20HookMsg, ExecuteMsg,
+        InstantiateMsg, MigrateMsg, MintMsg, MintersResponse, ModuleContract, ModuleInfoResponse,
+        ModuleOrder, NftInfoResponseExtension, Offer, OffersResponse, QueryMsg,
+        RoyaltyInfoResponse, RoyaltyPayment, Token, TokenMetadata, TransferAgreement,
     },
+    version::{migrate_response, migrate_version, query_version, set_contract_version},
 };
 
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, coin, to_binary, Addr, Api, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Order, Pair,
-    Reply, Response, StdError, StdResult,
+    attr, coin, from_binary, to_binary, Addr, Api, BankMsg, Binary, Coin, Deps, DepsMut, Env,
+    Event, MessageInfo, Order, Pair, Reply, Response, StdError, StdResult, Storage, Uint128,
 };
+use cw20::Cw20ReceiveMsg;
 use cw721::{
-    AllNftInfoResponse, ApprovedForAllResponse, ContractInfoResponse, Cw721ReceiveMsg, Expiration,
-    NftInfoResponse, NumTokensResponse, OwnerOfResponse,
+    AllNftInfoResponse, ApprovedForAllResponse, Cw721ReceiveMsg, Expiration, NftInfoResponse,
+    NumTokensResponse, OwnerOfResponse, TokensResponse,
 };
 use cw_storage_plus::Bound;
 
+use crate::auction::{
+    execute_cancel_auction, execute_place_bid, execute_settle_auction, execute_start_auction,
+    query_auction,
+};
 use crate::state::{
-    decrement_num_tokens, has_transfer_rights, increment_num_tokens, load_token, mint_token,
-    TokenConfig, CONFIG, NUM_TOKENS, OPERATOR, TOKENS,
+    burn_token, decrement_num_tokens, has_transfer_rights, increment_minted_tokens,
+    increment_num_tokens, is_metadata_frozen, is_minter, is_token_migration_pending, load_token,
+    migrate_tokens, mint_token, save_token, TokenConfig, CONFIG, FROZEN_METADATA, MINTED_TOKENS,
+    NUM_TOKENS, OFFERS, OPERATOR, OWNED_TOKENS, TOKENS, TOKEN_MIGRATION,
 };
 
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
-    require(
-        msg.name.len() > 3,
-        StdError::generic_err("Name must be between 3 and 30 characters."),
-    )?;
+) -> Result<Response, ContractError> {
+    require(msg.name.len() > 3, ContractError::InvalidNameLength {})?;
     msg.validate()?;
     let config = TokenConfig {
         name: msg.name.clone(),
         symbol: msg.symbol.clone(),
         minter: msg.minter.to_string(),
+        max_supply: msg.max_supply,
+        mint_start: msg.mint_start,
+        mint_end: msg.mint_end,
+        metadata_update_policy: msg.metadata_update_policy.unwrap_or_default(),
+        metadata_frozen: false,
     };
 
     let modules = Modules::new(msg.modules);
@@ -53,6 +81,7 @@ pub fn instantiate(
 
     CONFIG.save(deps.storage, &config)?;
     CONTRACT_OWNER.save(deps.storage, &msg.minter)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     store_modules(deps.storage, modules)?;
 
     Ok(Response::new()
@@ -67,29 +96,52 @@ pub fn instantiate(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
     if msg.result.is_err() {
-        return Err(StdError::generic_err(msg.result.unwrap_err()));
+        return Err(StdError::generic_err(msg.result.unwrap_err()).into());
     }
 
     match msg.id {
         REPLY_RECEIPT => on_receipt_reply(deps, msg),
         REPLY_ADDRESS_LIST => on_address_list_reply(deps, msg),
-        _ => Err(StdError::generic_err("reply id is invalid")),
+        _ => Err(ContractError::InvalidReplyId {}),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
-    let modules = read_modules(deps.storage)?;
-    modules.on_execute(&deps, info.clone(), env.clone())?;
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    if let ExecuteMsg::MigrateTokens { limit } = msg {
+        return execute_migrate_tokens(deps, info, limit);
+    }
+    require(
+        !is_token_migration_pending(deps.storage)?,
+        ContractError::TokenMigrationPending {},
+    )?;
+
+    // CW20 sends are authorized against the sender of the tokens rather than the token contract,
+    // which is checked once the received message has been parsed
+    if !matches!(msg, ExecuteMsg::Receive(_)) {
+        let modules = read_modules(deps.storage)?;
+        modules.on_execute(&deps, info.clone(), env.clone())?;
+    }
 
     match msg {
         ExecuteMsg::Mint(msg) => execute_mint(deps, env, info, msg),
+        ExecuteMsg::BatchMint { tokens } => execute_batch_mint(deps, env, info, tokens),
         ExecuteMsg::TransferNft {
             recipient,
             token_id,
         } => execute_transfer(deps, env, info, recipient, token_id),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
+        ExecuteMsg::BatchTransferNft {
+            recipient,
+            token_ids,
+        } => execute_batch_transfer(deps, env, info, recipient, token_ids),
         ExecuteMsg::SendNft {
             contract,
             token_id,
@@ -109,16 +161,64 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
         ExecuteMsg::RevokeAll { operator } => execute_revoke_all(deps, env, info, operator),
         ExecuteMsg::TransferAgreement {
             token_id,
-            denom,
             amount,
             purchaser,
-        } => execute_transfer_agreement(deps, env, info, token_id, purchaser, amount.u128(), denom),
+        } => execute_transfer_agreement(deps, env, info, token_id, purchaser, amount),
         ExecuteMsg::Burn { token_id } => execute_burn(deps, env, info, token_id),
+        ExecuteMsg::BatchBurn { token_ids } => execute_batch_burn(deps, env, info, token_ids),
         ExecuteMsg::Archive { token_id } => execute_archive(deps, env, info, token_id),
-        ExecuteMsg::UpdateOwner { address } => execute_update_owner(deps, info, address),
+        ExecuteMsg::ProposeOwner {
+            address,
+            expiration,
+        } => execute_propose_owner(deps, env, info, address, expiration),
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, env, info),
+        ExecuteMsg::CancelOwnershipProposal {} => execute_cancel_ownership_proposal(deps, info),
+        ExecuteMsg::RenounceOwnership {} => execute_renounce_ownership(deps, info),
+        ExecuteMsg::GrantRole {
+            role,
+            address,
+            expiration,
+        } => execute_grant_role(deps, env, info, role, address, expiration),
+        ExecuteMsg::RevokeRole { role, address } => execute_revoke_role(deps, info, role, address),
         ExecuteMsg::UpdatePricing { token_id, price } => {
             execute_update_pricing(deps, env, info, token_id, price)
         }
+        ExecuteMsg::UpdateMetadata {
+            token_id,
+            name,
+            description,
+            image,
+            metadata,
+        } => execute_update_metadata(deps, info, token_id, name, description, image, metadata),
+        ExecuteMsg::FreezeMetadata { token_id } => execute_freeze_metadata(deps, info, token_id),
+        ExecuteMsg::PlaceOffer { token_id, expires } => {
+            execute_place_offer(deps, env, info, token_id, expires)
+        }
+        ExecuteMsg::AcceptOffer {
+            token_id,
+            purchaser,
+        } => execute_accept_offer(deps, env, info, token_id, purchaser),
+        ExecuteMsg::CancelOffer { token_id } => execute_cancel_offer(deps, info, token_id),
+        ExecuteMsg::StartAuction {
+            token_id,
+            auction_type,
+            denom,
+            duration,
+        } => execute_start_auction(deps, env, info, token_id, auction_type, denom, duration),
+        ExecuteMsg::PlaceBid { token_id } => execute_place_bid(deps, env, info, token_id),
+        ExecuteMsg::SettleAuction { token_id } => execute_settle_auction(deps, env, info, token_id),
+        ExecuteMsg::CancelAuction { token_id } => execute_cancel_auction(deps, info, token_id),
+        ExecuteMsg::UpdateMinter { address } => execute_update_minter(deps, info, address),
+        ExecuteMsg::AddMinter { address } => execute_add_minter(deps, env, info, address),
+        ExecuteMsg::RemoveMinter { address } => execute_remove_minter(deps, env, info, address),
+        ExecuteMsg::AddModule { module } => execute_add_module(deps, env, info, module),
+        ExecuteMsg::RemoveModule { module_idx } => {
+            execute_remove_module(deps, env, info, module_idx)
+        }
+        ExecuteMsg::UpdateModule { module_idx, module } => {
+            execute_update_module(deps, env, info, module_idx, module)
+        }
+        ExecuteMsg::MigrateTokens { .. } => Err(ContractError::NoTokenMigration {}),
     }
 }
 
@@ -127,24 +227,14 @@ pub fn execute_mint(
     env: Env,
     info: MessageInfo,
     msg: MintMsg,
-) -> StdResult<Response> {
-    let token = Token {
-        token_id: msg.token_id.clone(),
-        owner: info.sender.to_string(),
-        description: msg.description.clone(),
-        name: msg.name.clone(),
-        approvals: vec![],
-        transfer_agreement: None,
-        metadata: msg.metadata.clone(),
-        image: msg.image.clone(),
-        archived: false,
-        pricing: msg.pricing.clone(),
-        publisher: info.sender.to_string(),
-    };
-    let config = CONFIG.load(deps.storage)?;
-
-    mint_token(deps.storage, msg.token_id.to_string(), token)?;
-    increment_num_tokens(deps.storage)?;
+) -> Result<Response, ContractError> {
+    require(
+        is_minter(deps.storage, &env.block, info.sender.as_str())?,
+        ContractError::MintUnauthorized {},
+    )?;
+    let config = require_can_mint(deps.storage, &env, 1)?;
+    let token = store_minted_token(deps.storage, deps.api, &info, &msg)?;
+    let owner = token.owner;
 
     let modules = read_modules(deps.storage)?;
     let mod_res = modules.on_mint(&deps, info.clone(), env, msg.token_id.clone())?;
@@ -155,7 +245,7 @@ pub fn execute_mint(
         .add_attributes(vec![
             attr("action", "mint"),
             attr("token_id", msg.token_id),
-            attr("owner", info.sender.to_string()),
+            attr("owner", owner.to_string()),
             attr("name", msg.name),
             attr("symbol", config.symbol),
             attr(
@@ -178,13 +268,124 @@ pub fn execute_mint(
         ]))
 }
 
-pub fn execute_transfer(
+fn execute_batch_mint(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    tokens: Vec<MintMsg>,
+) -> Result<Response, ContractError> {
+    require(
+        is_minter(deps.storage, &env.block, info.sender.as_str())?,
+        ContractError::MintUnauthorized {},
+    )?;
+    require(!tokens.is_empty(), ContractError::EmptyBatch {})?;
+    // The mint window and max supply are checked once for the whole batch
+    require_can_mint(deps.storage, &env, tokens.len() as u64)?;
+
+    let modules = read_modules(deps.storage)?;
+    let mut mod_res = HookResponse::default();
+    let mut event = Event::new("batch_mint");
+    // Module mint hooks are token specific and so are still run for each token
+    for msg in tokens.iter() {
+        let token = store_minted_token(deps.storage, deps.api, &info, msg)?;
+        mod_res = mod_res.add_resp(modules.on_mint(
+            &deps,
+            info.clone(),
+            env.clone(),
+            token.token_id.clone(),
+        )?);
+        event = event.add_attributes(vec![
+            attr("token_id", token.token_id),
+            attr("owner", token.owner),
+        ]);
+    }
+
+    let res = Response::default()
+        .add_submessages(mod_res.msgs)
+        .add_events(mod_res.events)
+        .add_event(event)
+        .add_attributes(vec![
+            attr("action", "batch_mint"),
+            attr("count", tokens.len().to_string()),
+            attr("publisher", info.sender.to_string()),
+        ]);
+
+    add_receipt_message(deps.storage, res)
+}
+
+/// Errors if minting is outside of the configured mint window or if minting `count` more tokens
+/// would exceed the max supply. Returns the token config so that callers need not load it again.
+fn require_can_mint(
+    storage: &dyn Storage,
+    env: &Env,
+    count: u64,
+) -> Result<TokenConfig, ContractError> {
+    let config = CONFIG.load(storage)?;
+    if let Some(mint_start) = config.mint_start {
+        require(
+            mint_start.is_expired(&env.block),
+            ContractError::MintingNotStarted {},
+        )?;
+    }
+    if let Some(mint_end) = config.mint_end {
+        require(
+            !mint_end.is_expired(&env.block),
+            ContractError::MintingEnded {},
+        )?;
+    }
+    if let Some(max_supply) = config.max_supply {
+        let minted = MINTED_TOKENS.may_load(storage)?.unwrap_or_default();
+        require(
+            minted + count <= max_supply,
+            ContractError::MaxSupplyReached {},
+        )?;
+    }
+
+    Ok(config)
+}
+
+/// Creates and stores a token from a `MintMsg`, incrementing the token counts.
+/// Callers must first check that minting is permitted using `require_can_mint`.
+fn store_minted_token(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    info: &MessageInfo,
+    msg: &MintMsg,
+) -> Result<Token, ContractError> {
+    if let Some(royalty) = &msg.royalty {
+        royalty.validate(vec![])?;
+    }
+
+    let owner = api.addr_validate(&msg.owner)?;
+    let token = Token {
+        token_id: msg.token_id.clone(),
+        owner: owner.to_string(),
+        description: msg.description.clone(),
+        name: msg.name.clone(),
+        approvals: vec![],
+        transfer_agreement: None,
+        metadata: msg.metadata.clone(),
+        image: msg.image.clone(),
+        archived: false,
+        pricing: msg.pricing.clone(),
+        publisher: info.sender.to_string(),
+        royalty: msg.royalty.clone(),
+    };
+
+    mint_token(storage, msg.token_id.to_string(), token.clone())?;
+    increment_num_tokens(storage)?;
+    increment_minted_tokens(storage)?;
+
+    Ok(token)
+}
+
+pub fn execute_transfer(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
     recipient: String,
     token_id: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let modules = read_modules(deps.storage)?;
     let mod_res = modules.on_transfer(
         &deps,
@@ -194,7 +395,18 @@ pub fn execute_transfer(
         token_id.clone(),
     )?;
 
-    let res = transfer_nft(deps, &env, &info, &recipient, &token_id)?;
+    let mut funds = info.funds.clone();
+    let res = transfer_nft(
+        &mut deps,
+        &env,
+        &info,
+        &recipient,
+        &token_id,
+        &mut funds,
+        Response::new(),
+        true,
+    )?;
+    let res = refund_funds(res, &info.sender, funds);
 
     Ok(res
         .add_submessages(mod_res.msgs)
@@ -207,14 +419,147 @@ pub fn execute_transfer(
         ]))
 }
 
-pub fn execute_send_nft(
+fn execute_receive(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&msg.msg)? {
+        Cw20HookMsg::TransferNft {
+            recipient,
+            token_id,
+        } => execute_cw20_transfer(deps, env, info, msg.sender, msg.amount, recipient, token_id),
+    }
+}
+
+/// Transfers a token whose transfer agreement is priced in a CW20 token, paid for with the received CW20 tokens.
+/// The CW20 tokens are paid out as defined by the agreement and any excess is refunded to the purchaser.
+fn execute_cw20_transfer(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: String,
+    amount: Uint128,
+    recipient: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let token = load_token(deps.storage, token_id.clone())?;
+    let agreement = match token.transfer_agreement.clone() {
+        Some(agreement) if matches!(agreement.amount, Funds::Cw20(_)) => agreement,
+        _ => return Err(ContractError::NoCw20TransferAgreement {}),
+    };
+    require(
+        agreement.amount.denom().eq(&info.sender.to_string()),
+        ContractError::InvalidCw20Token {},
+    )?;
+
+    // The purchaser is the sender of the CW20 tokens rather than the CW20 token contract
+    let purchaser_info = MessageInfo {
+        sender: deps.api.addr_validate(&sender)?,
+        funds: vec![],
+    };
+    let modules = read_modules(deps.storage)?;
+    modules.on_execute(&deps, purchaser_info.clone(), env.clone())?;
+    let mod_res = modules.on_transfer(
+        &deps,
+        purchaser_info.clone(),
+        env.clone(),
+        recipient.clone(),
+        token_id.clone(),
+    )?;
+
+    let required = agreement.total_payments(
+        &deps,
+        &purchaser_info,
+        &env,
+        token.owner.clone(),
+        token.royalty.clone(),
+    )?;
+    require(amount >= required, ContractError::InsufficientFunds {})?;
+
+    let mut res = complete_transfer(
+        &mut deps,
+        &env,
+        &purchaser_info,
+        &recipient,
+        token,
+        Response::new(),
+        true,
+    )?;
+    if amount > required {
+        let mut refund = vec![];
+        add_payment(
+            &mut refund,
+            sender.clone(),
+            agreement.amount.with_amount(amount - required),
+        );
+        res = res.add_message(refund[0].to_msg()?);
+    }
+
+    Ok(res
+        .add_submessages(mod_res.msgs)
+        .add_events(mod_res.events)
+        .add_attributes(vec![
+            attr("action", "transfer"),
+            attr("recipient", recipient),
+            attr("token_id", token_id),
+            attr("sender", sender),
+        ]))
+}
+
+fn execute_batch_transfer(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    token_ids: Vec<String>,
+) -> Result<Response, ContractError> {
+    require(!token_ids.is_empty(), ContractError::EmptyBatch {})?;
+
+    let modules = read_modules(deps.storage)?;
+    let mut mod_res = HookResponse::default();
+    let mut res = Response::new();
+    // Funds sent with the batch are shared between the agreed transfers within it
+    let mut funds = info.funds.clone();
+    let mut event = Event::new("batch_transfer").add_attribute("recipient", recipient.clone());
+    // Module transfer hooks are token specific and so are still run for each token
+    for token_id in token_ids.iter() {
+        mod_res = mod_res.add_resp(modules.on_transfer(
+            &deps,
+            info.clone(),
+            env.clone(),
+            recipient.clone(),
+            token_id.clone(),
+        )?);
+        res = transfer_nft(
+            &mut deps, &env, &info, &recipient, token_id, &mut funds, res, false,
+        )?;
+        event = event.add_attribute("token_id", token_id);
+    }
+
+    let res = refund_funds(res, &info.sender, funds)
+        .add_submessages(mod_res.msgs)
+        .add_events(mod_res.events)
+        .add_event(event)
+        .add_attributes(vec![
+            attr("action", "batch_transfer"),
+            attr("recipient", recipient),
+            attr("count", token_ids.len().to_string()),
+            attr("sender", info.sender.to_string()),
+        ]);
+
+    add_receipt_message(deps.storage, res)
+}
+
+pub fn execute_send_nft(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
     contract: String,
     token_id: String,
     msg: Binary,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let modules = read_modules(deps.storage)?;
     let mod_res = modules.on_send(
         &deps,
@@ -225,7 +570,18 @@ pub fn execute_send_nft(
     )?;
 
     // Transfer token
-    let res = transfer_nft(deps, &env, &info, &contract, &token_id)?;
+    let mut funds = info.funds.clone();
+    let res = transfer_nft(
+        &mut deps,
+        &env,
+        &info,
+        &contract,
+        &token_id,
+        &mut funds,
+        Response::new(),
+        true,
+    )?;
+    let res = refund_funds(res, &info.sender, funds);
 
     let send = Cw721ReceiveMsg {
         sender: info.sender.to_string(),
@@ -253,7 +609,7 @@ pub fn execute_approve(
     token_id: String,
     spender: String,
     expires: Option<Expiration>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let modules = read_modules(deps.storage)?;
     let mod_res = modules.on_approve(
         &deps,
@@ -288,7 +644,7 @@ pub fn execute_revoke(
     info: MessageInfo,
     token_id: String,
     spender: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let modules = read_modules(deps.storage)?;
     let mod_res = modules.on_revoke(
         &deps,
@@ -318,7 +674,7 @@ fn execute_approve_all(
     info: MessageInfo,
     operator: String,
     expires: Option<Expiration>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let modules = read_modules(deps.storage)?;
     let mod_res = modules.on_approve_all(
         &deps,
@@ -349,7 +705,7 @@ fn execute_revoke_all(
     env: Env,
     info: MessageInfo,
     operator: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let modules = read_modules(deps.storage)?;
     let mod_res = modules.on_revoke_all(&deps, info.clone(), env, operator.clone())?;
 
@@ -371,9 +727,8 @@ fn execute_transfer_agreement(
     info: MessageInfo,
     token_id: String,
     purchaser: String,
-    amount: u128,
-    denom: String,
-) -> StdResult<Response> {
+    amount: Funds,
+) -> Result<Response, ContractError> {
     let modules = read_modules(deps.storage)?;
     let mod_res = modules.on_transfer_agreement(
         &deps,
@@ -381,21 +736,17 @@ fn execute_transfer_agreement(
         env.clone(),
         token_id.clone(),
         purchaser.clone(),
-        amount.clone(),
-        denom.clone(),
+        amount.amount().u128(),
+        amount.denom(),
     )?;
     let mut token = load_token(deps.storage, token_id.clone())?;
 
     require(
         info.sender.to_string().eq(&token.owner),
-        StdError::generic_err("Only the token owner can create a transfer agreement"),
-    )?;
-    require(
-        !token.archived,
-        StdError::generic_err("This token is archived and cannot be changed in any way."),
+        ContractError::TransferAgreementUnauthorized {},
     )?;
+    require(!token.archived, ContractError::TokenArchived {})?;
 
-    let amount = coin(amount, denom);
     let agreement = TransferAgreement {
         purchaser: purchaser.clone(),
         amount: amount.clone(),
@@ -416,26 +767,13 @@ fn execute_transfer_agreement(
 }
 
 fn execute_burn(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     token_id: String,
-) -> StdResult<Response> {
-    let token = load_token(deps.storage, token_id.clone())?;
-    require(
-        token.owner.eq(&info.sender.to_string()),
-        StdError::generic_err("Cannot burn a token you do not own"),
-    )?;
-    require(
-        !token.archived,
-        StdError::generic_err("This token is archived and cannot be changed in any way."),
-    )?;
-
+) -> Result<Response, ContractError> {
     let modules = read_modules(deps.storage)?;
-    let mod_res = modules.on_burn(&deps, info.clone(), env, token_id.clone())?;
-
-    TOKENS.remove(deps.storage, token_id.clone());
-    decrement_num_tokens(deps.storage)?;
+    let mod_res = burn(&mut deps, &env, &info, &modules, token_id.clone())?;
 
     Ok(Response::default()
         .add_submessages(mod_res.msgs)
@@ -447,21 +785,71 @@ fn execute_burn(
         ]))
 }
 
+fn execute_batch_burn(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_ids: Vec<String>,
+) -> Result<Response, ContractError> {
+    require(!token_ids.is_empty(), ContractError::EmptyBatch {})?;
+
+    let modules = read_modules(deps.storage)?;
+    let mut mod_res = HookResponse::default();
+    let mut event = Event::new("batch_burn");
+    // Module burn hooks are token specific and so are still run for each token
+    for token_id in token_ids.iter() {
+        mod_res = mod_res.add_resp(burn(&mut deps, &env, &info, &modules, token_id.clone())?);
+        event = event.add_attribute("token_id", token_id);
+    }
+
+    let res = Response::default()
+        .add_submessages(mod_res.msgs)
+        .add_events(mod_res.events)
+        .add_event(event)
+        .add_attributes(vec![
+            attr("action", "batch_burn"),
+            attr("count", token_ids.len().to_string()),
+            attr("sender", info.sender.to_string()),
+        ]);
+
+    add_receipt_message(deps.storage, res)
+}
+
+/// Removes a token owned by the sender, returning the response of the modules' burn hooks
+fn burn(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    modules: &Modules,
+    token_id: String,
+) -> Result<HookResponse, ContractError> {
+    let token = load_token(deps.storage, token_id.clone())?;
+    require(
+        token.owner.eq(&info.sender.to_string()),
+        ContractError::BurnUnauthorized {},
+    )?;
+    require(!token.archived, ContractError::TokenArchived {})?;
+
+    let mod_res = modules.on_burn(deps, info.clone(), env.clone(), token_id.clone())?;
+
+    burn_token(deps.storage, token_id)?;
+    decrement_num_tokens(deps.storage)?;
+
+    Ok(mod_res)
+}
+
 fn execute_archive(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     token_id: String,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut token = load_token(deps.storage, token_id.clone())?;
     require(
         token.owner.eq(&info.sender.to_string()),
-        StdError::generic_err("Cannot archive a token you do not own"),
-    )?;
-    require(
-        !token.archived,
-        StdError::generic_err("This token is archived and cannot be changed in any way."),
+        ContractError::ArchiveUnauthorized {},
     )?;
+    require(!token.archived, ContractError::TokenArchived {})?;
 
     let modules = read_modules(deps.storage)?;
     let mod_res = modules.on_archive(&deps, info.clone(), env, token_id.clone())?;
@@ -485,16 +873,13 @@ fn execute_update_pricing(
     info: MessageInfo,
     token_id: String,
     pricing: Option<Coin>,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let mut token = load_token(deps.storage, token_id.clone())?;
     require(
         token.owner.eq(&info.sender.to_string()),
-        StdError::generic_err("Cannot update pricing for a token you do not own"),
-    )?;
-    require(
-        !token.archived,
-        StdError::generic_err("This token is archived and cannot be changed in any way."),
+        ContractError::UpdatePricingUnauthorized {},
     )?;
+    require(!token.archived, ContractError::TokenArchived {})?;
 
     token.pricing = pricing.clone();
     TOKENS.save(deps.storage, token_id.clone(), &Some(token))?;
@@ -512,75 +897,535 @@ fn execute_update_pricing(
     ]))
 }
 
-fn transfer_nft(
+fn execute_update_metadata(
     deps: DepsMut,
-    env: &Env,
-    info: &MessageInfo,
-    recipient: &String,
-    token_id: &String,
-) -> StdResult<Response> {
-    let mut token = load_token(deps.storage, token_id.to_string())?;
+    info: MessageInfo,
+    token_id: String,
+    name: Option<String>,
+    description: Option<String>,
+    image: Option<String>,
+    metadata: Option<TokenMetadata>,
+) -> Result<Response, ContractError> {
+    let mut token = load_token(deps.storage, token_id.clone())?;
+    require(!token.archived, ContractError::TokenArchived {})?;
     require(
-        has_transfer_rights(deps.storage, env, info.sender.to_string(), &token)?,
-        StdError::generic_err("Address does not have transfer rights for this token"),
+        !is_metadata_frozen(deps.storage, &token)?,
+        ContractError::MetadataFrozen {},
     )?;
+    let config = CONFIG.load(deps.storage)?;
     require(
-        !token.archived,
-        StdError::generic_err("This token is archived and cannot be changed in any way."),
+        config
+            .metadata_update_policy
+            .is_authorized(&token, info.sender.as_str()),
+        ContractError::MetadataUpdateUnauthorized {},
     )?;
-    let owner = token.owner;
 
-    token.owner = recipient.to_string();
-    token.approvals = vec![];
+    if let Some(name) = name {
+        token.name = name;
+    }
+    if description.is_some() {
+        token.description = description;
+    }
+    if image.is_some() {
+        token.image = image;
+    }
+    if metadata.is_some() {
+        token.metadata = metadata;
+    }
+    save_token(deps.storage, token_id.clone(), &token)?;
 
-    let mut res = Response::new();
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "update_metadata"),
+        attr("token_id", token_id),
+        attr("sender", info.sender.to_string()),
+    ]))
+}
 
-    // [GLOBAL-02] Changing is_some() + .unwrap() to if let Some()
-    if let Some(transfer_agreement) = token.transfer_agreement.clone() {
-        res = transfer_agreement.on_transfer(&deps, info, env, owner, res)?;
+fn execute_freeze_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: Option<String>,
+) -> Result<Response, ContractError> {
+    match token_id {
+        None => {
+            require(
+                is_contract_owner(deps.storage, info.sender.to_string())?,
+                ContractError::Unauthorized {},
+            )?;
+            let mut config = CONFIG.load(deps.storage)?;
+            require(
+                !config.metadata_frozen,
+                ContractError::CollectionMetadataFrozen {},
+            )?;
+            config.metadata_frozen = true;
+            CONFIG.save(deps.storage, &config)?;
+
+            Ok(Response::default().add_attributes(vec![
+                attr("action", "freeze_metadata"),
+                attr("sender", info.sender.to_string()),
+            ]))
+        }
+        Some(token_id) => {
+            let token = load_token(deps.storage, token_id.clone())?;
+            require(!token.archived, ContractError::TokenArchived {})?;
+            require(
+                !is_metadata_frozen(deps.storage, &token)?,
+                ContractError::MetadataFrozen {},
+            )?;
+            let config = CONFIG.load(deps.storage)?;
+            require(
+                config
+                    .metadata_update_policy
+                    .is_authorized(&token, info.sender.as_str()),
+                ContractError::MetadataUpdateUnauthorized {},
+            )?;
+            FROZEN_METADATA.save(deps.storage, token_id.clone(), &true)?;
+
+            Ok(Response::default().add_attributes(vec![
+                attr("action", "freeze_metadata"),
+                attr("token_id", token_id),
+                attr("sender", info.sender.to_string()),
+            ]))
+        }
     }
-    TOKENS.save(deps.storage, token_id.to_string(), &Some(token))?;
-    Ok(res)
 }
 
-fn add_approval(
+fn execute_place_offer(
     deps: DepsMut,
-    info: &MessageInfo,
+    env: Env,
+    info: MessageInfo,
     token_id: String,
-    approval: Approval,
-) -> StdResult<()> {
-    let mut token = load_token(deps.storage, token_id.to_string())?;
+    expires: Expiration,
+) -> Result<Response, ContractError> {
+    let token = load_token(deps.storage, token_id.clone())?;
     require(
-        token.owner.eq(&info.sender.to_string()),
-        StdError::generic_err("Only the token owner can add approvals"),
+        !token.owner.eq(&info.sender.to_string()),
+        ContractError::OfferOnOwnToken {},
+    )?;
+    require(!token.archived, ContractError::TokenArchived {})?;
+    require(
+        !expires.is_expired(&env.block),
+        ContractError::OfferAlreadyExpired {},
+    )?;
+    require(
+        info.funds.len() == 1 && !info.funds[0].amount.is_zero(),
+        ContractError::InvalidOfferFunds {},
     )?;
+    let key = (token_id.clone(), info.sender.to_string());
     require(
-        !token.archived,
-        StdError::generic_err("This token is archived and cannot be changed in any way."),
+        OFFERS.may_load(deps.storage, key.clone())?.is_none(),
+        ContractError::OfferExists {},
     )?;
 
-    token.filter_approval(&approval.spender.clone());
+    let offer = Offer {
+        purchaser: info.sender.to_string(),
+        amount: info.funds[0].clone(),
+        expires,
+    };
+    OFFERS.save(deps.storage, key, &offer)?;
 
-    token.approvals.push(approval);
-    TOKENS.save(deps.storage, token_id.to_string(), &Some(token))?;
-    Ok(())
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "place_offer"),
+        attr("token_id", token_id),
+        attr("purchaser", offer.purchaser),
+        attr("amount", offer.amount.to_string()),
+    ]))
 }
 
-fn remove_approval(
+fn execute_accept_offer(
     deps: DepsMut,
-    info: &MessageInfo,
+    env: Env,
+    info: MessageInfo,
     token_id: String,
-    spender: &Addr,
-) -> StdResult<()> {
-    let mut token = load_token(deps.storage, token_id.to_string())?;
+    purchaser: String,
+) -> Result<Response, ContractError> {
+    let mut token = load_token(deps.storage, token_id.clone())?;
     require(
         token.owner.eq(&info.sender.to_string()),
-        StdError::generic_err("Only the token owner can remove approvals"),
+        ContractError::AcceptOfferUnauthorized {},
     )?;
+    require(!token.archived, ContractError::TokenArchived {})?;
+    let key = (token_id.clone(), purchaser.clone());
+    let offer = OFFERS
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::OfferNotFound {})?;
     require(
-        !token.archived,
-        StdError::generic_err("This token is archived and cannot be changed in any way."),
+        !offer.is_expired(&env.block),
+        ContractError::OfferExpired {},
     )?;
+    OFFERS.remove(deps.storage, key);
+
+    let modules = read_modules(deps.storage)?;
+    let mod_res = modules.on_transfer(
+        &deps,
+        info.clone(),
+        env.clone(),
+        purchaser.clone(),
+        token_id.clone(),
+    )?;
+
+    let owner = token.owner;
+    token.owner = purchaser.clone();
+    token.approvals = vec![];
+    token.transfer_agreement = None;
+    save_token(deps.storage, token_id.clone(), &token)?;
+
+    let agreement = TransferAgreement {
+        amount: Funds::Native(offer.amount.clone()),
+        purchaser: purchaser.clone(),
+    };
+    let res = Response::default()
+        .add_submessages(mod_res.msgs)
+        .add_events(mod_res.events)
+        .add_attributes(vec![
+            attr("action", "accept_offer"),
+            attr("token_id", token_id),
+            attr("purchaser", purchaser),
+            attr("amount", offer.amount.to_string()),
+            attr("sender", info.sender.to_string()),
+        ]);
+
+    agreement.on_escrowed_transfer(&deps, &info, &env, owner, token.royalty, res)
+}
+
+fn execute_cancel_offer(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let key = (token_id.clone(), info.sender.to_string());
+    let offer = OFFERS
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::OfferNotFound {})?;
+    OFFERS.remove(deps.storage, key);
+
+    Ok(Response::default()
+        .add_message(BankMsg::Send {
+            to_address: offer.purchaser.clone(),
+            amount: vec![offer.amount.clone()],
+        })
+        .add_attributes(vec![
+            attr("action", "cancel_offer"),
+            attr("token_id", token_id),
+            attr("purchaser", offer.purchaser),
+            attr("amount", offer.amount.to_string()),
+        ]))
+}
+
+fn execute_update_minter(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    require(
+        is_contract_owner(deps.storage, info.sender.to_string())?,
+        ContractError::Unauthorized {},
+    )?;
+    let minter = deps.api.addr_validate(&address)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    config.minter = minter.to_string();
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "update_minter"),
+        attr("minter", address),
+    ]))
+}
+
+fn execute_add_minter(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    require(
+        is_contract_owner(deps.storage, info.sender.to_string())?,
+        ContractError::Unauthorized {},
+    )?;
+    let minter = deps.api.addr_validate(&address)?;
+
+    require(
+        !has_role(deps.storage, &env.block, ROLE_MINTER, minter.as_str())?,
+        ContractError::AlreadyMinter {},
+    )?;
+    grant_role(deps.storage, &env.block, ROLE_MINTER, minter.as_str(), None)?;
+
+    Ok(Response::default()
+        .add_attributes(vec![attr("action", "add_minter"), attr("minter", address)]))
+}
+
+fn execute_remove_minter(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    require(
+        is_contract_owner(deps.storage, info.sender.to_string())?,
+        ContractError::Unauthorized {},
+    )?;
+
+    require(
+        has_role(deps.storage, &env.block, ROLE_MINTER, &address)?,
+        ContractError::NotAdditionalMinter {},
+    )?;
+    revoke_role(deps.storage, ROLE_MINTER, &address)?;
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "remove_minter"),
+        attr("minter", address),
+    ]))
+}
+
+fn execute_add_module(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    module: ModuleDefinition,
+) -> Result<Response, ContractError> {
+    require(
+        is_contract_owner(deps.storage, info.sender.to_string())?,
+        ContractError::Unauthorized {},
+    )?;
+    let mut modules = read_modules(deps.storage)?;
+    modules.module_defs.push(module.clone());
+    let event = Event::new("add_module")
+        .add_attribute("module", module.name())
+        .add_attribute("module_idx", (modules.module_defs.len() - 1).to_string());
+
+    update_modules(deps, env, info, modules, Some(module), event, "add_module")
+}
+
+fn execute_remove_module(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    module_idx: u64,
+) -> Result<Response, ContractError> {
+    require(
+        is_contract_owner(deps.storage, info.sender.to_string())?,
+        ContractError::Unauthorized {},
+    )?;
+    let mut modules = read_modules(deps.storage)?;
+    require(
+        (module_idx as usize) < modules.module_defs.len(),
+        ContractError::ModuleIndexOutOfRange {},
+    )?;
+    let removed = modules.module_defs.remove(module_idx as usize);
+    let event = Event::new("remove_module")
+        .add_attribute("module", removed.name())
+        .add_attribute("module_idx", module_idx.to_string());
+
+    update_modules(deps, env, info, modules, None, event, "remove_module")
+}
+
+fn execute_update_module(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    module_idx: u64,
+    module: ModuleDefinition,
+) -> Result<Response, ContractError> {
+    require(
+        is_contract_owner(deps.storage, info.sender.to_string())?,
+        ContractError::Unauthorized {},
+    )?;
+    let mut modules = read_modules(deps.storage)?;
+    require(
+        (module_idx as usize) < modules.module_defs.len(),
+        ContractError::ModuleIndexOutOfRange {},
+    )?;
+    modules.module_defs[module_idx as usize] = module.clone();
+    let event = Event::new("update_module")
+        .add_attribute("module", module.name())
+        .add_attribute("module_idx", module_idx.to_string());
+
+    update_modules(
+        deps,
+        env,
+        info,
+        modules,
+        Some(module),
+        event,
+        "update_module",
+    )
+}
+
+/// Validates and stores an updated set of modules, instantiating any module contract required by a new `module`.
+/// The change is recorded by the receipt module in place before the change, if there is one.
+/// Errors if the auction module would be removed while auctions are open, as auctioned tokens are only locked by the auction module.
+fn update_modules(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    modules: Modules,
+    module: Option<ModuleDefinition>,
+    event: Event,
+    action: &str,
+) -> Result<Response, ContractError> {
+    modules.validate()?;
+    let has_auction_module = modules
+        .module_defs
+        .iter()
+        .any(|m| matches!(m, ModuleDefinition::Auction { .. }));
+    if !has_auction_module {
+        require(
+            AUCTIONS
+                .range(deps.storage, None, None, Order::Ascending)
+                .next()
+                .is_none(),
+            ContractError::AuctionsOpen {},
+        )?;
+    }
+    let mod_res = match module {
+        Some(module) => module.as_module().on_instantiate(&deps, info, env)?,
+        None => HookResponse::default(),
+    };
+
+    let res = add_receipt_message(deps.storage, Response::new().add_event(event))?;
+    store_modules(deps.storage, modules)?;
+
+    Ok(res
+        .add_submessages(mod_res.msgs)
+        .add_events(mod_res.events)
+        .add_attribute("action", action))
+}
+
+/// Transfers a token, appending any agreed transfer payments to the given response.
+/// The payments for an agreed transfer are deducted from `funds`, erroring if they are insufficient.
+/// Any funds remaining afterwards should be refunded to the sender.
+/// A receipt for the agreed transfer is only generated if `generate_receipt` is set.
+#[allow(clippy::too_many_arguments)]
+fn transfer_nft(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    recipient: &String,
+    token_id: &String,
+    funds: &mut [Coin],
+    res_in: Response,
+    generate_receipt: bool,
+) -> Result<Response, ContractError> {
+    let token = load_token(deps.storage, token_id.to_string())?;
+    if let Some(agreement) = &token.transfer_agreement {
+        if let Funds::Cw20(_) = agreement.amount {
+            return Err(ContractError::Cw20PaymentRequired {});
+        }
+        let required = agreement.total_payments(
+            deps,
+            info,
+            env,
+            token.owner.clone(),
+            token.royalty.clone(),
+        )?;
+        match funds
+            .iter_mut()
+            .find(|coin| coin.denom == agreement.amount.denom())
+        {
+            Some(coin) if coin.amount >= required => coin.amount -= required,
+            _ => return Err(ContractError::InsufficientFunds {}),
+        }
+    }
+
+    complete_transfer(deps, env, info, recipient, token, res_in, generate_receipt)
+}
+
+/// Refunds any of the given funds that were not required to the sender
+fn refund_funds(res: Response, sender: &Addr, funds: Vec<Coin>) -> Response {
+    let refund: Vec<Coin> = funds
+        .into_iter()
+        .filter(|coin| !coin.amount.is_zero())
+        .collect();
+    if refund.is_empty() {
+        return res;
+    }
+
+    res.add_message(BankMsg::Send {
+        to_address: sender.to_string(),
+        amount: refund,
+    })
+}
+
+/// Transfers a loaded token once any payment for an agreed transfer has been checked.
+fn complete_transfer(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    recipient: &String,
+    mut token: Token,
+    res_in: Response,
+    generate_receipt: bool,
+) -> Result<Response, ContractError> {
+    require(
+        has_transfer_rights(deps.storage, env, info.sender.to_string(), &token)?,
+        ContractError::TransferUnauthorized {},
+    )?;
+    require(!token.archived, ContractError::TokenArchived {})?;
+    let owner = token.owner;
+
+    token.owner = recipient.to_string();
+    token.approvals = vec![];
+
+    let mut res = res_in;
+
+    // [GLOBAL-02] Changing is_some() + .unwrap() to if let Some()
+    if let Some(transfer_agreement) = token.transfer_agreement.clone() {
+        res = match generate_receipt {
+            true => transfer_agreement.on_transfer(
+                deps,
+                info,
+                env,
+                owner,
+                token.royalty.clone(),
+                res,
+            )?,
+            false => transfer_agreement.generate_transfer_response(
+                deps,
+                info,
+                env,
+                owner,
+                token.royalty.clone(),
+                res,
+            )?,
+        };
+    }
+    save_token(deps.storage, token.token_id.clone(), &token)?;
+    Ok(res)
+}
+
+fn add_approval(
+    deps: DepsMut,
+    info: &MessageInfo,
+    token_id: String,
+    approval: Approval,
+) -> Result<(), ContractError> {
+    let mut token = load_token(deps.storage, token_id.to_string())?;
+    require(
+        token.owner.eq(&info.sender.to_string()),
+        ContractError::AddApprovalUnauthorized {},
+    )?;
+    require(!token.archived, ContractError::TokenArchived {})?;
+
+    token.filter_approval(&approval.spender.clone());
+
+    token.approvals.push(approval);
+    TOKENS.save(deps.storage, token_id.to_string(), &Some(token))?;
+    Ok(())
+}
+
+fn remove_approval(
+    deps: DepsMut,
+    info: &MessageInfo,
+    token_id: String,
+    spender: &Addr,
+) -> Result<(), ContractError> {
+    let mut token = load_token(deps.storage, token_id.to_string())?;
+    require(
+        token.owner.eq(&info.sender.to_string()),
+        ContractError::RemoveApprovalUnauthorized {},
+    )?;
+    require(!token.archived, ContractError::TokenArchived {})?;
 
     token.filter_approval(spender);
 
@@ -589,32 +1434,79 @@ fn remove_approval(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
-        QueryMsg::OwnerOf { token_id } => to_binary(&query_owner(deps, env, token_id)?),
+        QueryMsg::OwnerOf { token_id } => Ok(to_binary(&query_owner(deps, env, token_id)?)?),
         QueryMsg::ApprovedForAll {
             start_after,
             owner,
             include_expired,
             limit,
-        } => to_binary(&query_all_approvals(
+        } => Ok(to_binary(&query_all_approvals(
             deps,
             env,
             owner,
             include_expired.unwrap_or_default(),
             start_after,
             limit,
-        )?),
-        QueryMsg::NumTokens {} => to_binary(&query_num_tokens(deps, env)?),
-        QueryMsg::NftInfo { token_id } => to_binary(&query_nft_info(deps, token_id)?),
-        QueryMsg::AllNftInfo { token_id } => to_binary(&query_all_nft_info(deps, env, token_id)?),
-        QueryMsg::ContractInfo {} => to_binary(&query_contract_info(deps)?),
-        QueryMsg::ModuleInfo {} => to_binary(&query_module_info(deps)?),
-        QueryMsg::ContractOwner {} => to_binary(&query_contract_owner(deps)?),
+        )?)?),
+        QueryMsg::NumTokens {} => Ok(to_binary(&query_num_tokens(deps, env)?)?),
+        QueryMsg::NftInfo { token_id } => Ok(to_binary(&query_nft_info(deps, token_id)?)?),
+        QueryMsg::AllNftInfo { token_id } => {
+            Ok(to_binary(&query_all_nft_info(deps, env, token_id)?)?)
+        }
+        QueryMsg::Tokens {
+            owner,
+            start_after,
+            limit,
+        } => Ok(to_binary(&query_tokens(deps, owner, start_after, limit)?)?),
+        QueryMsg::AllTokens { start_after, limit } => {
+            Ok(to_binary(&query_all_tokens(deps, start_after, limit)?)?)
+        }
+        QueryMsg::ContractInfo {} => Ok(to_binary(&query_contract_info(deps)?)?),
+        QueryMsg::ModuleInfo {} => Ok(to_binary(&query_module_info(deps)?)?),
+        QueryMsg::ContractOwner {} => Ok(to_binary(&query_contract_owner(deps)?)?),
+        QueryMsg::PendingOwner {} => Ok(to_binary(&query_pending_owner(deps)?)?),
+        QueryMsg::Version {} => Ok(to_binary(&query_version(deps)?)?),
+        QueryMsg::HasRole { role, address } => {
+            Ok(to_binary(&query_has_role(deps, env, role, address)?)?)
+        }
+        QueryMsg::RoleMembers {
+            role,
+            start_after,
+            limit,
+        } => Ok(to_binary(&query_role_members(
+            deps,
+            env,
+            role,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::Minters { start_after, limit } => {
+            Ok(to_binary(&query_minters(deps, env, start_after, limit)?)?)
+        }
+        QueryMsg::RoyaltyInfo {
+            token_id,
+            sale_price,
+        } => Ok(to_binary(&query_royalty_info(deps, token_id, sale_price)?)?),
+        QueryMsg::CheckRoyalties { token_id } => {
+            Ok(to_binary(&query_check_royalties(deps, token_id)?)?)
+        }
+        QueryMsg::Auction { token_id } => Ok(to_binary(&query_auction(deps, env, token_id)?)?),
+        QueryMsg::Offers {
+            token_id,
+            start_after,
+            limit,
+        } => Ok(to_binary(&query_offers(
+            deps,
+            token_id,
+            start_after,
+            limit,
+        )?)?),
     }
 }
 
-fn query_owner(deps: Deps, _env: Env, token_id: String) -> StdResult<OwnerOfResponse> {
+fn query_owner(deps: Deps, _env: Env, token_id: String) -> Result<OwnerOfResponse, ContractError> {
     let token = load_token(deps.storage, token_id.to_string())?;
     Ok(OwnerOfResponse {
         owner: token.owner.clone(),
@@ -632,7 +1524,7 @@ fn query_all_approvals(
     include_expired: bool,
     start_after: Option<String>,
     limit: Option<u32>,
-) -> StdResult<ApprovedForAllResponse> {
+) -> Result<ApprovedForAllResponse, ContractError> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let start_addr = maybe_addr(deps.api, start_after)?;
     let start = start_addr.map(|addr| Bound::exclusive(addr.as_ref()));
@@ -647,7 +1539,7 @@ fn query_all_approvals(
     Ok(ApprovedForAllResponse { operators: res? })
 }
 
-fn query_num_tokens(deps: Deps, _env: Env) -> StdResult<NumTokensResponse> {
+fn query_num_tokens(deps: Deps, _env: Env) -> Result<NumTokensResponse, ContractError> {
     let num_tokens = NUM_TOKENS.load(deps.storage).unwrap_or_default();
     Ok(NumTokensResponse { count: num_tokens })
 }
@@ -655,9 +1547,10 @@ fn query_num_tokens(deps: Deps, _env: Env) -> StdResult<NumTokensResponse> {
 fn query_nft_info(
     deps: Deps,
     token_id: String,
-) -> StdResult<NftInfoResponse<NftInfoResponseExtension>> {
+) -> Result<NftInfoResponse<NftInfoResponseExtension>, ContractError> {
     let token = load_token(deps.storage, token_id)?;
     let extension = NftInfoResponseExtension {
+        metadata_frozen: is_metadata_frozen(deps.storage, &token)?,
         metadata: token.metadata,
         archived: token.archived,
         transfer_agreement: token.transfer_agreement,
@@ -676,46 +1569,197 @@ fn query_all_nft_info(
     deps: Deps,
     env: Env,
     token_id: String,
-) -> StdResult<AllNftInfoResponse<NftInfoResponseExtension>> {
+) -> Result<AllNftInfoResponse<NftInfoResponseExtension>, ContractError> {
     let access = query_owner(deps, env, token_id.clone())?;
     let info = query_nft_info(deps, token_id)?;
 
     Ok(AllNftInfoResponse { access, info })
 }
 
-fn query_contract_info(deps: Deps) -> StdResult<ContractInfoResponse> {
-    let config = CONFIG.load(deps.storage)?;
-    Ok(ContractInfoResponse {
-        name: config.name,
-        symbol: config.symbol,
-    })
+fn query_tokens(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<TokensResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let tokens: StdResult<Vec<String>> = OWNED_TOKENS
+        .prefix(owner)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|k| String::from_utf8(k).map_err(StdError::from))
+        .collect();
+    Ok(TokensResponse { tokens: tokens? })
 }
 
-fn query_module_info(deps: Deps) -> StdResult<ModuleInfoResponse> {
-    let modules = read_modules(deps.storage)?;
-    let contracts: Vec<ModuleContract> = modules
-        .module_defs
-        .iter()
-        .map(|def| {
-            let name = def.name();
-            let addr = def.as_module().get_contract_address(deps.storage);
+fn query_all_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<TokensResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
 
-            ModuleContract {
-                module: name,
-                contract: addr,
-            }
-        })
+    let tokens: StdResult<Vec<String>> = TOKENS
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|r| r.is_err() || r.as_ref().unwrap().1.is_some())
+        .take(limit)
+        .map(|item| item.and_then(|(k, _)| String::from_utf8(k).map_err(StdError::from)))
         .collect();
+    Ok(TokensResponse { tokens: tokens? })
+}
 
-    Ok(ModuleInfoResponse {
-        modules: modules.module_defs,
-        contracts,
+fn query_offers(
+    deps: Deps,
+    token_id: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<OffersResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let offers: StdResult<Vec<Offer>> = OFFERS
+        .prefix(token_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, offer)| offer))
+        .collect();
+    Ok(OffersResponse { offers: offers? })
+}
+
+fn query_contract_info(deps: Deps) -> Result<ContractInfoResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ContractInfoResponse {
+        name: config.name,
+        symbol: config.symbol,
+        max_supply: config.max_supply,
+        mint_start: config.mint_start,
+        mint_end: config.mint_end,
     })
 }
 
-/**
-The next few functions are taken from the CW721-base contract:
-https://github.com/CosmWasm/cw-plus/tree/main/contracts/cw721-base
+fn query_minters(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<MintersResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let config = CONFIG.load(deps.storage)?;
+    let additional_minters = role_members(
+        deps.storage,
+        &env.block,
+        ROLE_MINTER,
+        start_after,
+        Some(limit),
+    )?
+    .into_iter()
+    .map(|member| member.address)
+    .collect();
+    Ok(MintersResponse {
+        minter: config.minter,
+        additional_minters,
+    })
+}
+
+fn query_royalty_info(
+    deps: Deps,
+    token_id: String,
+    sale_price: Coin,
+) -> Result<RoyaltyInfoResponse, ContractError> {
+    let royalties = match token_royalty(deps.storage, token_id)? {
+        Some(royalty) => {
+            let fee = calculate_rounded_fee(
+                royalty.rate,
+                sale_price.clone(),
+                royalty.rounding.unwrap_or_default(),
+            )?;
+            // Matches the checks made when the royalty is paid for an agreed transfer
+            require(
+                fee.denom == sale_price.denom,
+                ContractError::RoyaltyDenomMismatch {},
+            )?;
+            require(
+                fee.amount <= sale_price.amount,
+                ContractError::NotEnoughFundsToDeduct {},
+            )?;
+            calculate_shares(Funds::Native(fee.clone()), &royalty.receivers)?
+                .into_iter()
+                .filter(|share| !share.amount.amount().is_zero())
+                .map(|share| RoyaltyPayment {
+                    receiver: share.receiver,
+                    amount: coin(share.amount.amount().u128(), fee.denom.clone()),
+                })
+                .collect()
+        }
+        None => vec![],
+    };
+
+    Ok(RoyaltyInfoResponse { royalties })
+}
+
+fn query_check_royalties(
+    deps: Deps,
+    token_id: String,
+) -> Result<CheckRoyaltiesResponse, ContractError> {
+    Ok(CheckRoyaltiesResponse {
+        royalty_payments: token_royalty(deps.storage, token_id)?.is_some(),
+    })
+}
+
+/// The royalty applied to agreed transfers of a token, the token's own royalty taking the place of the contract's royalty module
+fn token_royalty(
+    storage: &dyn Storage,
+    token_id: String,
+) -> Result<Option<Royalty>, ContractError> {
+    let token = load_token(storage, token_id)?;
+    match token.royalty {
+        Some(royalty) => Ok(Some(royalty)),
+        None => get_royalty_module(storage),
+    }
+}
+
+fn query_module_info(deps: Deps) -> Result<ModuleInfoResponse, ContractError> {
+    let modules = read_modules(deps.storage)?;
+    let contracts: Vec<ModuleContract> = modules
+        .module_defs
+        .iter()
+        .map(|def| {
+            let name = def.name();
+            let addr = def.as_module().get_contract_address(deps.storage);
+
+            ModuleContract {
+                module: name,
+                contract: addr,
+            }
+        })
+        .collect();
+    let execution_order: Vec<ModuleOrder> = modules
+        .execution_order()
+        .into_iter()
+        .map(|idx| {
+            let def = &modules.module_defs[idx];
+
+            ModuleOrder {
+                module_idx: idx as u64,
+                module: def.name(),
+                priority: def.priority(),
+            }
+        })
+        .collect();
+
+    Ok(ModuleInfoResponse {
+        modules: modules.module_defs,
+        contracts,
+        execution_order,
+    })
+}
+
+/**
+The next few functions are taken from the CW721-base contract:
+https://github.com/CosmWasm/cw-plus/tree/main/contracts/cw721-base
 */
 fn parse_approval(item: StdResult<Pair<Expiration>>) -> StdResult<cw721::Approval> {
     item.and_then(|(k, expires)| {
@@ -740,19 +1784,70 @@ fn humanize_approval(approval: &Approval) -> cw721::Approval {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let previous = migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    migrate_modules(deps.storage)?;
+    // Contracts without a stored version predate the current token format
+    if previous.is_none() {
+        TOKEN_MIGRATION.save(deps.storage, &None)?;
+        // Burnt tokens were not counted, so the number of existing tokens is the best available count
+        let num_tokens = NUM_TOKENS.may_load(deps.storage)?.unwrap_or_default();
+        MINTED_TOKENS.save(deps.storage, &num_tokens)?;
+    }
+    // Tokens are migrated in batches so that large collections do not exceed the gas limit
+    let pending = is_token_migration_pending(deps.storage)?
+        && migrate_tokens(deps.storage, migration_limit(msg.limit))?;
+
+    Ok(migrate_response(previous, CONTRACT_VERSION)
+        .add_attribute("token_migration_pending", pending.to_string()))
+}
+
+fn execute_migrate_tokens(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    require(
+        is_contract_owner(deps.storage, info.sender.to_string())?,
+        ContractError::Unauthorized {},
+    )?;
+    require(
+        is_token_migration_pending(deps.storage)?,
+        ContractError::NoTokenMigration {},
+    )?;
+    let pending = migrate_tokens(deps.storage, migration_limit(limit))?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate_tokens"),
+        attr("token_migration_pending", pending.to_string()),
+    ]))
+}
+
+const DEFAULT_MIGRATION_LIMIT: u32 = 100;
+
+/// The number of tokens to migrate in a single batch, at least one so that the migration always progresses
+fn migration_limit(limit: Option<u32>) -> u32 {
+    limit.unwrap_or(DEFAULT_MIGRATION_LIMIT).max(1)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use andromeda_protocol::token::{Approval, ExecuteMsg};
+    use andromeda_protocol::receipt::{ExecuteMsg as ReceiptExecuteMsg, Receipt};
+    use andromeda_protocol::{
+        modules::{
+            custom::{CustomHook, CustomHookMsg, HookForward, HookMsg, HookType},
+            AddressWeight, FlatRate, ModuleDefinition, Rate,
+        },
+        testing::mock_querier::mock_dependencies_custom,
+        token::{Approval, ExecuteMsg, MetadataType, MetadataUpdatePolicy},
+    };
     use cosmwasm_std::{
         from_binary,
         testing::{mock_dependencies, mock_env, mock_info},
-        Api, BankMsg, Uint128,
+        Api, BankMsg, SubMsg, Uint128, WasmMsg,
     };
+    use cw20::{Cw20Coin, Cw20ExecuteMsg};
 
     const TOKEN_NAME: &str = "test";
     const TOKEN_SYMBOL: &str = "T";
@@ -766,6 +1861,11 @@ mod tests {
                     name: TOKEN_NAME.to_string(),
                     symbol: TOKEN_SYMBOL.to_string(),
                     minter,
+                    max_supply: None,
+                    mint_start: None,
+                    mint_end: None,
+                    metadata_update_policy: MetadataUpdatePolicy::Publisher,
+                    metadata_frozen: false,
                 },
             )
             .unwrap()
@@ -780,6 +1880,10 @@ mod tests {
             name: TOKEN_NAME.to_string(),
             symbol: TOKEN_SYMBOL.to_string(),
             modules: vec![],
+            max_supply: None,
+            mint_start: None,
+            mint_end: None,
+            metadata_update_policy: None,
             minter: String::from("creator"),
         };
 
@@ -798,13 +1902,17 @@ mod tests {
             name: TOKEN_NAME.to_string(),
             symbol: TOKEN_SYMBOL.to_string(),
             modules: vec![],
+            max_supply: None,
+            mint_start: None,
+            mint_end: None,
+            metadata_update_policy: None,
             minter: String::from(BLACK_LIST_ADDRESS),
         };
 
         let env = mock_env();
 
         let res = instantiate(deps.as_mut(), env, info, msg).unwrap_err();
-        let err = StdError::generic_err("Minter is on blacklist. Not allowed to mint.");
+        let err = ContractError::MinterBlacklisted {};
         assert_eq!(err, res);
     }
 
@@ -824,9 +1932,10 @@ mod tests {
             metadata: None,
             image: None,
             pricing: None,
+            royalty: None,
         };
 
-        store_mock_config(deps.as_mut(), String::from("minter"));
+        store_mock_config(deps.as_mut(), creator.clone());
 
         let msg = ExecuteMsg::Mint(mint_msg);
 
@@ -874,6 +1983,7 @@ mod tests {
             archived: false,
             pricing: None,
             publisher: minter.to_string(),
+            royalty: None,
         };
 
         TOKENS
@@ -883,10 +1993,7 @@ mod tests {
         let unauth_info = mock_info("anyone", &[]);
 
         let unauth_res = execute(deps.as_mut(), env.clone(), unauth_info, msg.clone()).unwrap_err();
-        assert_eq!(
-            unauth_res,
-            StdError::generic_err("Address does not have transfer rights for this token")
-        );
+        assert_eq!(unauth_res, ContractError::TransferUnauthorized {});
 
         let notfound_msg = ExecuteMsg::TransferNft {
             recipient: recipient.to_string(),
@@ -897,7 +2004,9 @@ mod tests {
 
         assert_eq!(
             notfound_res,
-            StdError::not_found("core::option::Option<andromeda_protocol::token::Token>")
+            ContractError::Std(StdError::not_found(
+                "core::option::Option<andromeda_protocol::token::Token>"
+            ))
         );
 
         let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
@@ -926,6 +2035,7 @@ mod tests {
             image: None,
             pricing: None,
             publisher: minter.to_string(),
+            royalty: None,
         };
         let msg = ExecuteMsg::TransferNft {
             recipient: recipient.to_string(),
@@ -979,6 +2089,7 @@ mod tests {
             image: None,
             pricing: None,
             publisher: minter.to_string(),
+            royalty: None,
         };
         let msg = ExecuteMsg::TransferNft {
             recipient: recipient.to_string(),
@@ -1041,13 +2152,14 @@ mod tests {
             approvals: vec![],
             transfer_agreement: Some(TransferAgreement {
                 purchaser: recipient.to_string(),
-                amount: amount.clone(),
+                amount: Funds::Native(amount.clone()),
             }),
             metadata: None,
             archived: false,
             image: None,
             pricing: None,
             publisher: minter.to_string(),
+            royalty: None,
         };
 
         TOKENS
@@ -1058,6 +2170,16 @@ mod tests {
             )
             .unwrap();
 
+        // The agreed amount must be sent with the transfer
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+        assert_eq!(res, ContractError::InsufficientFunds {});
+
+        let info = mock_info(minter, &[coin(99, "uluna")]);
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+        assert_eq!(res, ContractError::InsufficientFunds {});
+
+        // Any excess funds are refunded to the sender
+        let info = mock_info(minter, &[coin(120, "uluna"), coin(5, "uusd")]);
         let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
         assert_eq!(
             Response::new()
@@ -1065,6 +2187,10 @@ mod tests {
                     to_address: minter.to_string(),
                     amount: vec![amount.clone()]
                 })
+                .add_message(BankMsg::Send {
+                    to_address: minter.to_string(),
+                    amount: vec![coin(20, "uluna"), coin(5, "uusd")]
+                })
                 .add_event(token.transfer_agreement.unwrap().generate_event())
                 .add_attributes(vec![
                     attr("action", "transfer"),
@@ -1076,6 +2202,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_agreed_transfer_custom_message() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let minter = "minter";
+        let purchaser = "purchaser";
+        let info = mock_info(minter, &[]);
+        store_mock_config(deps.as_mut(), minter.to_string());
+        store_modules(
+            deps.as_mut().storage,
+            Modules::new(vec![ModuleDefinition::Custom {
+                address: "custom_contract".to_string(),
+                hooks: vec![CustomHook {
+                    hook: HookType::AgreedTransfer,
+                    forward: HookForward::Message,
+                }],
+                priority: None,
+            }]),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["1"]),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::TransferAgreement {
+                token_id: "1".to_string(),
+                amount: Funds::Native(coin(100, "uluna")),
+                purchaser: purchaser.to_string(),
+            },
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(purchaser, &[coin(100, "uluna")]),
+            ExecuteMsg::TransferNft {
+                recipient: purchaser.to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: minter.to_string(),
+                    amount: vec![coin(100, "uluna")],
+                }),
+                SubMsg::new(WasmMsg::Execute {
+                    contract_addr: "custom_contract".to_string(),
+                    msg: to_binary(&CustomHookMsg::Hook(HookMsg::OnAgreedTransfer {
+                        sender: purchaser.to_string(),
+                        owner: minter.to_string(),
+                        purchaser: purchaser.to_string(),
+                        amount: Funds::Native(coin(100, "uluna")),
+                    }))
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ]
+        );
+    }
+
     #[test]
     fn test_approve() {
         let mut deps = mock_dependencies(&[]);
@@ -1103,6 +2301,7 @@ mod tests {
             image: None,
             pricing: None,
             publisher: sender.to_string(),
+            royalty: None,
         };
 
         TOKENS
@@ -1146,6 +2345,7 @@ mod tests {
             image: None,
             pricing: None,
             publisher: sender.to_string(),
+            royalty: None,
         };
 
         TOKENS
@@ -1178,6 +2378,7 @@ mod tests {
             metadata: None,
             image: None,
             pricing: None,
+            royalty: None,
         });
         execute(deps.as_mut(), env.clone(), info.clone(), mint_msg).unwrap();
 
@@ -1193,10 +2394,7 @@ mod tests {
         )
         .unwrap_err();
 
-        assert_eq!(
-            err,
-            StdError::generic_err("Address does not have transfer rights for this token"),
-        );
+        assert_eq!(err, ContractError::TransferUnauthorized {},);
 
         let approve_all_msg = ExecuteMsg::ApproveAll {
             operator: operator.to_string(),
@@ -1236,6 +2434,7 @@ mod tests {
             metadata: None,
             image: None,
             pricing: None,
+            royalty: None,
         });
         execute(deps.as_mut(), env.clone(), info.clone(), mint_msg).unwrap();
 
@@ -1268,10 +2467,7 @@ mod tests {
         };
         let err = execute(deps.as_mut(), env, operator_info, transfer_msg).unwrap_err();
 
-        assert_eq!(
-            err,
-            StdError::generic_err("Address does not have transfer rights for this token"),
-        );
+        assert_eq!(err, ContractError::TransferUnauthorized {},);
     }
 
     #[test]
@@ -1291,6 +2487,10 @@ mod tests {
             symbol: "TS".to_string(),
             minter: minter.to_string(),
             modules: vec![],
+            max_supply: None,
+            mint_start: None,
+            mint_end: None,
+            metadata_update_policy: None,
         };
         instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg).unwrap();
 
@@ -1302,13 +2502,13 @@ mod tests {
             metadata,
             image: None,
             pricing: None,
+            royalty: None,
         });
         execute(deps.as_mut(), env.clone(), info.clone(), mint_msg).unwrap();
 
         let transfer_agreement_msg = ExecuteMsg::TransferAgreement {
             token_id: token_id.clone(),
-            denom: denom.to_string(),
-            amount: Uint128::from(amount),
+            amount: Funds::Native(coin(amount.u128(), denom)),
             purchaser: purchaser.to_string(),
         };
         execute(
@@ -1327,7 +2527,7 @@ mod tests {
         let agreement = token_res.extension.transfer_agreement.unwrap();
 
         assert_eq!(agreement.purchaser, purchaser);
-        assert_eq!(agreement.amount, coin(amount.u128(), denom))
+        assert_eq!(agreement.amount, Funds::Native(coin(amount.u128(), denom)))
     }
 
     #[test]
@@ -1347,6 +2547,7 @@ mod tests {
             metadata: None,
             image: None,
             pricing: None,
+            royalty: None,
         };
 
         let msg = ExecuteMsg::Mint(mint_msg);
@@ -1360,10 +2561,7 @@ mod tests {
 
         let resp = execute(deps.as_mut(), env.clone(), unauth_info, burn_msg.clone()).unwrap_err();
 
-        assert_eq!(
-            resp,
-            StdError::generic_err("Cannot burn a token you do not own")
-        );
+        assert_eq!(resp, ContractError::BurnUnauthorized {});
 
         execute(deps.as_mut(), env.clone(), info, burn_msg).unwrap();
 
@@ -1375,7 +2573,9 @@ mod tests {
 
         assert_eq!(
             query_res,
-            StdError::not_found("core::option::Option<andromeda_protocol::token::Token>")
+            ContractError::Std(StdError::not_found(
+                "core::option::Option<andromeda_protocol::token::Token>"
+            ))
         )
     }
 
@@ -1396,6 +2596,7 @@ mod tests {
             metadata: None,
             image: None,
             pricing: None,
+            royalty: None,
         };
 
         let msg = ExecuteMsg::Mint(mint_msg);
@@ -1410,10 +2611,7 @@ mod tests {
         let resp =
             execute(deps.as_mut(), env.clone(), unauth_info, archive_msg.clone()).unwrap_err();
 
-        assert_eq!(
-            resp,
-            StdError::generic_err("Cannot archive a token you do not own")
-        );
+        assert_eq!(resp, ContractError::ArchiveUnauthorized {});
 
         execute(
             deps.as_mut(),
@@ -1424,10 +2622,7 @@ mod tests {
         .unwrap();
 
         let archived_resp = execute(deps.as_mut(), env.clone(), info, archive_msg).unwrap_err();
-        assert_eq!(
-            archived_resp,
-            StdError::generic_err("This token is archived and cannot be changed in any way.")
-        );
+        assert_eq!(archived_resp, ContractError::TokenArchived {});
 
         let token_query = QueryMsg::NftInfo {
             token_id: token_id.to_string(),
@@ -1454,6 +2649,7 @@ mod tests {
             metadata: None,
             image: None,
             pricing: None,
+            royalty: None,
         };
 
         let msg = ExecuteMsg::Mint(mint_msg);
@@ -1469,10 +2665,7 @@ mod tests {
 
         let resp =
             execute(deps.as_mut(), env.clone(), unauth_info, update_msg.clone()).unwrap_err();
-        assert_eq!(
-            resp,
-            StdError::generic_err("Cannot update pricing for a token you do not own")
-        );
+        assert_eq!(resp, ContractError::UpdatePricingUnauthorized {});
 
         let resp = execute(deps.as_mut(), env.clone(), info.clone(), update_msg).unwrap();
         let expected = Response::default().add_attributes(vec![
@@ -1489,4 +2682,1949 @@ mod tests {
         let token_res: NftInfoResponse<NftInfoResponseExtension> = from_binary(&res).unwrap();
         assert_eq!(token_res.extension.pricing.unwrap(), pricing)
     }
+
+    #[test]
+    fn test_query_tokens() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let minter = "minter";
+        let recipient = "recipient";
+        let info = mock_info(minter, &[]);
+        store_mock_config(deps.as_mut(), minter.to_string());
+
+        for token_id in ["1", "2", "3"] {
+            let mint_msg = ExecuteMsg::Mint(MintMsg {
+                token_id: token_id.to_string(),
+                owner: minter.to_string(),
+                description: None,
+                name: "TestToken".to_string(),
+                metadata: None,
+                image: None,
+                pricing: None,
+                royalty: None,
+            });
+            execute(deps.as_mut(), env.clone(), info.clone(), mint_msg).unwrap();
+        }
+
+        let transfer_msg = ExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id: "2".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), transfer_msg).unwrap();
+
+        let burn_msg = ExecuteMsg::Burn {
+            token_id: "3".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info, burn_msg).unwrap();
+
+        let query_msg = QueryMsg::Tokens {
+            owner: minter.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res: TokensResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(res.tokens, vec!["1".to_string()]);
+
+        let query_msg = QueryMsg::Tokens {
+            owner: recipient.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res: TokensResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(res.tokens, vec!["2".to_string()]);
+
+        let query_msg = QueryMsg::AllTokens {
+            start_after: None,
+            limit: None,
+        };
+        let res: TokensResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(res.tokens, vec!["1".to_string(), "2".to_string()]);
+
+        let query_msg = QueryMsg::AllTokens {
+            start_after: Some("1".to_string()),
+            limit: Some(1),
+        };
+        let res: TokensResponse =
+            from_binary(&query(deps.as_ref(), env, query_msg).unwrap()).unwrap();
+        assert_eq!(res.tokens, vec!["2".to_string()]);
+    }
+
+    #[test]
+    fn test_mint_minter_only() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let minter = "minter";
+        let owner = "owner";
+        store_mock_config(deps.as_mut(), minter.to_string());
+
+        let mint_msg = ExecuteMsg::Mint(MintMsg {
+            token_id: "1".to_string(),
+            owner: owner.to_string(),
+            description: None,
+            name: "TestToken".to_string(),
+            metadata: None,
+            image: None,
+            pricing: None,
+            royalty: None,
+        });
+
+        let unauth_info = mock_info("anyone", &[]);
+        let resp = execute(deps.as_mut(), env.clone(), unauth_info, mint_msg.clone()).unwrap_err();
+        assert_eq!(resp, ContractError::MintUnauthorized {});
+
+        let info = mock_info(minter, &[]);
+        execute(deps.as_mut(), env, info, mint_msg).unwrap();
+
+        let token = load_token(deps.as_ref().storage, "1".to_string()).unwrap();
+        assert_eq!(token.owner, owner.to_string());
+        assert_eq!(token.publisher, minter.to_string());
+    }
+
+    #[test]
+    fn test_manage_minters() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let owner = "owner";
+        let info = mock_info(owner, &[]);
+        let new_minter = "newminter";
+        let extra_minter = "extraminter";
+        store_mock_config(deps.as_mut(), owner.to_string());
+        CONTRACT_OWNER
+            .save(deps.as_mut().storage, &owner.to_string())
+            .unwrap();
+
+        let add_msg = ExecuteMsg::AddMinter {
+            address: extra_minter.to_string(),
+        };
+        let unauth_info = mock_info("anyone", &[]);
+        let resp = execute(deps.as_mut(), env.clone(), unauth_info, add_msg.clone()).unwrap_err();
+        assert_eq!(resp, ContractError::Unauthorized {});
+
+        execute(deps.as_mut(), env.clone(), info.clone(), add_msg.clone()).unwrap();
+        let resp = execute(deps.as_mut(), env.clone(), info.clone(), add_msg).unwrap_err();
+        assert_eq!(resp, ContractError::AlreadyMinter {});
+
+        let update_msg = ExecuteMsg::UpdateMinter {
+            address: new_minter.to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), update_msg).unwrap();
+
+        let minters_msg = |start_after: Option<&str>, limit: Option<u32>| QueryMsg::Minters {
+            start_after: start_after.map(String::from),
+            limit,
+        };
+        let res = query(deps.as_ref(), env.clone(), minters_msg(None, None)).unwrap();
+        let minters: MintersResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            minters,
+            MintersResponse {
+                minter: new_minter.to_string(),
+                additional_minters: vec![extra_minter.to_string()],
+            }
+        );
+
+        // Additional minters are paginated
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::AddMinter {
+                address: "otherminter".to_string(),
+            },
+        )
+        .unwrap();
+        let res = query(deps.as_ref(), env.clone(), minters_msg(None, Some(1))).unwrap();
+        let minters: MintersResponse = from_binary(&res).unwrap();
+        assert_eq!(minters.additional_minters, vec![extra_minter.to_string()]);
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            minters_msg(Some(extra_minter), None),
+        )
+        .unwrap();
+        let minters: MintersResponse = from_binary(&res).unwrap();
+        assert_eq!(minters.additional_minters, vec!["otherminter".to_string()]);
+
+        let extra_info = mock_info(extra_minter, &[]);
+        let mint_msg = ExecuteMsg::Mint(MintMsg {
+            token_id: "1".to_string(),
+            owner: extra_minter.to_string(),
+            description: None,
+            name: "TestToken".to_string(),
+            metadata: None,
+            image: None,
+            pricing: None,
+            royalty: None,
+        });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            extra_info.clone(),
+            mint_msg.clone(),
+        )
+        .unwrap();
+
+        let remove_msg = ExecuteMsg::RemoveMinter {
+            address: extra_minter.to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info, remove_msg).unwrap();
+
+        let resp = execute(deps.as_mut(), env, extra_info, mint_msg).unwrap_err();
+        assert_eq!(resp, ContractError::MintUnauthorized {});
+    }
+
+    #[test]
+    fn test_manage_modules() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let owner = "owner";
+        let info = mock_info(owner, &[]);
+        store_mock_config(deps.as_mut(), owner.to_string());
+        CONTRACT_OWNER
+            .save(deps.as_mut().storage, &owner.to_string())
+            .unwrap();
+        store_modules(
+            deps.as_mut().storage,
+            Modules::new(vec![ModuleDefinition::Receipt {
+                address: Some("receipt_contract".to_string()),
+                code_id: None,
+                moderators: None,
+            }]),
+        )
+        .unwrap();
+
+        let tax = ModuleDefinition::Taxable {
+            rate: Rate::Percent(2),
+            receivers: vec![AddressWeight::new("tax_receiver", 1)],
+            description: None,
+            rounding: None,
+        };
+        let add_msg = ExecuteMsg::AddModule {
+            module: tax.clone(),
+        };
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            add_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::Unauthorized {});
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), add_msg).unwrap();
+        let event = Event::new("add_module")
+            .add_attribute("module", "tax")
+            .add_attribute("module_idx", "1");
+        assert_eq!(res.events, vec![event.clone()]);
+        // The change is recorded by the receipt module
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "receipt_contract".to_string(),
+                msg: to_binary(&ReceiptExecuteMsg::StoreReceipt {
+                    receipt: Receipt {
+                        events: vec![event],
+                    },
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::AddModule {
+                module: ModuleDefinition::Receipt {
+                    address: Some("other_receipt_contract".to_string()),
+                    code_id: None,
+                    moderators: None,
+                },
+            },
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::DuplicateReceiptModule {});
+
+        let updated_tax = ModuleDefinition::Taxable {
+            rate: Rate::Percent(5),
+            receivers: vec![AddressWeight::new("tax_receiver", 1)],
+            description: None,
+            rounding: None,
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::UpdateModule {
+                module_idx: 1,
+                module: updated_tax.clone(),
+            },
+        )
+        .unwrap();
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::UpdateModule {
+                module_idx: 1,
+                module: ModuleDefinition::Taxable {
+                    rate: Rate::Percent(0),
+                    receivers: vec![AddressWeight::new("tax_receiver", 1)],
+                    description: None,
+                    rounding: None,
+                },
+            },
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::InvalidTaxRate {});
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::RemoveModule { module_idx: 0 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new("remove_module")
+                .add_attribute("module", "receipt")
+                .add_attribute("module_idx", "0")]
+        );
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::RemoveModule { module_idx: 1 },
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::ModuleIndexOutOfRange {});
+
+        // A custom module's address is validated when it is added
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::AddModule {
+                module: ModuleDefinition::Custom {
+                    address: "xy".to_string(),
+                    hooks: vec![CustomHook {
+                        hook: HookType::Transfer,
+                        forward: HookForward::Query,
+                    }],
+                    priority: None,
+                },
+            },
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::InvalidCustomModuleAddress {});
+
+        // A whitelist with a code ID instantiates its module contract
+        let whitelist = ModuleDefinition::Whitelist {
+            address: None,
+            code_id: Some(1),
+            moderators: Some(vec![owner.to_string()]),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::AddModule {
+                module: whitelist.clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].id, REPLY_ADDRESS_LIST);
+
+        let res = query(deps.as_ref(), env, QueryMsg::ModuleInfo {}).unwrap();
+        let module_info: ModuleInfoResponse = from_binary(&res).unwrap();
+        assert_eq!(module_info.modules, vec![updated_tax, whitelist]);
+        // The whitelist's hooks are executed before the tax module's
+        assert_eq!(
+            module_info.execution_order,
+            vec![
+                ModuleOrder {
+                    module_idx: 1,
+                    module: "whitelist".to_string(),
+                    priority: 0,
+                },
+                ModuleOrder {
+                    module_idx: 0,
+                    module: "tax".to_string(),
+                    priority: 30,
+                },
+            ]
+        );
+    }
+
+    fn batch_mint_msg(owner: &str, token_ids: &[&str]) -> ExecuteMsg {
+        ExecuteMsg::BatchMint {
+            tokens: token_ids
+                .iter()
+                .map(|token_id| MintMsg {
+                    token_id: token_id.to_string(),
+                    owner: owner.to_string(),
+                    description: None,
+                    name: "TestToken".to_string(),
+                    metadata: None,
+                    image: None,
+                    pricing: None,
+                    royalty: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_batch_mint() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let minter = "minter";
+        let owner = "owner";
+        let info = mock_info(minter, &[]);
+        store_mock_config(deps.as_mut(), minter.to_string());
+
+        let msg = batch_mint_msg(owner, &["1", "2"]);
+
+        let unauth_info = mock_info("anyone", &[]);
+        let resp = execute(deps.as_mut(), env.clone(), unauth_info, msg.clone()).unwrap_err();
+        assert_eq!(resp, ContractError::MintUnauthorized {});
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(owner, &[]),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::EmptyBatch {});
+
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            Response::new()
+                .add_event(Event::new("batch_mint").add_attributes(vec![
+                    attr("token_id", "1"),
+                    attr("owner", owner),
+                    attr("token_id", "2"),
+                    attr("owner", owner),
+                ]))
+                .add_attributes(vec![
+                    attr("action", "batch_mint"),
+                    attr("count", "2"),
+                    attr("publisher", minter),
+                ]),
+            res
+        );
+
+        let res = query(deps.as_ref(), env, QueryMsg::NumTokens {}).unwrap();
+        let num_tokens: NumTokensResponse = from_binary(&res).unwrap();
+        assert_eq!(num_tokens.count, 2);
+        for token_id in ["1", "2"] {
+            let token = load_token(deps.as_ref().storage, token_id.to_string()).unwrap();
+            assert_eq!(token.owner, owner.to_string());
+        }
+    }
+
+    #[test]
+    fn test_batch_transfer() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let minter = "minter";
+        let recipient = "recipient";
+        let info = mock_info(minter, &[]);
+        store_mock_config(deps.as_mut(), minter.to_string());
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["1", "2"]),
+        )
+        .unwrap();
+
+        let amount = coin(100, "uluna");
+        let mut token = load_token(deps.as_ref().storage, "2".to_string()).unwrap();
+        let agreement = TransferAgreement {
+            purchaser: recipient.to_string(),
+            amount: Funds::Native(amount.clone()),
+        };
+        token.transfer_agreement = Some(agreement.clone());
+        save_token(deps.as_mut().storage, "2".to_string(), &token).unwrap();
+
+        let msg = ExecuteMsg::BatchTransferNft {
+            recipient: recipient.to_string(),
+            token_ids: vec!["1".to_string(), "2".to_string()],
+        };
+
+        let unauth_info = mock_info("anyone", &[]);
+        let resp = execute(deps.as_mut(), env.clone(), unauth_info, msg.clone()).unwrap_err();
+        assert_eq!(resp, ContractError::TransferUnauthorized {});
+
+        let unfunded_msg = ExecuteMsg::BatchTransferNft {
+            recipient: recipient.to_string(),
+            token_ids: vec!["2".to_string()],
+        };
+        let resp = execute(deps.as_mut(), env.clone(), info, unfunded_msg).unwrap_err();
+        assert_eq!(resp, ContractError::InsufficientFunds {});
+
+        let info = mock_info(minter, &[coin(100, "uluna")]);
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            Response::new()
+                .add_message(BankMsg::Send {
+                    to_address: minter.to_string(),
+                    amount: vec![amount],
+                })
+                .add_event(agreement.generate_event())
+                .add_event(Event::new("batch_transfer").add_attributes(vec![
+                    attr("recipient", recipient),
+                    attr("token_id", "1"),
+                    attr("token_id", "2"),
+                ]))
+                .add_attributes(vec![
+                    attr("action", "batch_transfer"),
+                    attr("recipient", recipient),
+                    attr("count", "2"),
+                    attr("sender", minter),
+                ]),
+            res
+        );
+
+        for token_id in ["1", "2"] {
+            let token = load_token(deps.as_ref().storage, token_id.to_string()).unwrap();
+            assert_eq!(token.owner, recipient.to_string());
+        }
+    }
+
+    #[test]
+    fn test_batch_burn() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let minter = "minter";
+        let info = mock_info(minter, &[]);
+        store_mock_config(deps.as_mut(), minter.to_string());
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["1", "2", "3"]),
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::BatchBurn {
+            token_ids: vec!["1".to_string(), "2".to_string()],
+        };
+
+        let unauth_info = mock_info("anyone", &[]);
+        let resp = execute(deps.as_mut(), env.clone(), unauth_info, msg.clone()).unwrap_err();
+        assert_eq!(resp, ContractError::BurnUnauthorized {});
+
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            Response::new()
+                .add_event(
+                    Event::new("batch_burn")
+                        .add_attributes(vec![attr("token_id", "1"), attr("token_id", "2"),])
+                )
+                .add_attributes(vec![
+                    attr("action", "batch_burn"),
+                    attr("count", "2"),
+                    attr("sender", minter),
+                ]),
+            res
+        );
+
+        let res = query(deps.as_ref(), env, QueryMsg::NumTokens {}).unwrap();
+        let num_tokens: NumTokensResponse = from_binary(&res).unwrap();
+        assert_eq!(num_tokens.count, 1);
+        assert!(load_token(deps.as_ref().storage, "1".to_string()).is_err());
+        assert!(load_token(deps.as_ref().storage, "3".to_string()).is_ok());
+    }
+
+    #[test]
+    fn test_mint_max_supply() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let minter = "minter";
+        let info = mock_info(minter, &[]);
+        store_mock_config(deps.as_mut(), minter.to_string());
+        CONFIG
+            .update(deps.as_mut().storage, |mut config| -> StdResult<_> {
+                config.max_supply = Some(2);
+                Ok(config)
+            })
+            .unwrap();
+
+        // A batch exceeding the max supply is rejected before any token is minted
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["1", "2", "3"]),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::MaxSupplyReached {});
+        assert!(TOKENS
+            .may_load(deps.as_ref().storage, "1".to_string())
+            .unwrap()
+            .is_none());
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["1", "2"]),
+        )
+        .unwrap();
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["3"]),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::MaxSupplyReached {});
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["1", "2", "3"]),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::MaxSupplyReached {});
+
+        // Burning a token does not allow another to be minted in its place
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Burn {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["3"]),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::MaxSupplyReached {});
+    }
+
+    #[test]
+    fn test_mint_window() {
+        let mut deps = mock_dependencies(&[]);
+        let mut env = mock_env();
+        let minter = "minter";
+        let info = mock_info(minter, &[]);
+        let msg = InstantiateMsg {
+            name: TOKEN_NAME.to_string(),
+            symbol: TOKEN_SYMBOL.to_string(),
+            modules: vec![],
+            minter: minter.to_string(),
+            max_supply: Some(10),
+            mint_start: Some(Expiration::AtHeight(env.block.height + 10)),
+            mint_end: Some(Expiration::AtHeight(env.block.height + 20)),
+            metadata_update_policy: None,
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::ContractInfo {}).unwrap();
+        let contract_info: ContractInfoResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            contract_info,
+            ContractInfoResponse {
+                name: TOKEN_NAME.to_string(),
+                symbol: TOKEN_SYMBOL.to_string(),
+                max_supply: msg.max_supply,
+                mint_start: msg.mint_start,
+                mint_end: msg.mint_end,
+            }
+        );
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["1"]),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::MintingNotStarted {});
+
+        env.block.height += 10;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["1"]),
+        )
+        .unwrap();
+
+        env.block.height += 10;
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["2"]),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::MintingEnded {});
+    }
+
+    #[test]
+    fn test_instantiate_invalid_mint_config() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            name: TOKEN_NAME.to_string(),
+            symbol: TOKEN_SYMBOL.to_string(),
+            modules: vec![],
+            minter: "creator".to_string(),
+            max_supply: Some(0),
+            mint_start: None,
+            mint_end: None,
+            metadata_update_policy: None,
+        };
+        let resp = instantiate(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+        assert_eq!(resp, ContractError::InvalidMaxSupply {});
+
+        let msg = InstantiateMsg {
+            max_supply: None,
+            mint_start: Some(Expiration::AtHeight(20)),
+            mint_end: Some(Expiration::AtHeight(10)),
+            ..msg
+        };
+        let resp = instantiate(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(resp, ContractError::InvalidMintWindow {});
+    }
+
+    fn update_metadata_msg(token_id: &str, data_url: &str) -> ExecuteMsg {
+        ExecuteMsg::UpdateMetadata {
+            token_id: token_id.to_string(),
+            name: Some("UpdatedToken".to_string()),
+            description: None,
+            image: None,
+            metadata: Some(TokenMetadata {
+                data_type: MetadataType::Image,
+                external_url: None,
+                data_url: Some(data_url.to_string()),
+                attributes: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_update_metadata() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let minter = "minter";
+        let owner = "owner";
+        let info = mock_info(minter, &[]);
+        store_mock_config(deps.as_mut(), minter.to_string());
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(owner, &["1"]),
+        )
+        .unwrap();
+
+        let owner_info = mock_info(owner, &[]);
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            owner_info.clone(),
+            update_metadata_msg("1", "ipfs://owner"),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::MetadataUpdateUnauthorized {});
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            update_metadata_msg("1", "ipfs://fixed"),
+        )
+        .unwrap();
+        assert_eq!(
+            Response::default().add_attributes(vec![
+                attr("action", "update_metadata"),
+                attr("token_id", "1"),
+                attr("sender", minter),
+            ]),
+            res
+        );
+        let token = load_token(deps.as_ref().storage, "1".to_string()).unwrap();
+        assert_eq!(token.name, "UpdatedToken".to_string());
+        assert_eq!(
+            token.metadata.unwrap().data_url,
+            Some("ipfs://fixed".to_string())
+        );
+
+        CONFIG
+            .update(deps.as_mut().storage, |mut config| -> StdResult<_> {
+                config.metadata_update_policy = MetadataUpdatePolicy::Owner;
+                Ok(config)
+            })
+            .unwrap();
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            update_metadata_msg("1", "ipfs://minter"),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::MetadataUpdateUnauthorized {});
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            owner_info.clone(),
+            update_metadata_msg("1", "ipfs://owner"),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            owner_info.clone(),
+            ExecuteMsg::Archive {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        let resp = execute(
+            deps.as_mut(),
+            env,
+            owner_info,
+            update_metadata_msg("1", "ipfs://archived"),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::TokenArchived {});
+    }
+
+    #[test]
+    fn test_freeze_metadata() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let minter = "minter";
+        let info = mock_info(minter, &[]);
+        store_mock_config(deps.as_mut(), minter.to_string());
+        CONTRACT_OWNER
+            .save(deps.as_mut().storage, &minter.to_string())
+            .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["1", "2"]),
+        )
+        .unwrap();
+
+        let freeze_msg = ExecuteMsg::FreezeMetadata {
+            token_id: Some("1".to_string()),
+        };
+        let unauth_info = mock_info("anyone", &[]);
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            unauth_info.clone(),
+            freeze_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::MetadataUpdateUnauthorized {});
+
+        execute(deps.as_mut(), env.clone(), info.clone(), freeze_msg.clone()).unwrap();
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            update_metadata_msg("1", "ipfs://frozen"),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::MetadataFrozen {});
+
+        let query_msg = QueryMsg::NftInfo {
+            token_id: "1".to_string(),
+        };
+        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+        let nft_info: NftInfoResponse<NftInfoResponseExtension> = from_binary(&res).unwrap();
+        assert!(nft_info.extension.metadata_frozen);
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            update_metadata_msg("2", "ipfs://fixed"),
+        )
+        .unwrap();
+
+        let freeze_collection_msg = ExecuteMsg::FreezeMetadata { token_id: None };
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            unauth_info,
+            freeze_collection_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::Unauthorized {});
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            freeze_collection_msg.clone(),
+        )
+        .unwrap();
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            freeze_collection_msg,
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::CollectionMetadataFrozen {});
+
+        let resp = execute(
+            deps.as_mut(),
+            env,
+            info,
+            update_metadata_msg("2", "ipfs://frozen"),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::MetadataFrozen {});
+    }
+
+    #[test]
+    fn test_place_and_cancel_offer() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let minter = "minter";
+        let purchaser = "purchaser";
+        let info = mock_info(minter, &[]);
+        store_mock_config(deps.as_mut(), minter.to_string());
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["1"]),
+        )
+        .unwrap();
+
+        let offer_msg = ExecuteMsg::PlaceOffer {
+            token_id: "1".to_string(),
+            expires: Expiration::AtHeight(env.block.height + 10),
+        };
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(minter, &[coin(100, "uluna")]),
+            offer_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::OfferOnOwnToken {});
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(purchaser, &[]),
+            offer_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::InvalidOfferFunds {});
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(purchaser, &[coin(100, "uluna")]),
+            ExecuteMsg::PlaceOffer {
+                token_id: "1".to_string(),
+                expires: Expiration::AtHeight(env.block.height),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::OfferAlreadyExpired {});
+
+        let purchaser_info = mock_info(purchaser, &[coin(100, "uluna")]);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            purchaser_info.clone(),
+            offer_msg.clone(),
+        )
+        .unwrap();
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            purchaser_info.clone(),
+            offer_msg,
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::OfferExists {});
+
+        let query_msg = QueryMsg::Offers {
+            token_id: "1".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap();
+        let offers: OffersResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            offers.offers,
+            vec![Offer {
+                purchaser: purchaser.to_string(),
+                amount: coin(100, "uluna"),
+                expires: Expiration::AtHeight(env.block.height + 10),
+            }]
+        );
+
+        let cancel_msg = ExecuteMsg::CancelOffer {
+            token_id: "1".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            purchaser_info.clone(),
+            cancel_msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            Response::default()
+                .add_message(BankMsg::Send {
+                    to_address: purchaser.to_string(),
+                    amount: vec![coin(100, "uluna")],
+                })
+                .add_attributes(vec![
+                    attr("action", "cancel_offer"),
+                    attr("token_id", "1"),
+                    attr("purchaser", purchaser),
+                    attr("amount", "100uluna"),
+                ]),
+            res
+        );
+
+        let resp = execute(deps.as_mut(), env.clone(), purchaser_info, cancel_msg).unwrap_err();
+        assert_eq!(resp, ContractError::OfferNotFound {});
+
+        let res = query(deps.as_ref(), env, query_msg).unwrap();
+        let offers: OffersResponse = from_binary(&res).unwrap();
+        assert!(offers.offers.is_empty());
+    }
+
+    #[test]
+    fn test_accept_offer() {
+        let mut deps = mock_dependencies(&[]);
+        let mut env = mock_env();
+        let minter = "minter";
+        let purchaser = "purchaser";
+        let info = mock_info(minter, &[]);
+        store_mock_config(deps.as_mut(), minter.to_string());
+        let tax_definition = ModuleDefinition::Taxable {
+            rate: Rate::Percent(10),
+            receivers: vec![AddressWeight::new("tax_receiver", 1)],
+            description: None,
+            rounding: None,
+        };
+        let royalty_definition = ModuleDefinition::Royalties {
+            rate: Rate::Percent(10),
+            receivers: vec![AddressWeight::new("royalty_receiver", 1)],
+            description: None,
+            rounding: None,
+        };
+        store_modules(
+            deps.as_mut().storage,
+            Modules::new(vec![tax_definition, royalty_definition]),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["1"]),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(purchaser, &[coin(100, "uluna")]),
+            ExecuteMsg::PlaceOffer {
+                token_id: "1".to_string(),
+                expires: Expiration::AtHeight(env.block.height + 10),
+            },
+        )
+        .unwrap();
+
+        let accept_msg = ExecuteMsg::AcceptOffer {
+            token_id: "1".to_string(),
+            purchaser: purchaser.to_string(),
+        };
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            accept_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::AcceptOfferUnauthorized {});
+
+        let mut expired_env = env.clone();
+        expired_env.block.height += 10;
+        let resp =
+            execute(deps.as_mut(), expired_env, info.clone(), accept_msg.clone()).unwrap_err();
+        assert_eq!(resp, ContractError::OfferExpired {});
+
+        env.block.height += 1;
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), accept_msg.clone()).unwrap();
+        // The tax is covered by the escrowed offer and so is deducted from the owner's payment
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: minter.to_string(),
+                    amount: vec![coin(80, "uluna")],
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "tax_receiver".to_string(),
+                    amount: vec![coin(10, "uluna")],
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "royalty_receiver".to_string(),
+                    amount: vec![coin(10, "uluna")],
+                }),
+            ]
+        );
+
+        let token = load_token(deps.as_ref().storage, "1".to_string()).unwrap();
+        assert_eq!(token.owner, purchaser.to_string());
+
+        let resp = execute(deps.as_mut(), env, mock_info(purchaser, &[]), accept_msg).unwrap_err();
+        assert_eq!(resp, ContractError::OfferNotFound {});
+    }
+
+    #[test]
+    fn test_accept_offer_fee_in_other_denom() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let minter = "minter";
+        let purchaser = "purchaser";
+        let info = mock_info(minter, &[]);
+        store_mock_config(deps.as_mut(), minter.to_string());
+        store_modules(
+            deps.as_mut().storage,
+            Modules::new(vec![ModuleDefinition::Taxable {
+                rate: Rate::Flat(FlatRate {
+                    amount: Uint128::from(10u128),
+                    denom: "uusd".to_string(),
+                }),
+                receivers: vec![AddressWeight::new("tax_receiver", 1)],
+                description: None,
+                rounding: None,
+            }]),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["1"]),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(purchaser, &[coin(100, "uluna")]),
+            ExecuteMsg::PlaceOffer {
+                token_id: "1".to_string(),
+                expires: Expiration::AtHeight(env.block.height + 10),
+            },
+        )
+        .unwrap();
+
+        // The escrowed offer cannot cover a tax in another denomination
+        let resp = execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::AcceptOffer {
+                token_id: "1".to_string(),
+                purchaser: purchaser.to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::InvalidEscrowedPayment {});
+    }
+
+    #[test]
+    fn test_agreed_transfer_fee_in_other_denom() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let minter = "minter";
+        let purchaser = "purchaser";
+        let info = mock_info(minter, &[]);
+        store_mock_config(deps.as_mut(), minter.to_string());
+        store_modules(
+            deps.as_mut().storage,
+            Modules::new(vec![ModuleDefinition::Taxable {
+                rate: Rate::Flat(FlatRate {
+                    amount: Uint128::from(10u128),
+                    denom: "uusd".to_string(),
+                }),
+                receivers: vec![AddressWeight::new("tax_receiver", 1)],
+                description: None,
+                rounding: None,
+            }]),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["1", "2"]),
+        )
+        .unwrap();
+        // The contract holds an escrowed offer in the tax denomination
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(purchaser, &[coin(100, "uusd")]),
+            ExecuteMsg::PlaceOffer {
+                token_id: "2".to_string(),
+                expires: Expiration::AtHeight(env.block.height + 10),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::TransferAgreement {
+                token_id: "1".to_string(),
+                amount: Funds::Native(coin(100, "uluna")),
+                purchaser: purchaser.to_string(),
+            },
+        )
+        .unwrap();
+
+        // The tax must not be paid from the escrowed offer, even if the purchaser sends it
+        for funds in [
+            vec![coin(100, "uluna")],
+            vec![coin(100, "uluna"), coin(10, "uusd")],
+        ] {
+            let resp = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(purchaser, &funds),
+                ExecuteMsg::TransferNft {
+                    recipient: purchaser.to_string(),
+                    token_id: "1".to_string(),
+                },
+            )
+            .unwrap_err();
+            assert_eq!(resp, ContractError::InvalidAgreedPayment {});
+        }
+        assert_eq!(
+            load_token(deps.as_ref().storage, "1".to_string())
+                .unwrap()
+                .owner,
+            minter
+        );
+        assert_eq!(
+            OFFERS
+                .load(
+                    deps.as_ref().storage,
+                    ("2".to_string(), purchaser.to_string())
+                )
+                .unwrap()
+                .amount,
+            coin(100, "uusd")
+        );
+    }
+
+    #[test]
+    fn test_query_royalty_info() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let minter = "minter";
+        let info = mock_info(minter, &[]);
+        store_mock_config(deps.as_mut(), minter.to_string());
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            batch_mint_msg(minter, &["1"]),
+        )
+        .unwrap();
+
+        let royalty_info_msg = QueryMsg::RoyaltyInfo {
+            token_id: "1".to_string(),
+            sale_price: coin(1000, "uluna"),
+        };
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::CheckRoyalties {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        let check: CheckRoyaltiesResponse = from_binary(&res).unwrap();
+        assert!(!check.royalty_payments);
+        let res = query(deps.as_ref(), env.clone(), royalty_info_msg.clone()).unwrap();
+        let royalty_info: RoyaltyInfoResponse = from_binary(&res).unwrap();
+        assert!(royalty_info.royalties.is_empty());
+
+        store_modules(
+            deps.as_mut().storage,
+            Modules::new(vec![ModuleDefinition::Royalties {
+                rate: Rate::Percent(5),
+                receivers: vec![
+                    AddressWeight::new("receiver_one", 2),
+                    AddressWeight::new("receiver_two", 3),
+                ],
+                description: None,
+                rounding: None,
+            }]),
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::CheckRoyalties {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        let check: CheckRoyaltiesResponse = from_binary(&res).unwrap();
+        assert!(check.royalty_payments);
+        let res = query(deps.as_ref(), env.clone(), royalty_info_msg).unwrap();
+        let royalty_info: RoyaltyInfoResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            royalty_info.royalties,
+            vec![
+                RoyaltyPayment {
+                    receiver: "receiver_one".to_string(),
+                    amount: coin(20, "uluna"),
+                },
+                RoyaltyPayment {
+                    receiver: "receiver_two".to_string(),
+                    amount: coin(30, "uluna"),
+                },
+            ]
+        );
+
+        // A token's own royalty is used in place of the royalty module
+        let mint_msg = |token_id: &str, royalty: Royalty| {
+            ExecuteMsg::Mint(MintMsg {
+                token_id: token_id.to_string(),
+                owner: minter.to_string(),
+                description: None,
+                name: "TestToken".to_string(),
+                metadata: None,
+                image: None,
+                pricing: None,
+                royalty: Some(royalty),
+            })
+        };
+        store_modules(deps.as_mut().storage, Modules::new(vec![])).unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(minter, &[]),
+            mint_msg(
+                "2",
+                Royalty {
+                    rate: Rate::Percent(5),
+                    receivers: vec![
+                        AddressWeight::new("receiver_one", 1000),
+                        AddressWeight::new("receiver_two", 1),
+                    ],
+                    description: None,
+                    rounding: None,
+                },
+            ),
+        )
+        .unwrap();
+        let check_royalties = |deps: Deps, token_id: &str| -> bool {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::CheckRoyalties {
+                    token_id: token_id.to_string(),
+                },
+            )
+            .unwrap();
+            from_binary::<CheckRoyaltiesResponse>(&res)
+                .unwrap()
+                .royalty_payments
+        };
+        assert!(!check_royalties(deps.as_ref(), "1"));
+        assert!(check_royalties(deps.as_ref(), "2"));
+
+        // Receivers of a zero share are not paid
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RoyaltyInfo {
+                token_id: "2".to_string(),
+                sale_price: coin(1000, "uluna"),
+            },
+        )
+        .unwrap();
+        let royalty_info: RoyaltyInfoResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            royalty_info.royalties,
+            vec![RoyaltyPayment {
+                receiver: "receiver_one".to_string(),
+                amount: coin(50, "uluna"),
+            }]
+        );
+
+        // A flat royalty in another denomination cannot be paid from the sale
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(minter, &[]),
+            mint_msg(
+                "3",
+                Royalty {
+                    rate: Rate::Flat(FlatRate {
+                        amount: Uint128::from(10u128),
+                        denom: "uusd".to_string(),
+                    }),
+                    receivers: vec![AddressWeight::new("receiver_one", 1)],
+                    description: None,
+                    rounding: None,
+                },
+            ),
+        )
+        .unwrap();
+        let err = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RoyaltyInfo {
+                token_id: "3".to_string(),
+                sale_price: coin(1000, "uluna"),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::RoyaltyDenomMismatch {});
+
+        query(
+            deps.as_ref(),
+            env,
+            QueryMsg::RoyaltyInfo {
+                token_id: "4".to_string(),
+                sale_price: coin(1000, "uluna"),
+            },
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_token_royalty() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let minter = "minter";
+        let purchaser = "purchaser";
+        let info = mock_info(minter, &[]);
+        store_mock_config(deps.as_mut(), minter.to_string());
+        store_modules(
+            deps.as_mut().storage,
+            Modules::new(vec![ModuleDefinition::Royalties {
+                rate: Rate::Percent(5),
+                receivers: vec![AddressWeight::new("module_receiver", 1)],
+                description: None,
+                rounding: None,
+            }]),
+        )
+        .unwrap();
+
+        let mint_msg = MintMsg {
+            token_id: "1".to_string(),
+            owner: minter.to_string(),
+            description: None,
+            name: "TestToken".to_string(),
+            metadata: None,
+            image: None,
+            pricing: None,
+            royalty: Some(Royalty {
+                rate: Rate::Percent(10),
+                receivers: vec![],
+                description: None,
+                rounding: None,
+            }),
+        };
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint(mint_msg.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::NoRoyaltyReceivers {});
+
+        let mint_msg = MintMsg {
+            royalty: Some(Royalty {
+                rate: Rate::Percent(10),
+                receivers: vec![AddressWeight::new("token_receiver", 1)],
+                description: None,
+                rounding: None,
+            }),
+            ..mint_msg
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint(mint_msg),
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RoyaltyInfo {
+                token_id: "1".to_string(),
+                sale_price: coin(1000, "uluna"),
+            },
+        )
+        .unwrap();
+        let royalty_info: RoyaltyInfoResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            royalty_info.royalties,
+            vec![RoyaltyPayment {
+                receiver: "token_receiver".to_string(),
+                amount: coin(100, "uluna"),
+            }]
+        );
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::TransferAgreement {
+                token_id: "1".to_string(),
+                amount: Funds::Native(coin(1000, "uluna")),
+                purchaser: purchaser.to_string(),
+            },
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(minter, &[coin(1000, "uluna")]),
+            ExecuteMsg::TransferNft {
+                recipient: purchaser.to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: minter.to_string(),
+                    amount: vec![coin(900, "uluna")],
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "token_receiver".to_string(),
+                    amount: vec![coin(100, "uluna")],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cw20_agreed_transfer() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let minter = "minter";
+        let purchaser = "purchaser";
+        let info = mock_info(minter, &[]);
+        store_mock_config(deps.as_mut(), minter.to_string());
+        store_modules(
+            deps.as_mut().storage,
+            Modules::new(vec![
+                ModuleDefinition::Taxable {
+                    rate: Rate::Percent(10),
+                    receivers: vec![AddressWeight::new("tax_receiver", 1)],
+                    description: None,
+                    rounding: None,
+                },
+                ModuleDefinition::Royalties {
+                    rate: Rate::Percent(5),
+                    receivers: vec![AddressWeight::new("royalty_receiver", 1)],
+                    description: None,
+                    rounding: None,
+                },
+            ]),
+        )
+        .unwrap();
+
+        let mint_msg = MintMsg {
+            token_id: "1".to_string(),
+            owner: minter.to_string(),
+            description: None,
+            name: "TestToken".to_string(),
+            metadata: None,
+            image: None,
+            pricing: None,
+            royalty: None,
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint(mint_msg),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::TransferAgreement {
+                token_id: "1".to_string(),
+                amount: Funds::Cw20(Cw20Coin {
+                    address: "cw20".to_string(),
+                    amount: Uint128::from(1000u128),
+                }),
+                purchaser: purchaser.to_string(),
+            },
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(purchaser, &[]),
+            ExecuteMsg::TransferNft {
+                recipient: purchaser.to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Cw20PaymentRequired {});
+
+        let receive_msg = |amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: purchaser.to_string(),
+                amount: Uint128::from(amount),
+                msg: to_binary(&Cw20HookMsg::TransferNft {
+                    recipient: purchaser.to_string(),
+                    token_id: "1".to_string(),
+                })
+                .unwrap(),
+            })
+        };
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("othercw20", &[]),
+            receive_msg(1200),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidCw20Token {});
+
+        // The purchaser pays the 100 tax on top of the agreed 1000
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("cw20", &[]),
+            receive_msg(1050),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InsufficientFunds {});
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("cw20", &[]),
+            receive_msg(1200),
+        )
+        .unwrap();
+        let cw20_transfer = |recipient: &str, amount: u128| {
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "cw20".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount: Uint128::from(amount),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        };
+        assert_eq!(
+            res.messages,
+            vec![
+                cw20_transfer(minter, 950),
+                cw20_transfer("tax_receiver", 100),
+                cw20_transfer("royalty_receiver", 50),
+                cw20_transfer(purchaser, 100),
+            ]
+        );
+
+        let token = load_token(deps.as_ref().storage, "1".to_string()).unwrap();
+        assert_eq!(token.owner, purchaser.to_string());
+    }
+
+    #[test]
+    fn test_cw20_agreed_transfer_whitelisted_purchaser() {
+        let mut deps = mock_dependencies_custom(&[]);
+        let env = mock_env();
+        let minter = "minter";
+        let purchaser = "purchaser";
+        let info = mock_info(minter, &[]);
+        store_mock_config(deps.as_mut(), minter.to_string());
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            batch_mint_msg(minter, &["1"]),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::TransferAgreement {
+                token_id: "1".to_string(),
+                amount: Funds::Cw20(Cw20Coin {
+                    address: "cw20".to_string(),
+                    amount: Uint128::from(1000u128),
+                }),
+                purchaser: purchaser.to_string(),
+            },
+        )
+        .unwrap();
+        // Only the purchaser is whitelisted, the CW20 token contract is not
+        store_modules(
+            deps.as_mut().storage,
+            Modules::new(vec![ModuleDefinition::Whitelist {
+                address: Some("addresslist_contract_address2".to_string()),
+                code_id: None,
+                moderators: None,
+            }]),
+        )
+        .unwrap();
+
+        let receive_msg = |sender: &str| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: sender.to_string(),
+                amount: Uint128::from(1000u128),
+                msg: to_binary(&Cw20HookMsg::TransferNft {
+                    recipient: purchaser.to_string(),
+                    token_id: "1".to_string(),
+                })
+                .unwrap(),
+            })
+        };
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("cw20", &[]),
+            receive_msg("other"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AddressNotAuthorized {});
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("cw20", &[]),
+            receive_msg(purchaser),
+        )
+        .unwrap();
+        let token = load_token(deps.as_ref().storage, "1".to_string()).unwrap();
+        assert_eq!(token.owner, purchaser.to_string());
+    }
+
+    #[test]
+    fn test_migrate_unweighted_receivers() {
+        let mut deps = mock_dependencies(&[]);
+        deps.as_mut().storage.set(
+            b"modules",
+            br#"{"module_defs":[{"royalties":{"rate":{"percent":5},"receivers":["one","two"],"description":null}}]}"#,
+        );
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg { limit: None }).unwrap();
+
+        assert_eq!(
+            read_modules(deps.as_ref().storage).unwrap(),
+            Modules::new(vec![ModuleDefinition::Royalties {
+                rate: Rate::Percent(5),
+                receivers: vec![AddressWeight::new("one", 1), AddressWeight::new("two", 1)],
+                description: None,
+                rounding: None,
+            }])
+        );
+
+        // Modules in the current format are unchanged
+        migrate(deps.as_mut(), mock_env(), MigrateMsg { limit: None }).unwrap();
+        assert_eq!(
+            get_royalty_module(deps.as_ref().storage)
+                .unwrap()
+                .unwrap()
+                .receivers
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_migrate_baseline_storage() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        deps.as_mut().storage.set(
+            b"config",
+            br#"{"name":"Token","symbol":"T","minter":"minter"}"#,
+        );
+        deps.as_mut().storage.set(
+            &TOKENS.key("1".to_string()),
+            br#"{"token_id":"1","owner":"owner","name":"One","publisher":"minter","description":null,"approvals":[],"transfer_agreement":{"amount":{"denom":"uluna","amount":"100"},"purchaser":"purchaser"},"metadata":null,"archived":false,"image":null,"pricing":null}"#,
+        );
+        deps.as_mut().storage.set(
+            &TOKENS.key("2".to_string()),
+            br#"{"token_id":"2","owner":"owner","name":"Two","publisher":"minter","description":null,"approvals":[],"transfer_agreement":null,"metadata":null,"archived":false,"image":null,"pricing":null}"#,
+        );
+        NUM_TOKENS.save(deps.as_mut().storage, &2).unwrap();
+        CONTRACT_OWNER
+            .save(deps.as_mut().storage, &"minter".to_string())
+            .unwrap();
+
+        // Tokens are migrated in batches, the first batch as part of the migration
+        let res = migrate(deps.as_mut(), env.clone(), MigrateMsg { limit: Some(1) }).unwrap();
+        assert!(res
+            .attributes
+            .contains(&attr("token_migration_pending", "true")));
+        assert_eq!(MINTED_TOKENS.load(deps.as_ref().storage).unwrap(), 2);
+
+        // Other messages are rejected until every token has been migrated
+        let transfer_msg = ExecuteMsg::TransferNft {
+            recipient: "purchaser".to_string(),
+            token_id: "1".to_string(),
+        };
+        let purchaser_info = mock_info("purchaser", &[coin(100, "uluna")]);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            purchaser_info.clone(),
+            transfer_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::TokenMigrationPending {});
+
+        let migrate_tokens_msg = ExecuteMsg::MigrateTokens { limit: Some(1) };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            migrate_tokens_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // A full batch may not be the last
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("minter", &[]),
+            migrate_tokens_msg.clone(),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .contains(&attr("token_migration_pending", "true")));
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("minter", &[]),
+            migrate_tokens_msg.clone(),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .contains(&attr("token_migration_pending", "false")));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("minter", &[]),
+            migrate_tokens_msg,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NoTokenMigration {});
+
+        assert_eq!(
+            CONFIG.load(deps.as_ref().storage).unwrap(),
+            TokenConfig {
+                name: "Token".to_string(),
+                symbol: "T".to_string(),
+                minter: "minter".to_string(),
+                max_supply: None,
+                mint_start: None,
+                mint_end: None,
+                metadata_update_policy: MetadataUpdatePolicy::Publisher,
+                metadata_frozen: false,
+            }
+        );
+        assert!(is_minter(deps.as_ref().storage, &env.block, "minter").unwrap());
+
+        let token = load_token(deps.as_ref().storage, "1".to_string()).unwrap();
+        assert_eq!(
+            token.transfer_agreement,
+            Some(TransferAgreement {
+                amount: Funds::Native(coin(100, "uluna")),
+                purchaser: "purchaser".to_string(),
+            })
+        );
+        assert_eq!(token.royalty, None);
+        let token = load_token(deps.as_ref().storage, "2".to_string()).unwrap();
+        assert_eq!(token.transfer_agreement, None);
+
+        // Existing tokens are added to the owner index
+        let query_msg = QueryMsg::Tokens {
+            owner: "owner".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res: TokensResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap()).unwrap();
+        assert_eq!(res.tokens, vec!["1".to_string(), "2".to_string()]);
+
+        // Migrated tokens can be transferred as usual
+        let res = execute(deps.as_mut(), env.clone(), purchaser_info, transfer_msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "owner".to_string(),
+                amount: vec![coin(100, "uluna")],
+            })]
+        );
+
+        let res: TokensResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(res.tokens, vec!["2".to_string()]);
+
+        // Tokens are only migrated from the legacy format once
+        migrate(deps.as_mut(), env, MigrateMsg { limit: None }).unwrap();
+        let token = load_token(deps.as_ref().storage, "1".to_string()).unwrap();
+        assert_eq!(token.owner, "purchaser");
+    }
 }
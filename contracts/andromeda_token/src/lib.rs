@@ -1,3 +1,4 @@
+mod auction;
 pub mod contract;
 pub mod state;
 #[cfg(test)]
@@ -1,7 +1,12 @@
-use andromeda_protocol::token::Token;
-use cosmwasm_std::{Env, StdError, StdResult, Storage};
+use andromeda_protocol::error::ContractError;
+use andromeda_protocol::{
+    modules::common::Funds,
+    roles::{has_role, ROLE_MINTER},
+    token::{Approval, MetadataUpdatePolicy, Offer, Token, TokenMetadata, TransferAgreement},
+};
+use cosmwasm_std::{BlockInfo, Coin, Env, Order, StdError, StdResult, Storage};
 use cw721::Expiration;
-use cw_storage_plus::{Item, Map};
+use cw_storage_plus::{Bound, Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -9,24 +14,82 @@ use serde::{Deserialize, Serialize};
 pub struct TokenConfig {
     pub name: String,
     pub symbol: String,
+    /// Addresses allowed to mint alongside the `minter` hold the `minter` role
     pub minter: String,
+    /// The maximum number of tokens that may ever be minted, burnt tokens included
+    pub max_supply: Option<u64>,
+    /// Minting is disabled until this has expired
+    pub mint_start: Option<Expiration>,
+    /// Minting is disabled once this has expired
+    pub mint_end: Option<Expiration>,
+    /// Who may update a token's metadata
+    #[serde(default)]
+    pub metadata_update_policy: MetadataUpdatePolicy,
+    /// Whether the metadata of every token in the collection is frozen
+    #[serde(default)]
+    pub metadata_frozen: bool,
 }
 
 pub const CONFIG: Item<TokenConfig> = Item::new("config");
 pub const TOKENS: Map<String, Option<Token>> = Map::new("ownership");
 pub const OPERATOR: Map<(String, String), Expiration> = Map::new("operator");
 pub const NUM_TOKENS: Item<u64> = Item::new("numtokens");
+/// The total number of tokens ever minted, which unlike `NUM_TOKENS` is not reduced by burning
+pub const MINTED_TOKENS: Item<u64> = Item::new("mintedtokens");
+/// Owner index over `TOKENS`, keyed by (owner, token_id)
+pub const OWNED_TOKENS: Map<(String, String), bool> = Map::new("ownedtokens");
+/// Offers for tokens, keyed by (token_id, purchaser)
+pub const OFFERS: Map<(String, String), Offer> = Map::new("offers");
+/// Tokens whose metadata has been individually frozen
+pub const FROZEN_METADATA: Map<String, bool> = Map::new("frozenmetadata");
+/// Present only while tokens are being migrated from the legacy format, holding the last migrated token ID
+pub const TOKEN_MIGRATION: Item<Option<String>> = Item::new("tokenmigration");
 
-pub fn mint_token(storage: &mut dyn Storage, token_id: String, token: Token) -> StdResult<()> {
+pub fn mint_token(
+    storage: &mut dyn Storage,
+    token_id: String,
+    token: Token,
+) -> Result<(), ContractError> {
     //Check if token with ID exists (may be None if token was burnt)
     let saved_token = TOKENS.may_load(storage, token_id.clone())?;
     if let Some(..) = saved_token {
-        Err(StdError::generic_err("Token with given ID already exists"))
+        Err(ContractError::TokenExists {})
     } else {
-        TOKENS.save(storage, token_id, &Some(token))
+        OWNED_TOKENS.save(storage, (token.owner.clone(), token_id.clone()), &true)?;
+        Ok(TOKENS.save(storage, token_id, &Some(token))?)
     }
 }
 
+/// Stores an existing token, moving it within the owner index if its owner has changed
+pub fn save_token(storage: &mut dyn Storage, token_id: String, token: &Token) -> StdResult<()> {
+    let saved_token = TOKENS.may_load(storage, token_id.clone())?.flatten();
+    if let Some(saved_token) = saved_token {
+        if saved_token.owner != token.owner {
+            OWNED_TOKENS.remove(storage, (saved_token.owner, token_id.clone()));
+        }
+    }
+    OWNED_TOKENS.save(storage, (token.owner.clone(), token_id.clone()), &true)?;
+    TOKENS.save(storage, token_id, &Some(token.clone()))
+}
+
+/// Removes a token and its owner index entry
+pub fn burn_token(storage: &mut dyn Storage, token_id: String) -> Result<(), ContractError> {
+    let token = load_token(storage, token_id.clone())?;
+    OWNED_TOKENS.remove(storage, (token.owner, token_id.clone()));
+    FROZEN_METADATA.remove(storage, token_id.clone());
+    TOKENS.remove(storage, token_id);
+    Ok(())
+}
+
+/// Whether a token's metadata can no longer be updated, either because it (or the collection) was frozen or because it is archived
+pub fn is_metadata_frozen(storage: &dyn Storage, token: &Token) -> Result<bool, ContractError> {
+    let config = CONFIG.load(storage)?;
+    let frozen = FROZEN_METADATA
+        .may_load(storage, token.token_id.clone())?
+        .unwrap_or_default();
+    Ok(token.archived || config.metadata_frozen || frozen)
+}
+
 pub fn load_token(storage: &dyn Storage, token_id: String) -> StdResult<Token> {
     let token = TOKENS.load(storage, token_id)?;
 
@@ -42,7 +105,7 @@ pub fn has_transfer_rights(
     env: &Env,
     addr: String,
     token: &Token,
-) -> StdResult<bool> {
+) -> Result<bool, ContractError> {
     Ok(token.owner.eq(&addr)
         || has_approval(env, &addr, token)
         || is_operator(storage, env, token.owner.clone(), addr.clone())?
@@ -68,7 +131,7 @@ pub fn is_operator(
     env: &Env,
     owner: String,
     addr: String,
-) -> StdResult<bool> {
+) -> Result<bool, ContractError> {
     let expiry = OPERATOR.may_load(storage, (owner, addr))?;
 
     match expiry {
@@ -77,11 +140,115 @@ pub fn is_operator(
     }
 }
 
+pub fn increment_minted_tokens(storage: &mut dyn Storage) -> StdResult<()> {
+    let minted = MINTED_TOKENS.may_load(storage)?.unwrap_or_default();
+    MINTED_TOKENS.save(storage, &(minted + 1))
+}
+
 pub fn increment_num_tokens(storage: &mut dyn Storage) -> StdResult<()> {
     let token_count = NUM_TOKENS.load(storage).unwrap_or_default();
     NUM_TOKENS.save(storage, &(token_count + 1))
 }
 
+#[derive(Serialize, Deserialize)]
+/// The stored format of `TransferAgreement` before agreed transfers could be priced in a CW20 token
+struct LegacyTransferAgreement {
+    amount: Coin,
+    purchaser: String,
+}
+
+#[derive(Serialize, Deserialize)]
+/// The stored format of `Token` before agreed transfers could be priced in a CW20 token
+struct LegacyToken {
+    token_id: String,
+    owner: String,
+    name: String,
+    publisher: String,
+    description: Option<String>,
+    approvals: Vec<Approval>,
+    transfer_agreement: Option<LegacyTransferAgreement>,
+    metadata: Option<TokenMetadata>,
+    archived: bool,
+    image: Option<String>,
+    pricing: Option<Coin>,
+}
+
+impl From<LegacyToken> for Token {
+    fn from(token: LegacyToken) -> Token {
+        Token {
+            token_id: token.token_id,
+            owner: token.owner,
+            name: token.name,
+            publisher: token.publisher,
+            description: token.description,
+            approvals: token.approvals,
+            transfer_agreement: token.transfer_agreement.map(|agreement| TransferAgreement {
+                amount: Funds::Native(agreement.amount),
+                purchaser: agreement.purchaser,
+            }),
+            metadata: token.metadata,
+            archived: token.archived,
+            image: token.image,
+            pricing: token.pricing,
+            royalty: None,
+        }
+    }
+}
+
+const LEGACY_TOKENS: Map<String, Option<LegacyToken>> = Map::new("ownership");
+
+/// Whether tokens are still being migrated from the legacy format
+pub fn is_token_migration_pending(storage: &dyn Storage) -> StdResult<bool> {
+    Ok(TOKEN_MIGRATION.may_load(storage)?.is_some())
+}
+
+/// Migrates up to `limit` tokens stored in the legacy format, continuing from the last migrated token.
+/// Native transfer agreement amounts are converted to `Funds` and each token is added to the owner index,
+/// which did not exist when legacy tokens were minted.
+/// Must only be run while a token migration is pending, as tokens are read in the legacy format.
+///
+/// Returns whether any tokens may remain to be migrated, in which case the migration remains pending.
+pub fn migrate_tokens(storage: &mut dyn Storage, limit: u32) -> Result<bool, ContractError> {
+    let start = TOKEN_MIGRATION
+        .may_load(storage)?
+        .flatten()
+        .map(Bound::exclusive);
+    let legacy_tokens: Vec<(Vec<u8>, Option<LegacyToken>)> = LEGACY_TOKENS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit as usize)
+        .collect::<StdResult<_>>()?;
+    let pending = legacy_tokens.len() == limit as usize;
+
+    let mut last_token_id = None;
+    for (key, token) in legacy_tokens {
+        let token_id = String::from_utf8(key).map_err(StdError::from)?;
+        let token = token.map(Token::from);
+        if let Some(token) = &token {
+            OWNED_TOKENS.save(storage, (token.owner.clone(), token_id.clone()), &true)?;
+        }
+        TOKENS.save(storage, token_id.clone(), &token)?;
+        last_token_id = Some(token_id);
+    }
+
+    if pending {
+        TOKEN_MIGRATION.save(storage, &last_token_id)?;
+    } else {
+        TOKEN_MIGRATION.remove(storage);
+    }
+
+    Ok(pending)
+}
+
+/// Whether the given address is the minter or holds the `minter` role
+pub fn is_minter(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    addr: &str,
+) -> Result<bool, ContractError> {
+    let config = CONFIG.load(storage)?;
+    Ok(config.minter == addr || has_role(storage, block, ROLE_MINTER, addr)?)
+}
+
 pub fn read_config(storage: &dyn Storage) -> StdResult<TokenConfig> {
     CONFIG.load(storage)
 }
@@ -90,13 +257,11 @@ pub fn store_config(storage: &mut dyn Storage, config: &TokenConfig) -> StdResul
     CONFIG.save(storage, config)
 }
 
-pub fn decrement_num_tokens(storage: &mut dyn Storage) -> StdResult<()> {
+pub fn decrement_num_tokens(storage: &mut dyn Storage) -> Result<(), ContractError> {
     let token_count = NUM_TOKENS.load(storage).unwrap_or_default();
     if token_count == 0 {
-        Err(StdError::generic_err(
-            "Cannot decrement token count below 0",
-        ))
+        Err(ContractError::TokenCountUnderflow {})
     } else {
-        NUM_TOKENS.save(storage, &(token_count - 1))
+        Ok(NUM_TOKENS.save(storage, &(token_count - 1))?)
     }
 }
@@ -4,7 +4,8 @@ use andromeda_protocol::{
     address_list::InstantiateMsg as AddressListInstantiateMsg,
     modules::{
         address_list::{ADDRESS_LIST_CONTRACT, REPLY_ADDRESS_LIST},
-        ModuleDefinition, Rate,
+        common::Funds,
+        AddressWeight, ModuleDefinition, Rate,
     },
     receipt::{ExecuteMsg as ReceiptExecuteMsg, Receipt},
     testing::mock_querier::mock_dependencies_custom,
@@ -13,7 +14,7 @@ use andromeda_protocol::{
 use cosmwasm_std::{
     attr, coin,
     testing::{mock_env, mock_info},
-    to_binary, BankMsg, CosmosMsg, Event, ReplyOn, Response, SubMsg, Uint128, WasmMsg,
+    to_binary, BankMsg, CosmosMsg, Event, ReplyOn, Response, SubMsg, WasmMsg,
 };
 use cw_storage_plus::Item;
 
@@ -29,9 +30,9 @@ fn test_token_modules() {
     let env = mock_env();
     let whitelist_moderators = "creator".to_string();
     let tax_fee: Rate = Rate::Percent(1u64);
-    let tax_receivers = vec!["tax_recever1".to_string()];
+    let tax_receivers = vec![AddressWeight::new("tax_recever1", 1)];
     let royality_fee: Rate = Rate::Percent(1u64);
-    let royality_receivers = vec!["royality_recever1".to_string()];
+    let royality_receivers = vec![AddressWeight::new("royality_recever1", 1)];
     let modules = vec![
         ModuleDefinition::Whitelist {
             moderators: Some(vec![whitelist_moderators]),
@@ -42,11 +43,13 @@ fn test_token_modules() {
             rate: tax_fee,
             receivers: tax_receivers,
             description: None,
+            rounding: None,
         },
         ModuleDefinition::Royalties {
             rate: royality_fee,
             receivers: royality_receivers,
             description: None,
+            rounding: None,
         },
         ModuleDefinition::Receipt {
             address: Some("receipt_contract_address".to_string()),
@@ -58,6 +61,10 @@ fn test_token_modules() {
         name: TOKEN_NAME.to_string(),
         symbol: TOKEN_SYMBOL.to_string(),
         modules,
+        max_supply: None,
+        mint_start: None,
+        mint_end: None,
+        metadata_update_policy: None,
         minter: String::from("creator"),
     };
 
@@ -96,12 +103,13 @@ fn test_token_modules() {
     //test token_mint
     let mint_msg = MintMsg {
         token_id: "token_id1".to_string(),
-        owner: "".to_string(),
+        owner: "creator".to_string(),
         description: Some("Test Token".to_string()),
         name: "TestToken".to_string(),
         metadata: None,
         image: None,
         pricing: None,
+        royalty: None,
     };
     let res = execute(
         deps.as_mut(),
@@ -138,8 +146,7 @@ fn test_token_modules() {
     // test transfer_agreement
     let transfer_agreement_msg = ExecuteMsg::TransferAgreement {
         token_id: "token_id1".to_string(),
-        denom: "uusd".to_string(),
-        amount: Uint128::from(100u128),
+        amount: Funds::Native(coin(100, "uusd")),
         purchaser: "purchaser1".to_string(),
     };
     let res = execute(
@@ -165,7 +172,13 @@ fn test_token_modules() {
         token_id: "token_id1".to_string(),
     };
 
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), transfernft_msg).unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("creator", &[coin(101, "uusd")]),
+        transfernft_msg,
+    )
+    .unwrap();
     let expected_res = Response::default()
         .add_submessages(vec![
             SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
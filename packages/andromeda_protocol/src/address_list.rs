@@ -1,21 +1,17 @@
+use crate::error::ContractError;
 use cosmwasm_std::{to_binary, QuerierWrapper, QueryRequest, StdResult, Storage, WasmQuery};
+use cw721::Expiration;
 use cw_storage_plus::Map;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 pub const ADDRESS_LIST: Map<String, bool> = Map::new("addresslist");
 
+/// Addresses allowed to add/remove addresses from the list hold the `moderator` role.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Eq)]
-pub struct AddressList {
-    /// A list of addresses allowed to add/remove addresses from the list
-    pub moderators: Vec<String>,
-}
+pub struct AddressList {}
 
 impl AddressList {
-    /// Check if an address is a moderator of the address list.
-    pub fn is_moderator(&self, addr: &String) -> bool {
-        self.moderators.contains(addr)
-    }
     /// Add an address to the address list.
     pub fn add_address(&self, storage: &mut dyn Storage, addr: &String) -> StdResult<()> {
         ADDRESS_LIST.save(storage, addr.clone(), &true)
@@ -49,7 +45,7 @@ pub fn query_includes_address(
     querier: QuerierWrapper,
     contract_addr: String,
     address: String,
-) -> StdResult<bool> {
+) -> Result<bool, ContractError> {
     let res: IncludesAddressResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr,
         msg: to_binary(&QueryMsg::IncludesAddress { address })?,
@@ -60,6 +56,7 @@ pub fn query_includes_address(
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
+    /// Addresses granted the `moderator` role
     pub moderators: Vec<String>,
 }
 
@@ -70,8 +67,29 @@ pub enum ExecuteMsg {
     AddAddress { address: String },
     /// Remove an address from the address list
     RemoveAddress { address: String },
-    /// Update ownership of the contract
-    UpdateOwner { address: String },
+    /// Propose a new owner of the contract, replacing any pending proposal. Only executable by the current contract owner.
+    ProposeOwner {
+        /// The address of the proposed contract owner.
+        address: String,
+        /// When the proposal lapses if it has not been accepted.
+        expiration: Option<Expiration>,
+    },
+    /// Accept a pending ownership proposal. Only executable by the proposed owner.
+    AcceptOwnership {},
+    /// Withdraw a pending ownership proposal. Only executable by the current contract owner.
+    CancelOwnershipProposal {},
+    /// Permanently give up ownership of the contract. Only executable by the current contract owner.
+    RenounceOwnership {},
+    /// Grant a role to an address, optionally until an expiration. Only executable by the current contract owner.
+    GrantRole {
+        /// The name of the role, such as "moderator", "minter" or "operator".
+        role: String,
+        address: String,
+        /// When the role lapses. Defaults to never.
+        expiration: Option<Expiration>,
+    },
+    /// Revoke a role from an address. Only executable by the current contract owner.
+    RevokeRole { role: String, address: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -81,8 +99,23 @@ pub enum QueryMsg {
     IncludesAddress { address: String },
     /// Query the current contract owner
     ContractOwner {},
+    /// The pending ownership proposal, if any
+    PendingOwner {},
+    /// Whether an address currently holds a role
+    HasRole { role: String, address: String },
+    /// The addresses currently holding a role
+    RoleMembers {
+        role: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// The stored contract name and version
+    Version {},
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 pub struct IncludesAddressResponse {
     /// Whether the address is included in the address list
@@ -0,0 +1,386 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+/// The error type shared by every Andromeda contract. Contracts and the `require` helper return this
+/// rather than a generic `StdError` so callers can match on the reason an operation failed.
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    // Authorization
+    #[error("May only be used by the contract owner")]
+    Unauthorized {},
+
+    #[error("Address is not authorized")]
+    AddressNotAuthorized {},
+
+    #[error("Address does not have transfer rights for this token")]
+    TransferUnauthorized {},
+
+    #[error("Address is not permitted to update metadata for this token")]
+    MetadataUpdateUnauthorized {},
+
+    #[error("Only a minter can mint tokens")]
+    MintUnauthorized {},
+
+    #[error("Minter is on blacklist. Not allowed to mint.")]
+    MinterBlacklisted {},
+
+    #[error("Only a moderator can add an address to the address list")]
+    AddAddressUnauthorized {},
+
+    #[error("Only a moderator can remove an address from the address list")]
+    RemoveAddressUnauthorized {},
+
+    #[error("Only the contract owner, the assigned minter or a moderator can mint a receipt")]
+    MintReceiptUnauthorized {},
+
+    #[error("Only the contract owner, the assigned minter or a moderator can edit a receipt")]
+    EditReceiptUnauthorized {},
+
+    #[error("Ownership of this contract can only be transferred by the current owner")]
+    OwnershipTransferUnauthorized {},
+
+    #[error("There is no pending ownership proposal")]
+    NoPendingOwner {},
+
+    #[error("Only the proposed owner can accept ownership")]
+    NotPendingOwner {},
+
+    #[error("The ownership proposal has expired")]
+    OwnershipProposalExpired {},
+
+    #[error("Role names must be 1 to 32 lowercase letters, digits or underscores")]
+    InvalidRoleName {},
+
+    #[error("Address does not have the given role")]
+    RoleNotHeld {},
+
+    #[error("Cannot update address for ADO that you did not create")]
+    UpdateAddressUnauthorized {},
+
+    #[error("Only the token owner can add approvals")]
+    AddApprovalUnauthorized {},
+
+    #[error("Only the token owner can remove approvals")]
+    RemoveApprovalUnauthorized {},
+
+    #[error("Only the token owner can create a transfer agreement")]
+    TransferAgreementUnauthorized {},
+
+    #[error("Cannot accept an offer for a token you do not own")]
+    AcceptOfferUnauthorized {},
+
+    #[error("Cannot archive a token you do not own")]
+    ArchiveUnauthorized {},
+
+    #[error("Cannot auction a token you do not own")]
+    AuctionUnauthorized {},
+
+    #[error("Cannot burn a token you do not own")]
+    BurnUnauthorized {},
+
+    #[error("Cannot update pricing for a token you do not own")]
+    UpdatePricingUnauthorized {},
+
+    #[error("Only the seller can cancel an auction")]
+    CancelAuctionUnauthorized {},
+
+    // Tokens
+    #[error("This token is archived and cannot be changed in any way.")]
+    TokenArchived {},
+
+    #[error("Token with given ID already exists")]
+    TokenExists {},
+
+    #[error("Cannot decrement token count below 0")]
+    TokenCountUnderflow {},
+
+    #[error("A batch must include at least one token")]
+    EmptyBatch {},
+
+    #[error("Address is already a minter")]
+    AlreadyMinter {},
+
+    #[error("Address is not an additional minter")]
+    NotAdditionalMinter {},
+
+    #[error("Max supply has been reached")]
+    MaxSupplyReached {},
+
+    #[error("Max supply must be greater than zero")]
+    InvalidMaxSupply {},
+
+    #[error("Mint start must be before mint end")]
+    InvalidMintWindow {},
+
+    #[error("Minting has not started")]
+    MintingNotStarted {},
+
+    #[error("Minting has ended")]
+    MintingEnded {},
+
+    #[error("Metadata for this token is frozen")]
+    MetadataFrozen {},
+
+    #[error("Collection metadata is already frozen")]
+    CollectionMetadataFrozen {},
+
+    #[error("Name must be between 3 and 30 characters.")]
+    InvalidNameLength {},
+
+    #[error("Name is illegal to be initialized.")]
+    IllegalName {},
+
+    #[error("Symbol is illegal to be used.")]
+    IllegalSymbol {},
+
+    #[error("Symbol is in use")]
+    SymbolInUse {},
+
+    // Agreed transfers and payments
+    #[error(
+        "Agreed transfers priced in a CW20 token must be paid through the CW20 token contract"
+    )]
+    Cw20PaymentRequired {},
+
+    #[error("Token does not have a transfer agreement priced in a CW20 token")]
+    NoCw20TransferAgreement {},
+
+    #[error("Invalid CW20 token sent for the agreed transfer")]
+    InvalidCw20Token {},
+
+    #[error("Insufficient funds sent for the agreed transfer")]
+    InsufficientFunds {},
+
+    #[error("Not enough funds to deduct payment")]
+    NotEnoughFundsToDeduct {},
+
+    #[error("No pending payments for the given address!")]
+    NoPendingPayments {},
+
+    #[error("Flat rate fees for CW20 payments must be denominated in the CW20 token")]
+    InvalidCw20FlatRate {},
+
+    #[error("Rate must be between 0 and 100%")]
+    InvalidRate {},
+
+    // Escrow: offers, auctions and timelocks
+    #[error("Offer does not exist")]
+    OfferNotFound {},
+
+    #[error("Offer has expired")]
+    OfferExpired {},
+
+    #[error("Offers must include funds of exactly one denomination")]
+    InvalidOfferFunds {},
+
+    #[error("An offer from this address already exists")]
+    OfferExists {},
+
+    #[error("Cannot place an offer that has already expired")]
+    OfferAlreadyExpired {},
+
+    #[error("Cannot place an offer for a token you own")]
+    OfferOnOwnToken {},
+
+    #[error("Auction has ended")]
+    AuctionEnded {},
+
+    #[error("Auction has not ended")]
+    AuctionNotEnded {},
+
+    #[error("Auctions are not enabled for this contract")]
+    AuctionsNotEnabled {},
+
+    #[error("Bid is below the current price")]
+    BidTooLow {},
+
+    #[error("Bids must only include funds in the auction's denomination")]
+    InvalidBidFunds {},
+
+    #[error("Cannot bid on your own auction")]
+    BidOnOwnAuction {},
+
+    #[error("Cannot cancel an auction with bids")]
+    AuctionHasBids {},
+
+    #[error("Token is currently being auctioned")]
+    TokenAuctioned {},
+
+    #[error("Token is not being auctioned")]
+    TokenNotAuctioned {},
+
+    #[error("Cannot remove the auction module while auctions are open")]
+    AuctionsOpen {},
+
+    #[error("The seller no longer owns the auctioned token")]
+    AuctionSellerNotOwner {},
+
+    #[error("Cannot escrow empty funds")]
+    EmptyEscrow {},
+
+    #[error("Cannot escrow funds with no expiration")]
+    MissingEscrowExpiration {},
+
+    #[error("Tokens are being migrated, the migration must be completed with MigrateTokens")]
+    TokenMigrationPending {},
+
+    #[error("There is no pending token migration")]
+    NoTokenMigration {},
+
+    #[error("Cannot overwrite Held Funds")]
+    FundsAlreadyHeld {},
+
+    #[error("Cannot set expiration in the past")]
+    ExpirationInPast {},
+
+    #[error("Escrow recipient must be a valid address")]
+    InvalidEscrowRecipient {},
+
+    #[error("Payments for an escrowed transfer must be in the escrowed denomination")]
+    InvalidEscrowedPayment {},
+
+    #[error("Payments for an agreed transfer must be in the agreed denomination")]
+    InvalidAgreedPayment {},
+
+    #[error("No locked funds for your address")]
+    NoLockedFunds {},
+
+    #[error("Your funds are still locked")]
+    FundsLocked {},
+
+    // Splitter
+    #[error("The splitter is currently locked")]
+    SplitterLocked {},
+
+    #[error("Exceeds max amount of coins allowed.")]
+    ExceedsMaxCoins {},
+
+    #[error("No coin sent")]
+    NoFunds {},
+
+    #[error("No balance available to withdraw")]
+    NoBalance {},
+
+    #[error("The amount received by the recipients should not exceed 100%")]
+    InvalidRecipientPercent {},
+
+    #[error("The recipients list must include at least one recipient")]
+    EmptyRecipientsList {},
+
+    #[error("Recipient percentages must total 100 to distribute dust to a recipient")]
+    IncompleteSplitForDustPolicy {},
+
+    #[error("The remainder address must be a valid address")]
+    InvalidRemainderAddress {},
+
+    #[error("Recipient contract {addr} has no message for the funds being split")]
+    MissingRecipientMsg { addr: String },
+
+    // Module validation
+    #[error("Any address list module must be unique")]
+    DuplicateAddressList {},
+
+    #[error("An address list module cannot be included alongside an address list module of the opposing type")]
+    IncompatibleAddressLists {},
+
+    #[error("Address list must include either a contract address or a code id and moderator list")]
+    InvalidAddressListModule {},
+
+    #[error("Address list does not have an assigned contract address")]
+    MissingAddressListContract {},
+
+    #[error("The auction module must be unique")]
+    DuplicateAuctionModule {},
+
+    #[error("Auction duration must be greater than zero")]
+    InvalidAuctionDuration {},
+
+    #[error("Auction extension window must be greater than zero")]
+    InvalidExtensionWindow {},
+
+    #[error("Minimum bid increment must be greater than zero")]
+    InvalidBidIncrement {},
+
+    #[error("Start price must be greater than end price")]
+    InvalidDutchAuctionPrices {},
+
+    #[error("Cannot apply a royalty with no receiving addresses")]
+    NoRoyaltyReceivers {},
+
+    #[error("Cannot apply a tax with no receiving addresses")]
+    NoTaxReceivers {},
+
+    #[error("Royalty rate must be non-zero")]
+    InvalidRoyaltyRate {},
+
+    #[error("Royalties must be in the denomination of the sale")]
+    RoyaltyDenomMismatch {},
+
+    #[error("Tax rate must be non-zero")]
+    InvalidTaxRate {},
+
+    #[error("Rate must be less than 100%")]
+    RateTooHigh {},
+
+    #[error("Receiver weights must be non-zero")]
+    InvalidReceiverWeight {},
+
+    #[error("Module description can be at most 200 characters long")]
+    DescriptionTooLong {},
+
+    #[error("Module index out of range")]
+    ModuleIndexOutOfRange {},
+
+    #[error("The receipt module must be unique")]
+    DuplicateReceiptModule {},
+
+    #[error("Receipt must include either a contract address or a code id and moderator list")]
+    InvalidReceiptModule {},
+
+    #[error("Receipt module does not have an assigned address")]
+    MissingReceiptContract {},
+
+    #[error("Custom module must include a contract address")]
+    MissingCustomModuleAddress {},
+
+    #[error("The custom module address must be a valid address")]
+    InvalidCustomModuleAddress {},
+
+    #[error("Custom module must forward at least one hook")]
+    NoCustomHooks {},
+
+    #[error("Custom module hooks must be unique")]
+    DuplicateCustomHook {},
+
+    #[error("The execute hook may only be forwarded as a query")]
+    InvalidExecuteHookForward {},
+
+    #[error("Custom module priority {priority} conflicts with the priority of another module")]
+    CustomModulePriorityConflict { priority: u64 },
+
+    #[error("{reason}")]
+    CustomModuleRejection { reason: String },
+
+    // Replies and instantiation
+    #[error("reply id is invalid")]
+    InvalidReplyId {},
+
+    #[error("Must provide one of the following: \"receipt_code_id\", \"token_code_id\", \"address_list_code_id\"")]
+    MissingCodeId {},
+
+    // Versioning and migration
+    #[error("Invalid contract version: {version}")]
+    InvalidContractVersion { version: String },
+
+    #[error("Cannot migrate from contract {found} to {expected}")]
+    ContractNameMismatch { expected: String, found: String },
+
+    #[error("Cannot migrate from version {current} to older version {new}")]
+    CannotMigrateToOlderVersion { current: String, new: String },
+}
@@ -1,4 +1,5 @@
 use crate::modules::ModuleDefinition;
+use cw721::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -29,8 +30,19 @@ pub enum ExecuteMsg {
         address_list_code_id: Option<u64>,
         token_code_id: Option<u64>,
     },
-    /// Update current contract owner
-    UpdateOwner { address: String },
+    /// Propose a new owner of the contract, replacing any pending proposal. Only executable by the current contract owner.
+    ProposeOwner {
+        /// The address of the proposed contract owner.
+        address: String,
+        /// When the proposal lapses if it has not been accepted.
+        expiration: Option<Expiration>,
+    },
+    /// Accept a pending ownership proposal. Only executable by the proposed owner.
+    AcceptOwnership {},
+    /// Withdraw a pending ownership proposal. Only executable by the current contract owner.
+    CancelOwnershipProposal {},
+    /// Permanently give up ownership of the contract. Only executable by the current contract owner.
+    RenounceOwnership {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -42,8 +54,15 @@ pub enum QueryMsg {
     CodeIds {},
     /// The current contract owner
     ContractOwner {},
+    /// The pending ownership proposal, if any
+    PendingOwner {},
+    /// The stored contract name and version
+    Version {},
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct AddressResponse {
     pub address: String,
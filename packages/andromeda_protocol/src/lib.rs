@@ -1,11 +1,13 @@
-use cosmwasm_std::{StdError, StdResult};
+use crate::error::ContractError;
 
 pub mod address_list;
+pub mod error;
 pub mod factory;
 pub mod modules;
 pub mod ownership;
 pub mod receipt;
 pub mod response;
+pub mod roles;
 pub mod splitter;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -13,6 +15,7 @@ pub mod testing;
 
 pub mod timelock;
 pub mod token;
+pub mod version;
 
 /// A simple implementation of Solidity's "require" function. Takes a precondition and an error to return if the precondition is not met.
 ///
@@ -23,11 +26,10 @@ pub mod token;
 ///
 /// ## Example
 /// ```
-/// use cosmwasm_std::StdError;
-/// use andromeda_protocol::require;
-/// require(false, StdError::generic_err("Some boolean condition was not met"));
+/// use andromeda_protocol::{error::ContractError, require};
+/// require(false, ContractError::Unauthorized {});
 /// ```
-pub fn require(precond: bool, err: StdError) -> StdResult<bool> {
+pub fn require(precond: bool, err: ContractError) -> Result<bool, ContractError> {
     match precond {
         true => Ok(true),
         false => Err(err),
@@ -1,6 +1,6 @@
+use crate::error::ContractError;
 use cosmwasm_std::{
-    to_binary, DepsMut, Env, MessageInfo, Reply, ReplyOn, Response, StdError, StdResult, Storage,
-    SubMsg, WasmMsg,
+    to_binary, DepsMut, Env, MessageInfo, Reply, ReplyOn, Response, Storage, SubMsg, WasmMsg,
 };
 use cw_storage_plus::Item;
 use schemars::JsonSchema;
@@ -35,18 +35,18 @@ pub struct AddressListModule {
 
 impl AddressListModule {
     /// Helper function to query the address list contract to determine if the provided address is authorized
-    pub fn is_authorized(self, deps: &DepsMut, address: String) -> StdResult<bool> {
+    pub fn is_authorized(self, deps: &DepsMut, address: String) -> Result<bool, ContractError> {
         let contract_addr = self.get_contract_address(deps.storage);
         require(
             contract_addr.is_some(),
-            StdError::generic_err("Address list does not have an assigned contract address"),
+            ContractError::MissingAddressListContract {},
         )?;
 
         let includes_address =
             query_includes_address(deps.querier, contract_addr.unwrap(), address)?;
         require(
             includes_address == self.inclusive,
-            StdError::generic_err("Address is not authorized"),
+            ContractError::AddressNotAuthorized {},
         )?;
 
         Ok(true)
@@ -59,10 +59,10 @@ impl Module for AddressListModule {
     /// * Must be unique
     /// * Cannot be included alongside an address list of the opposite type (no mixing whitelist/blacklist)
     /// * Must include either a contract address or a combination of a valid code id and an optional vector of moderating addresses
-    fn validate(&self, all_modules: Vec<ModuleDefinition>) -> StdResult<bool> {
+    fn validate(&self, all_modules: Vec<ModuleDefinition>) -> Result<bool, ContractError> {
         require(
             is_unique(self, &all_modules),
-            StdError::generic_err("Any address list module must be unique"),
+            ContractError::DuplicateAddressList {},
         )?;
 
         //Test to see if the opposite address list type is present
@@ -77,12 +77,12 @@ impl Module for AddressListModule {
 
         require(
             is_unique(&opposite_module, &includes_opposite),
-            StdError::generic_err("An address list module cannot be included alongside an address list module of the opposing type"),
+            ContractError::IncompatibleAddressLists {},
         )?;
 
         require(
             self.address.is_some() || (self.code_id.is_some() && self.moderators.is_some()),
-            StdError::generic_err("Address list must include either a contract address or a code id and moderator list"),
+            ContractError::InvalidAddressListModule {},
         )?;
 
         Ok(true)
@@ -117,7 +117,7 @@ impl MessageHooks for AddressListModule {
         _deps: &DepsMut,
         info: MessageInfo,
         _env: Env,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         let mut res = HookResponse::default();
         if self.address.is_none() {
             let inst_msg = WasmMsg::Instantiate {
@@ -143,7 +143,12 @@ impl MessageHooks for AddressListModule {
         Ok(res)
     }
     /// On any execute message, validates that the sender is authorized by the address list
-    fn on_execute(&self, deps: &DepsMut, info: MessageInfo, _env: Env) -> StdResult<HookResponse> {
+    fn on_execute(
+        &self,
+        deps: &DepsMut,
+        info: MessageInfo,
+        _env: Env,
+    ) -> Result<HookResponse, ContractError> {
         self.clone().is_authorized(deps, info.sender.to_string())?;
 
         Ok(HookResponse::default())
@@ -151,7 +156,7 @@ impl MessageHooks for AddressListModule {
 }
 
 /// Used to stored the contract address once the contract is instantiated
-pub fn on_address_list_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
+pub fn on_address_list_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
     let contract_addr = get_reply_address(msg)?;
 
     ADDRESS_LIST_CONTRACT.save(deps.storage, &contract_addr.to_string())?;
@@ -182,6 +187,7 @@ mod tests {
                 rate: Rate::Percent(2),
                 receivers: vec![],
                 description: None,
+                rounding: None,
             },
         ];
 
@@ -195,9 +201,7 @@ mod tests {
 
         assert_eq!(
             al.validate(modules.to_vec()),
-            Err(StdError::generic_err(
-                "Any address list module must be unique"
-            ))
+            Err(ContractError::DuplicateAddressList {})
         );
 
         let modules = vec![
@@ -206,6 +210,7 @@ mod tests {
                 rate: Rate::Percent(2),
                 receivers: vec![],
                 description: None,
+                rounding: None,
             },
             ModuleDefinition::Blacklist {
                 moderators: Some(vec![]),
@@ -216,7 +221,7 @@ mod tests {
 
         assert_eq!(
             al.validate(modules.to_vec()),
-            Err(StdError::generic_err("An address list module cannot be included alongside an address list module of the opposing type"))
+            Err(ContractError::IncompatibleAddressLists {})
         );
     }
 
@@ -238,7 +243,7 @@ mod tests {
             .on_execute(&deps.as_mut(), info.clone(), env.clone())
             .unwrap_err();
 
-        assert_eq!(resp, StdError::generic_err("Address is not authorized"));
+        assert_eq!(resp, ContractError::AddressNotAuthorized {});
 
         let valid_addresslist = AddressListModule {
             moderators: Some(vec![]),
@@ -0,0 +1,396 @@
+use crate::error::ContractError;
+use cosmwasm_std::{BlockInfo, DepsMut, Env, MessageInfo, Storage, Uint128};
+use cw_storage_plus::Map;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    modules::{
+        common::is_unique,
+        hooks::{HookResponse, MessageHooks},
+        read_modules, Module, ModuleDefinition,
+    },
+    require,
+};
+
+/// Any active auctions, keyed by token ID
+pub const AUCTIONS: Map<String, Auction> = Map::new("auctions");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// The pricing mechanism of an auction
+pub enum AuctionType {
+    /// Ascending bids, the highest bid once the auction has ended wins the token
+    English {
+        /// The minimum amount of the first bid
+        min_bid: Uint128,
+        /// The amount by which each bid must exceed the current highest bid
+        min_increment: Uint128,
+    },
+    /// A declining price, the first bid matching the current price wins the token
+    Dutch {
+        /// The price at the start of the auction
+        start_price: Uint128,
+        /// The price at the end of the auction
+        end_price: Uint128,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// A bid placed in an English auction, the bid amount is held in escrow by the token contract
+pub struct Bid {
+    /// The address that placed the bid
+    pub bidder: String,
+    /// The amount of the bid, in the auction's denomination
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// An auction of a token
+pub struct Auction {
+    /// The owner of the token when the auction was started
+    pub seller: String,
+    /// The pricing mechanism of the auction
+    pub auction_type: AuctionType,
+    /// The denomination in which bids must be made
+    pub denom: String,
+    /// The start time of the auction, in seconds
+    pub start_time: u64,
+    /// The end time of the auction, in seconds. May be extended by late bids in an English auction.
+    pub end_time: u64,
+    /// The current highest bid of an English auction
+    pub highest_bid: Option<Bid>,
+}
+
+impl Auction {
+    /// Validates the auction:
+    /// * Auction must end after it starts
+    /// * English auctions must have a non-zero minimum bid increment
+    /// * Dutch auctions must have a start price greater than their end price
+    pub fn validate(&self) -> Result<bool, ContractError> {
+        require(
+            self.end_time > self.start_time,
+            ContractError::InvalidAuctionDuration {},
+        )?;
+        match self.auction_type {
+            AuctionType::English { min_increment, .. } => {
+                require(
+                    !min_increment.is_zero(),
+                    ContractError::InvalidBidIncrement {},
+                )?;
+            }
+            AuctionType::Dutch {
+                start_price,
+                end_price,
+            } => {
+                require(
+                    start_price > end_price,
+                    ContractError::InvalidDutchAuctionPrices {},
+                )?;
+            }
+        }
+
+        Ok(true)
+    }
+    pub fn is_ended(&self, block: &BlockInfo) -> bool {
+        block.time.seconds() >= self.end_time
+    }
+    /// The minimum amount required for a bid to be accepted.
+    /// For English auctions this is the minimum next bid, for Dutch auctions it is the current price.
+    /// Errors if the minimum next bid of an English auction overflows.
+    pub fn current_price(&self, block: &BlockInfo) -> Result<Uint128, ContractError> {
+        match self.auction_type {
+            AuctionType::English {
+                min_bid,
+                min_increment,
+            } => match &self.highest_bid {
+                Some(bid) => Ok(bid.amount.checked_add(min_increment)?),
+                None => Ok(min_bid),
+            },
+            AuctionType::Dutch {
+                start_price,
+                end_price,
+            } => {
+                let now = block.time.seconds().min(self.end_time);
+                let elapsed = now.saturating_sub(self.start_time);
+                let duration = self.end_time - self.start_time;
+                let decline = (start_price - end_price).multiply_ratio(elapsed, duration);
+                Ok(start_price - decline)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// A struct used to define the Auction module. Enables the auctioning of tokens within the token contract.
+/// Tokens cannot be transferred, burnt or archived while being auctioned.
+pub struct AuctionModule {
+    /// Bids placed on an English auction within this many seconds of its end extend the auction to this many seconds after the bid
+    pub extension_window: Option<u64>,
+}
+
+impl AuctionModule {
+    fn require_not_auctioned(
+        &self,
+        storage: &dyn Storage,
+        token_id: String,
+    ) -> Result<bool, ContractError> {
+        require(
+            AUCTIONS.may_load(storage, token_id)?.is_none(),
+            ContractError::TokenAuctioned {},
+        )
+    }
+}
+
+impl Module for AuctionModule {
+    /// Validates the auction module:
+    /// * Module must be unique
+    /// * Any extension window must be non-zero
+    fn validate(&self, all_modules: Vec<ModuleDefinition>) -> Result<bool, ContractError> {
+        require(
+            is_unique(self, &all_modules),
+            ContractError::DuplicateAuctionModule {},
+        )?;
+        require(
+            self.extension_window != Some(0),
+            ContractError::InvalidExtensionWindow {},
+        )?;
+
+        Ok(true)
+    }
+    fn as_definition(&self) -> ModuleDefinition {
+        ModuleDefinition::Auction {
+            extension_window: self.extension_window,
+        }
+    }
+}
+
+impl MessageHooks for AuctionModule {
+    /// Errors if the token is currently being auctioned
+    fn on_transfer(
+        &self,
+        deps: &DepsMut,
+        _info: MessageInfo,
+        _env: Env,
+        _recipient: String,
+        token_id: String,
+    ) -> Result<HookResponse, ContractError> {
+        self.require_not_auctioned(deps.storage, token_id)?;
+
+        Ok(HookResponse::default())
+    }
+    /// Errors if the token is currently being auctioned
+    fn on_send(
+        &self,
+        deps: &DepsMut,
+        _info: MessageInfo,
+        _env: Env,
+        _contract: String,
+        token_id: String,
+    ) -> Result<HookResponse, ContractError> {
+        self.require_not_auctioned(deps.storage, token_id)?;
+
+        Ok(HookResponse::default())
+    }
+    /// Errors if the token is currently being auctioned
+    fn on_transfer_agreement(
+        &self,
+        deps: &DepsMut,
+        _info: MessageInfo,
+        _env: Env,
+        token_id: String,
+        _purchaser: String,
+        _amount: u128,
+        _denom: String,
+    ) -> Result<HookResponse, ContractError> {
+        self.require_not_auctioned(deps.storage, token_id)?;
+
+        Ok(HookResponse::default())
+    }
+    /// Errors if the token is currently being auctioned
+    fn on_burn(
+        &self,
+        deps: &DepsMut,
+        _info: MessageInfo,
+        _env: Env,
+        token_id: String,
+    ) -> Result<HookResponse, ContractError> {
+        self.require_not_auctioned(deps.storage, token_id)?;
+
+        Ok(HookResponse::default())
+    }
+    /// Errors if the token is currently being auctioned
+    fn on_archive(
+        &self,
+        deps: &DepsMut,
+        _info: MessageInfo,
+        _env: Env,
+        token_id: String,
+    ) -> Result<HookResponse, ContractError> {
+        self.require_not_auctioned(deps.storage, token_id)?;
+
+        Ok(HookResponse::default())
+    }
+}
+
+/// Gets the auction module of the current contract, if it exists
+pub fn get_auction_module(storage: &dyn Storage) -> Result<Option<AuctionModule>, ContractError> {
+    let modules = read_modules(storage)?;
+    let auction_def = modules.module_defs.iter().find_map(|m| match m {
+        ModuleDefinition::Auction { extension_window } => Some(AuctionModule {
+            extension_window: *extension_window,
+        }),
+        _ => None,
+    });
+
+    Ok(auction_def)
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{
+        testing::{mock_dependencies, mock_env, mock_info},
+        Timestamp,
+    };
+
+    use super::*;
+
+    fn mock_auction(auction_type: AuctionType) -> Auction {
+        Auction {
+            seller: "seller".to_string(),
+            auction_type,
+            denom: "uluna".to_string(),
+            start_time: 100,
+            end_time: 200,
+            highest_bid: None,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let auction = mock_auction(AuctionType::English {
+            min_bid: Uint128::from(10u128),
+            min_increment: Uint128::zero(),
+        });
+        assert_eq!(
+            auction.validate().unwrap_err(),
+            ContractError::InvalidBidIncrement {}
+        );
+
+        let auction = mock_auction(AuctionType::Dutch {
+            start_price: Uint128::from(10u128),
+            end_price: Uint128::from(10u128),
+        });
+        assert_eq!(
+            auction.validate().unwrap_err(),
+            ContractError::InvalidDutchAuctionPrices {}
+        );
+
+        let auction = Auction {
+            end_time: 100,
+            ..mock_auction(AuctionType::Dutch {
+                start_price: Uint128::from(20u128),
+                end_price: Uint128::from(10u128),
+            })
+        };
+        assert_eq!(
+            auction.validate().unwrap_err(),
+            ContractError::InvalidAuctionDuration {}
+        );
+
+        let module = AuctionModule {
+            extension_window: Some(0),
+        };
+        assert_eq!(
+            module.validate(vec![module.as_definition()]).unwrap_err(),
+            ContractError::InvalidExtensionWindow {}
+        );
+    }
+
+    #[test]
+    fn test_current_price() {
+        let mut block = mock_env().block;
+
+        let mut auction = mock_auction(AuctionType::English {
+            min_bid: Uint128::from(10u128),
+            min_increment: Uint128::from(5u128),
+        });
+        assert_eq!(
+            auction.current_price(&block).unwrap(),
+            Uint128::from(10u128)
+        );
+        auction.highest_bid = Some(Bid {
+            bidder: "bidder".to_string(),
+            amount: Uint128::from(20u128),
+        });
+        assert_eq!(
+            auction.current_price(&block).unwrap(),
+            Uint128::from(25u128)
+        );
+        auction.highest_bid = Some(Bid {
+            bidder: "bidder".to_string(),
+            amount: Uint128::MAX,
+        });
+        assert!(matches!(
+            auction.current_price(&block).unwrap_err(),
+            ContractError::OverflowError(_)
+        ));
+
+        let auction = mock_auction(AuctionType::Dutch {
+            start_price: Uint128::from(1000u128),
+            end_price: Uint128::from(500u128),
+        });
+        block.time = Timestamp::from_seconds(100);
+        assert_eq!(
+            auction.current_price(&block).unwrap(),
+            Uint128::from(1000u128)
+        );
+        block.time = Timestamp::from_seconds(150);
+        assert_eq!(
+            auction.current_price(&block).unwrap(),
+            Uint128::from(750u128)
+        );
+        block.time = Timestamp::from_seconds(300);
+        assert_eq!(
+            auction.current_price(&block).unwrap(),
+            Uint128::from(500u128)
+        );
+        assert!(auction.is_ended(&block));
+    }
+
+    #[test]
+    fn test_on_transfer() {
+        let mut deps = mock_dependencies(&[]);
+        let module = AuctionModule {
+            extension_window: None,
+        };
+        let auction = mock_auction(AuctionType::English {
+            min_bid: Uint128::from(10u128),
+            min_increment: Uint128::from(5u128),
+        });
+        AUCTIONS
+            .save(deps.as_mut().storage, "1".to_string(), &auction)
+            .unwrap();
+
+        let resp = module
+            .on_transfer(
+                &deps.as_mut(),
+                mock_info("seller", &[]),
+                mock_env(),
+                "recipient".to_string(),
+                "1".to_string(),
+            )
+            .unwrap_err();
+        assert_eq!(resp, ContractError::TokenAuctioned {});
+
+        module
+            .on_transfer(
+                &deps.as_mut(),
+                mock_info("seller", &[]),
+                mock_env(),
+                "recipient".to_string(),
+                "2".to_string(),
+            )
+            .unwrap();
+    }
+}
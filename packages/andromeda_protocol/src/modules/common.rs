@@ -1,9 +1,116 @@
+use crate::error::ContractError;
 use crate::{
-    modules::{Module, ModuleDefinition, Rate},
+    modules::{hooks::PaymentAttribute, AddressWeight, Module, ModuleDefinition, Rate, Rounding},
     require,
 };
+use std::cmp::Ordering;
+
+use cosmwasm_std::{coin, to_binary, BankMsg, Coin, CosmosMsg, Fraction, Uint128, WasmMsg};
+use cw20::{Cw20Coin, Cw20ExecuteMsg};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// An amount of funds, either a native coin or a CW20 token
+pub enum Funds {
+    Native(Coin),
+    Cw20(Cw20Coin),
+}
+
+impl Funds {
+    pub fn amount(&self) -> Uint128 {
+        match self {
+            Funds::Native(coin) => coin.amount,
+            Funds::Cw20(coin) => coin.amount,
+        }
+    }
+    /// The denomination of native funds or the contract address of CW20 funds
+    pub fn denom(&self) -> String {
+        match self {
+            Funds::Native(coin) => coin.denom.clone(),
+            Funds::Cw20(coin) => coin.address.clone(),
+        }
+    }
+    /// Creates funds of the same denomination or CW20 token with the given amount
+    pub fn with_amount(&self, amount: Uint128) -> Funds {
+        match self {
+            Funds::Native(coin) => Funds::Native(Coin {
+                denom: coin.denom.clone(),
+                amount,
+            }),
+            Funds::Cw20(coin) => Funds::Cw20(Cw20Coin {
+                address: coin.address.clone(),
+                amount,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Funds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Funds::Native(coin) => write!(f, "{}", coin),
+            Funds::Cw20(coin) => write!(f, "{}{}", coin.amount, coin.address),
+        }
+    }
+}
 
-use cosmwasm_std::{coin, BankMsg, Coin, StdError, StdResult, Uint128};
+#[derive(Clone, Debug, PartialEq)]
+/// A payment generated for an agreed transfer
+pub enum Payment {
+    /// A payment of native funds
+    Bank(BankMsg),
+    /// A payment of CW20 tokens, executed on the CW20 token contract
+    Cw20 {
+        contract_addr: String,
+        msg: Cw20ExecuteMsg,
+    },
+}
+
+impl Payment {
+    /// The amount of this payment that is of the same denomination or CW20 token as the given funds
+    pub fn amount_of(&self, funds: &Funds) -> Uint128 {
+        match (self, funds) {
+            (Payment::Bank(BankMsg::Send { amount, .. }), Funds::Native(funds)) => amount
+                .iter()
+                .filter(|c| c.denom == funds.denom)
+                .map(|c| c.amount)
+                .sum(),
+            (
+                Payment::Cw20 {
+                    contract_addr,
+                    msg: Cw20ExecuteMsg::Transfer { amount, .. },
+                },
+                Funds::Cw20(funds),
+            ) if contract_addr == &funds.address => *amount,
+            _ => Uint128::zero(),
+        }
+    }
+    /// Whether the whole of this payment is of the same denomination or CW20 token as the given funds
+    pub fn is_denominated_in(&self, funds: &Funds) -> bool {
+        match (self, funds) {
+            (Payment::Bank(BankMsg::Send { amount, .. }), Funds::Native(funds)) => {
+                amount.iter().all(|c| c.denom == funds.denom)
+            }
+            (Payment::Cw20 { contract_addr, .. }, Funds::Cw20(funds)) => {
+                contract_addr == &funds.address
+            }
+            _ => false,
+        }
+    }
+    pub fn to_msg(&self) -> Result<CosmosMsg, ContractError> {
+        match self {
+            Payment::Bank(msg) => Ok(CosmosMsg::Bank(msg.clone())),
+            Payment::Cw20 { contract_addr, msg } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.clone(),
+                msg: to_binary(msg)?,
+                funds: vec![],
+            })),
+        }
+    }
+}
 
 /// Calculates a fee amount given a `Rate` and payment amount.
 ///
@@ -11,40 +118,153 @@ use cosmwasm_std::{coin, BankMsg, Coin, StdError, StdResult, Uint128};
 /// * `fee_rate` - The `Rate` of the fee to be paid
 /// * `payment` - The amount used to calculate the fee
 ///
-/// Returns the fee amount in a `Coin` struct.
-pub fn calculate_fee(fee_rate: Rate, payment: Coin) -> Coin {
-    if payment.amount > Uint128::MAX.checked_div(Uint128::from(100u128)).unwrap() {
-        panic!("Payment amount exceeds maximum value")
-    }
+/// Returns the fee amount in a `Coin` struct. Any remainder is rounded up in favour of the fee receiver.
+/// Errors if a percentage or decimal rate exceeds 100%.
+pub fn calculate_fee(fee_rate: Rate, payment: Coin) -> Result<Coin, ContractError> {
+    calculate_rounded_fee(fee_rate, payment, Rounding::Up)
+}
 
+/// Calculates a fee amount given a `Rate`, payment amount and `Rounding` policy.
+///
+/// ## Arguments
+/// * `fee_rate` - The `Rate` of the fee to be paid
+/// * `payment` - The amount used to calculate the fee
+/// * `rounding` - How any remainder of the fee is rounded
+///
+/// Returns the fee amount in a `Coin` struct.
+/// Errors if a percentage or decimal rate exceeds 100%.
+pub fn calculate_rounded_fee(
+    fee_rate: Rate,
+    payment: Coin,
+    rounding: Rounding,
+) -> Result<Coin, ContractError> {
     match fee_rate {
-        Rate::Flat(rate) => coin(Uint128::from(rate.amount).u128(), rate.denom),
+        Rate::Flat(rate) => Ok(coin(rate.amount.u128(), rate.denom)),
         Rate::Percent(rate) => {
-            // [COM-03] Make sure that fee_rate between 0 and 100.
-            require(
-                // No need for rate >=0 due to type limits (Question: Should add or remove?)
-                rate <= 100,
-                StdError::generic_err("Rate must be between 0 and 100%"),
-            )
-            .unwrap();
-            let mut fee_amount = payment.amount.multiply_ratio(rate, 100_u128).u128();
-
-            //Always round any remainder up and prioritise the fee receiver
-            let reversed_fee = (fee_amount * 100) / Uint128::from(rate).u128();
-            if payment.amount.u128() > reversed_fee {
-                // [COM-1] Added checked add to fee_amount rather than direct increment
-                let res = fee_amount.checked_add(1);
-                let _res = match res {
-                    None => panic!("Problem adding: Overflow in addition"),
-                    _ => fee_amount = res.unwrap(),
-                };
-            }
+            let fee_amount = calculate_fee_ratio(payment.amount, rate.into(), 100, rounding)?;
+            Ok(coin(fee_amount.u128(), payment.denom))
+        }
+        Rate::Decimal(rate) => {
+            let fee_amount = calculate_fee_ratio(
+                payment.amount,
+                rate.numerator(),
+                rate.denominator(),
+                rounding,
+            )?;
+            Ok(coin(fee_amount.u128(), payment.denom))
+        }
+    }
+}
+
+/// Calculates `amount * numerator / denominator` for a fee ratio of at most 1, rounding any remainder according to the given policy.
+fn calculate_fee_ratio(
+    amount: Uint128,
+    numerator: u128,
+    denominator: u128,
+    rounding: Rounding,
+) -> Result<Uint128, ContractError> {
+    // [COM-03] Make sure that fee_rate between 0 and 100.
+    require(numerator <= denominator, ContractError::InvalidRate {})?;
+    // The fee can never exceed the amount so this cannot overflow
+    let fee_amount = amount.multiply_ratio(numerator, denominator);
+    // (amount * numerator) % denominator, computed without overflowing
+    let remainder = (amount.u128() % denominator) * numerator % denominator;
+    let round_up = match rounding {
+        Rounding::Up => remainder > 0,
+        Rounding::Down => false,
+        Rounding::HalfEven => match (remainder * 2).cmp(&denominator) {
+            Ordering::Greater => true,
+            Ordering::Equal => fee_amount.u128() % 2 == 1,
+            Ordering::Less => false,
+        },
+    };
+    if round_up {
+        // [COM-1] Added checked add to fee_amount rather than direct increment
+        return Ok(fee_amount.checked_add(Uint128::from(1u128))?);
+    }
+
+    Ok(fee_amount)
+}
 
-            coin(fee_amount, payment.denom)
+/// Calculates a fee amount given a `Rate`, payment funds and `Rounding` policy.
+/// Flat rate fees on CW20 payments must be denominated in the CW20 token, using its contract address as the denomination.
+///
+/// Returns the fee amount as `Funds` of the same kind as the payment.
+pub fn calculate_payment_fee(
+    fee_rate: Rate,
+    payment: Funds,
+    rounding: Rounding,
+) -> Result<Funds, ContractError> {
+    match payment {
+        Funds::Native(payment) => Ok(Funds::Native(calculate_rounded_fee(
+            fee_rate, payment, rounding,
+        )?)),
+        Funds::Cw20(payment) => {
+            if let Rate::Flat(rate) = &fee_rate {
+                require(
+                    rate.denom == payment.address,
+                    ContractError::InvalidCw20FlatRate {},
+                )?;
+            }
+            let fee = calculate_rounded_fee(
+                fee_rate,
+                coin(payment.amount.u128(), payment.address.clone()),
+                rounding,
+            )?;
+            Ok(Funds::Cw20(Cw20Coin {
+                address: payment.address,
+                amount: fee.amount,
+            }))
         }
     }
 }
 
+/// Ensures that every receiver in a list of weighted receivers has a non-zero weight.
+pub fn validate_receivers(receivers: &[AddressWeight]) -> Result<bool, ContractError> {
+    require(
+        receivers.iter().all(|r| !r.weight.is_zero()),
+        ContractError::InvalidReceiverWeight {},
+    )
+}
+
+/// Splits a fee amongst a list of weighted receivers in proportion to their weights.
+///
+/// ## Arguments
+/// * `fee` - The total fee to be split
+/// * `receivers` - The weighted receivers of the fee
+///
+/// Each share is rounded down, any remainder is given to the first receiver so that the shares always sum to the fee.
+/// Returns the payment for each receiver, in the order the receivers were provided.
+pub fn calculate_shares(
+    fee: Funds,
+    receivers: &[AddressWeight],
+) -> Result<Vec<PaymentAttribute>, ContractError> {
+    let fee_amount = fee.amount();
+    let total_weight = receivers
+        .iter()
+        .try_fold(Uint128::zero(), |total, r| total.checked_add(r.weight))?;
+    require(
+        !total_weight.is_zero(),
+        ContractError::InvalidReceiverWeight {},
+    )?;
+
+    let mut shares: Vec<PaymentAttribute> = receivers
+        .iter()
+        .map(|r| PaymentAttribute {
+            receiver: r.addr.clone(),
+            amount: fee.with_amount(fee_amount.multiply_ratio(r.weight, total_weight)),
+        })
+        .collect();
+    let distributed: Uint128 = shares.iter().map(|s| s.amount.amount()).sum();
+    if let Some(first) = shares.first_mut() {
+        first.amount = first
+            .amount
+            .with_amount(first.amount.amount() + (fee_amount - distributed));
+    }
+
+    Ok(shares)
+}
+
 // [COM-02] Changed parameter all_modules type from Vec to a reference of a slice.
 /// Determines if a `ModuleDefinition` is unique within the context of a vector of `ModuleDefinition`
 ///
@@ -73,65 +293,95 @@ pub fn is_unique<M: Module>(module: &M, all_modules: &[ModuleDefinition]) -> boo
 /// ## Arguments
 /// * `coins` - The vector of `Coin` structs from which to deduct the given funds
 /// * `funds` - The amount to deduct
-pub fn deduct_funds(coins: &mut Vec<Coin>, funds: Coin) -> StdResult<bool> {
+pub fn deduct_funds(coins: &mut Vec<Coin>, funds: Coin) -> Result<bool, ContractError> {
     let coin_amount = coins.iter_mut().find(|c| c.denom.eq(&funds.denom));
 
     match coin_amount {
         Some(mut c) => {
             require(
                 c.amount >= funds.amount,
-                StdError::generic_err("Not enough funds to deduct payment"),
+                ContractError::NotEnoughFundsToDeduct {},
             )?;
             c.amount = c.amount - funds.amount;
             Ok(true)
         }
-        None => Err(StdError::generic_err("Not enough funds to deduct payment")),
+        None => Err(ContractError::NotEnoughFundsToDeduct {}),
     }
 }
 
-/// Adds a new payment message to a vector of `BankMsg` structs. Alters the provided vector, does not return a new vector.
+/// Adds a new payment to a vector of `Payment` structs. Alters the provided vector, does not return a new vector.
+/// Native funds are paid with a `BankMsg`, CW20 funds are paid with a `Cw20ExecuteMsg::Transfer`.
 ///
 /// ## Arguments
-/// * `payments` - The vector of `BankMsg` structs for which to attach the new `BankMsg`
+/// * `payments` - The vector of `Payment` structs for which to attach the new `Payment`
 /// * `to` - The recipient of the payment
 /// * `amount` - The amount to be sent
-pub fn add_payment(payments: &mut Vec<BankMsg>, to: String, amount: Coin) {
-    let payment = BankMsg::Send {
-        to_address: to,
-        amount: vec![amount],
+pub fn add_payment(payments: &mut Vec<Payment>, to: String, amount: Funds) {
+    let payment = match amount {
+        Funds::Native(amount) => Payment::Bank(BankMsg::Send {
+            to_address: to,
+            amount: vec![amount],
+        }),
+        Funds::Cw20(amount) => Payment::Cw20 {
+            contract_addr: amount.address,
+            msg: Cw20ExecuteMsg::Transfer {
+                recipient: to,
+                amount: amount.amount,
+            },
+        },
     };
 
     payments.push(payment);
 }
 
-/// Deducts a given amount from a vector of `BankMsg` structs. Alters the provided vector, does not return a new vector.
+/// Deducts a given amount from a vector of `Payment` structs. Alters the provided vector, does not return a new vector.
 ///
 /// ## Arguments
-/// * `payments` - The vector of `BankMsg` structs for which to deduct the amount
+/// * `payments` - The vector of `Payment` structs for which to deduct the amount
 /// * `to` - The recipient of the payment
-/// * `amount` - The amount to be deducted
+/// * `amount` - The amount to be deducted, only payments of the same denomination or CW20 token are deducted from
 ///
 /// Errors if there is no payment from which to deduct the funds
-pub fn deduct_payment(payments: &mut Vec<BankMsg>, to: String, amount: Coin) -> StdResult<bool> {
-    let payment = payments.iter_mut().find(|m| match m {
-        BankMsg::Send { to_address, .. } => to_address.eq(&to),
+pub fn deduct_payment(
+    payments: &mut Vec<Payment>,
+    to: String,
+    amount: Funds,
+) -> Result<bool, ContractError> {
+    let payment = payments.iter_mut().find(|p| match (&*p, &amount) {
+        (Payment::Bank(BankMsg::Send { to_address, .. }), Funds::Native(_)) => to_address.eq(&to),
+        (
+            Payment::Cw20 {
+                contract_addr,
+                msg: Cw20ExecuteMsg::Transfer { recipient, .. },
+            },
+            Funds::Cw20(funds),
+        ) => recipient.eq(&to) && contract_addr.eq(&funds.address),
         _ => false,
     });
 
     match payment {
         Some(p) => {
-            match p {
-                BankMsg::Send { amount: am, .. } => {
-                    deduct_funds(am, amount)?;
+            match (p, amount) {
+                (Payment::Bank(BankMsg::Send { amount: am, .. }), Funds::Native(funds)) => {
+                    deduct_funds(am, funds)?;
+                }
+                (
+                    Payment::Cw20 {
+                        msg: Cw20ExecuteMsg::Transfer { amount: am, .. },
+                        ..
+                    },
+                    Funds::Cw20(funds),
+                ) => {
+                    *am = am
+                        .checked_sub(funds.amount)
+                        .map_err(|_| ContractError::NotEnoughFundsToDeduct {})?;
                 }
                 _ => {}
             }
             Ok(true)
         }
         // [COM-05] Misleading error message since it should check whether there is pending deductions and not if it has enough funds.
-        None => Err(StdError::generic_err(
-            "No pending payments for the given address!",
-        )),
+        None => Err(ContractError::NoPendingPayments {}),
     }
 }
 
@@ -139,9 +389,12 @@ pub fn deduct_payment(payments: &mut Vec<BankMsg>, to: String, amount: Coin) ->
 mod tests {
     use super::*;
     use crate::modules::address_list::AddressListModule;
+    use crate::modules::hooks::MessageHooks;
+    use crate::modules::royalties::Royalty;
     use crate::modules::FlatRate;
     use crate::modules::Rate;
-    use cosmwasm_std::{coin, Uint128};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coin, Decimal, Uint128};
 
     #[test]
     fn test_is_unique() {
@@ -165,6 +418,7 @@ mod tests {
             rate: Rate::Percent(2),
             receivers: vec![],
             description: None,
+            rounding: None,
         };
 
         let valid = vec![module.as_definition().clone(), other_module.clone()];
@@ -198,65 +452,161 @@ mod tests {
 
         let e = deduct_funds(&mut funds, coin(10, "uluna")).unwrap_err();
 
-        assert_eq!(
-            StdError::generic_err("Not enough funds to deduct payment"),
-            e
-        );
+        assert_eq!(ContractError::NotEnoughFundsToDeduct {}, e);
     }
 
     #[test]
     fn test_add_payment() {
-        let mut payments: Vec<BankMsg> = vec![];
+        let mut payments: Vec<Payment> = vec![];
 
         let _from = String::from("from");
         let to = String::from("to");
         let amount = coin(1, "uluna");
 
-        let expected_payment = BankMsg::Send {
+        let expected_payment = Payment::Bank(BankMsg::Send {
             to_address: to.clone(),
             amount: vec![amount.clone()],
-        };
+        });
 
-        add_payment(&mut payments, to, amount);
+        add_payment(&mut payments, to, Funds::Native(amount));
 
         assert_eq!(1, payments.len());
         assert_eq!(expected_payment, payments[0]);
     }
 
+    #[test]
+    fn test_add_payment_cw20() {
+        let mut payments: Vec<Payment> = vec![];
+        let amount = Cw20Coin {
+            address: String::from("cw20"),
+            amount: Uint128::from(10u128),
+        };
+
+        add_payment(&mut payments, String::from("to"), Funds::Cw20(amount));
+
+        let expected_payment = Payment::Cw20 {
+            contract_addr: String::from("cw20"),
+            msg: Cw20ExecuteMsg::Transfer {
+                recipient: String::from("to"),
+                amount: Uint128::from(10u128),
+            },
+        };
+        assert_eq!(payments, vec![expected_payment.clone()]);
+        assert_eq!(
+            payments[0].to_msg().unwrap(),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("cw20"),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("to"),
+                    amount: Uint128::from(10u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        assert_eq!(
+            payments[0].amount_of(&Funds::Cw20(Cw20Coin {
+                address: String::from("cw20"),
+                amount: Uint128::zero(),
+            })),
+            Uint128::from(10u128)
+        );
+        assert_eq!(
+            payments[0].amount_of(&Funds::Native(coin(0, "cw20"))),
+            Uint128::zero()
+        );
+    }
+
     #[test]
     fn deduct_payment_test() {
         let to = String::from("to");
 
-        let mut payments: Vec<BankMsg> = vec![BankMsg::Send {
+        let mut payments: Vec<Payment> = vec![Payment::Bank(BankMsg::Send {
             to_address: to.clone(),
             amount: vec![Coin {
                 amount: Uint128::from(100 as u64),
                 denom: String::from("uluna"),
             }],
-        }];
+        })];
 
-        let expected_payment = BankMsg::Send {
+        let expected_payment = Payment::Bank(BankMsg::Send {
             to_address: to.clone(),
             amount: vec![Coin {
                 amount: Uint128::from(90 as u64),
                 denom: String::from("uluna"),
             }],
-        };
+        });
 
-        deduct_payment(&mut payments, to, coin(10, "uluna")).unwrap();
+        deduct_payment(&mut payments, to, Funds::Native(coin(10, "uluna"))).unwrap();
 
         assert_eq!(expected_payment, payments[0]);
     }
 
+    #[test]
+    fn test_deduct_payment_cw20() {
+        let to = String::from("to");
+        let cw20 = |amount: u128| {
+            Funds::Cw20(Cw20Coin {
+                address: String::from("cw20"),
+                amount: Uint128::from(amount),
+            })
+        };
+        let mut payments = vec![];
+        add_payment(&mut payments, to.clone(), Funds::Native(coin(100, "uluna")));
+        add_payment(&mut payments, to.clone(), cw20(100));
+
+        deduct_payment(&mut payments, to.clone(), cw20(10)).unwrap();
+
+        let mut expected = vec![];
+        add_payment(&mut expected, to.clone(), Funds::Native(coin(100, "uluna")));
+        add_payment(&mut expected, to.clone(), cw20(90));
+        assert_eq!(payments, expected);
+
+        assert_eq!(
+            deduct_payment(&mut payments, to.clone(), cw20(91)).unwrap_err(),
+            ContractError::NotEnoughFundsToDeduct {}
+        );
+        let other_cw20 = Funds::Cw20(Cw20Coin {
+            address: String::from("othercw20"),
+            amount: Uint128::from(1u128),
+        });
+        assert!(deduct_payment(&mut payments, to, other_cw20).is_err());
+    }
+
+    #[test]
+    fn test_calculate_payment_fee() {
+        let cw20 = Funds::Cw20(Cw20Coin {
+            address: String::from("cw20"),
+            amount: Uint128::from(101u128),
+        });
+
+        let fee = calculate_payment_fee(Rate::Percent(4), cw20.clone(), Rounding::Up).unwrap();
+        assert_eq!(fee, cw20.with_amount(Uint128::from(5u128)));
+
+        let flat = Rate::Flat(FlatRate {
+            amount: Uint128::from(5u128),
+            denom: String::from("cw20"),
+        });
+        let fee = calculate_payment_fee(flat, cw20.clone(), Rounding::Up).unwrap();
+        assert_eq!(fee, cw20.with_amount(Uint128::from(5u128)));
+
+        let flat = Rate::Flat(FlatRate {
+            amount: Uint128::from(5u128),
+            denom: String::from("uluna"),
+        });
+        assert_eq!(
+            calculate_payment_fee(flat, cw20, Rounding::Up).unwrap_err(),
+            ContractError::InvalidCw20FlatRate {}
+        );
+    }
+
     #[test]
     fn test_calculate_fee() {
         let payment = coin(101, "uluna");
         let expected = coin(5, "uluna");
         let fee = Rate::Percent(4);
 
-        let received = calculate_fee(fee, payment);
-
-        assert_eq!(expected, received);
+        let received = calculate_fee(fee, payment).unwrap();
 
         assert_eq!(expected, received);
 
@@ -267,17 +617,202 @@ mod tests {
             denom: "uluna".to_string(),
         });
 
-        let received = calculate_fee(fee, payment);
+        let received = calculate_fee(fee, payment).unwrap();
+
+        assert_eq!(expected, received);
+
+        let payment = coin(1000, "uluna");
+        let expected = coin(25, "uluna");
+        let fee = Rate::Decimal(Decimal::permille(25));
+
+        let received = calculate_fee(fee, payment).unwrap();
+
+        assert_eq!(expected, received);
+
+        // 0.75% of 101 is 0.7575, rounded up
+        let payment = coin(101, "uluna");
+        let expected = coin(1, "uluna");
+        let fee = Rate::Decimal(Decimal::from_ratio(75u128, 10_000u128));
+
+        let received = calculate_fee(fee, payment).unwrap();
 
         assert_eq!(expected, received);
+
+        let payment = coin(100, "uluna");
+        let fee = Rate::Decimal(Decimal::percent(101));
+
+        assert_eq!(
+            calculate_fee(fee, payment).unwrap_err(),
+            ContractError::InvalidRate {}
+        );
+    }
+
+    #[test]
+    fn test_calculate_rounded_fee() {
+        // 2.5% of 10 is 0.25, 5% of 10 is 0.5, 15% of 10 is 1.5, 7% of 10 is 0.7
+        let cases = vec![
+            (25u64, Rounding::Up, 1u128),
+            (25, Rounding::Down, 0),
+            (25, Rounding::HalfEven, 0),
+            (50, Rounding::Up, 1),
+            (50, Rounding::Down, 0),
+            (50, Rounding::HalfEven, 0),
+            (150, Rounding::Up, 2),
+            (150, Rounding::Down, 1),
+            (150, Rounding::HalfEven, 2),
+            (70, Rounding::Up, 1),
+            (70, Rounding::Down, 0),
+            (70, Rounding::HalfEven, 1),
+        ];
+        for (permille, rounding, expected) in cases {
+            let fee = calculate_rounded_fee(
+                Rate::Decimal(Decimal::permille(permille)),
+                coin(10, "uluna"),
+                rounding.clone(),
+            )
+            .unwrap();
+            assert_eq!(
+                fee,
+                coin(expected, "uluna"),
+                "{} permille rounded {:?}",
+                permille,
+                rounding
+            );
+        }
+    }
+
+    /// Generates a deterministic sequence of pseudo-random values for property testing
+    fn pseudo_random(seed: &mut u128) -> u128 {
+        *seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        *seed >> 64
+    }
+
+    #[test]
+    fn test_fee_never_exceeds_sale() {
+        let roundings = vec![Rounding::Up, Rounding::Down, Rounding::HalfEven];
+        let mut seed = 42u128;
+        let mut amounts: Vec<u128> = (0..=1000).collect();
+        for _ in 0..1000 {
+            amounts.push(pseudo_random(&mut seed) * pseudo_random(&mut seed));
+        }
+        amounts.push(Uint128::MAX.u128());
+
+        let mut deps = mock_dependencies(&[]);
+        for amount in amounts {
+            let sale = coin(amount, "uluna");
+            let rates = vec![
+                Rate::Percent(pseudo_random(&mut seed) as u64 % 101),
+                Rate::Decimal(Decimal::from_ratio(
+                    pseudo_random(&mut seed) % 1_000_001,
+                    1_000_000u128,
+                )),
+                // Flat rates both below and above the smaller sale amounts
+                Rate::Flat(FlatRate {
+                    amount: Uint128::from(pseudo_random(&mut seed) % 1000),
+                    denom: "uluna".to_string(),
+                }),
+            ];
+            for rate in rates {
+                for rounding in roundings.clone() {
+                    let fee = calculate_rounded_fee(rate.clone(), sale.clone(), rounding.clone())
+                        .unwrap();
+                    if !matches!(rate, Rate::Flat(_)) {
+                        assert!(fee.amount <= sale.amount);
+                    }
+
+                    // The royalty is deducted from the owner's payment, so the payments always total the sale
+                    let royalty = Royalty {
+                        rate: rate.clone(),
+                        receivers: vec![
+                            AddressWeight::new("one", pseudo_random(&mut seed) % 100 + 1),
+                            AddressWeight::new("two", pseudo_random(&mut seed) % 100 + 1),
+                            AddressWeight::new("three", pseudo_random(&mut seed) % 100 + 1),
+                        ],
+                        description: None,
+                        rounding: Some(rounding),
+                    };
+                    let mut payments = vec![];
+                    add_payment(
+                        &mut payments,
+                        "owner".to_string(),
+                        Funds::Native(sale.clone()),
+                    );
+                    let res = royalty.on_agreed_transfer(
+                        &deps.as_mut(),
+                        mock_info("purchaser", &[]),
+                        mock_env(),
+                        &mut payments,
+                        "owner".to_string(),
+                        "purchaser".to_string(),
+                        Funds::Native(sale.clone()),
+                    );
+                    if fee.amount > sale.amount {
+                        assert_eq!(res.unwrap_err(), ContractError::NotEnoughFundsToDeduct {});
+                        continue;
+                    }
+                    res.unwrap();
+                    let funds = Funds::Native(sale.clone());
+                    let total: Uint128 = payments.iter().map(|p| p.amount_of(&funds)).sum();
+                    assert_eq!(total, sale.amount);
+                    assert_eq!(payments[0].amount_of(&funds), sale.amount - fee.amount);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_shares() {
+        let receivers = vec![
+            AddressWeight::new("one", 1),
+            AddressWeight::new("two", 1),
+            AddressWeight::new("three", 1),
+        ];
+        let shares = calculate_shares(Funds::Native(coin(10, "uluna")), &receivers).unwrap();
+        let amounts: Vec<Funds> = shares.into_iter().map(|s| s.amount).collect();
+        assert_eq!(
+            amounts,
+            vec![
+                Funds::Native(coin(4, "uluna")),
+                Funds::Native(coin(3, "uluna")),
+                Funds::Native(coin(3, "uluna"))
+            ]
+        );
+
+        let receivers = vec![AddressWeight::new("one", 1), AddressWeight::new("two", 3)];
+        let shares = calculate_shares(Funds::Native(coin(100, "uluna")), &receivers).unwrap();
+        assert_eq!(shares[0].receiver, "one");
+        assert_eq!(shares[0].amount, Funds::Native(coin(25, "uluna")));
+        assert_eq!(shares[1].receiver, "two");
+        assert_eq!(shares[1].amount, Funds::Native(coin(75, "uluna")));
+
+        let receivers = vec![AddressWeight::new("one", 0)];
+        assert_eq!(
+            calculate_shares(Funds::Native(coin(100, "uluna")), &receivers).unwrap_err(),
+            ContractError::InvalidReceiverWeight {}
+        );
+        assert_eq!(
+            validate_receivers(&receivers).unwrap_err(),
+            ContractError::InvalidReceiverWeight {}
+        );
     }
 
     #[test]
-    #[should_panic]
     fn test_calculate_fee_max() {
         let payment = coin(Uint128::MAX.u128(), "uluna");
         let fee = Rate::Percent(4);
 
-        calculate_fee(fee, payment);
+        let received = calculate_fee(fee, payment).unwrap();
+
+        assert_eq!(
+            received.amount,
+            Uint128::MAX.multiply_ratio(4u128, 100u128) + Uint128::from(1u128)
+        );
+
+        let payment = coin(Uint128::MAX.u128(), "uluna");
+        let fee = Rate::Decimal(Decimal::one());
+
+        assert_eq!(calculate_fee(fee, payment).unwrap().amount, Uint128::MAX);
     }
 }
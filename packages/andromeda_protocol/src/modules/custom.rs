@@ -0,0 +1,735 @@
+use crate::error::ContractError;
+use cosmwasm_std::{
+    to_binary, DepsMut, Env, Event, MessageInfo, Storage, SubMsg, Uint128, WasmMsg,
+};
+use cw721::Expiration;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    modules::{
+        common::{add_payment, Funds, Payment},
+        hooks::{HookResponse, MessageHooks, PaymentAttribute, ATTR_PAYMENT},
+        Module, ModuleDefinition,
+    },
+    require,
+};
+
+pub const CUSTOM_EVENT_ID: &str = "custom";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Eq)]
+#[serde(rename_all = "snake_case")]
+/// The hooks that may be forwarded to a custom module contract
+pub enum HookType {
+    Execute,
+    Mint,
+    Transfer,
+    Send,
+    Approve,
+    Revoke,
+    ApproveAll,
+    RevokeAll,
+    TransferAgreement,
+    Burn,
+    Archive,
+    AgreedTransfer,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Eq)]
+#[serde(rename_all = "snake_case")]
+/// How a hook is forwarded to a custom module contract
+pub enum HookForward {
+    /// The contract is queried with a `CustomHookMsg`. The returned `CustomHookResponse` may reject the action or append payments to an agreed transfer.
+    Query,
+    /// The contract is sent a `CustomHookMsg` as a sub-message. Any error returned by the contract reverts the action.
+    Message,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Eq)]
+/// A hook forwarded to a custom module contract
+pub struct CustomHook {
+    /// The hook to forward
+    pub hook: HookType,
+    /// How the hook is forwarded
+    pub forward: HookForward,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// The data provided to a custom module contract for each hook. `sender` is the sender of the message that triggered the hook.
+pub enum HookMsg {
+    OnExecute {
+        sender: String,
+    },
+    OnMint {
+        sender: String,
+        token_id: String,
+    },
+    OnTransfer {
+        sender: String,
+        recipient: String,
+        token_id: String,
+    },
+    OnSend {
+        sender: String,
+        contract: String,
+        token_id: String,
+    },
+    OnApprove {
+        sender: String,
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    OnRevoke {
+        sender: String,
+        spender: String,
+        token_id: String,
+    },
+    OnApproveAll {
+        sender: String,
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    OnRevokeAll {
+        sender: String,
+        operator: String,
+    },
+    OnTransferAgreement {
+        sender: String,
+        token_id: String,
+        purchaser: String,
+        amount: Uint128,
+        denom: String,
+    },
+    OnBurn {
+        sender: String,
+        token_id: String,
+    },
+    OnArchive {
+        sender: String,
+        token_id: String,
+    },
+    OnAgreedTransfer {
+        sender: String,
+        owner: String,
+        purchaser: String,
+        amount: Funds,
+    },
+}
+
+impl HookMsg {
+    pub fn hook_type(&self) -> HookType {
+        match self {
+            HookMsg::OnExecute { .. } => HookType::Execute,
+            HookMsg::OnMint { .. } => HookType::Mint,
+            HookMsg::OnTransfer { .. } => HookType::Transfer,
+            HookMsg::OnSend { .. } => HookType::Send,
+            HookMsg::OnApprove { .. } => HookType::Approve,
+            HookMsg::OnRevoke { .. } => HookType::Revoke,
+            HookMsg::OnApproveAll { .. } => HookType::ApproveAll,
+            HookMsg::OnRevokeAll { .. } => HookType::RevokeAll,
+            HookMsg::OnTransferAgreement { .. } => HookType::TransferAgreement,
+            HookMsg::OnBurn { .. } => HookType::Burn,
+            HookMsg::OnArchive { .. } => HookType::Archive,
+            HookMsg::OnAgreedTransfer { .. } => HookType::AgreedTransfer,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// The message a custom module contract must accept, both as a `QueryMsg` and as an `ExecuteMsg`, for any hooks forwarded to it
+pub enum CustomHookMsg {
+    Hook(HookMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// A payment appended to an agreed transfer by a custom module contract
+pub struct CustomPayment {
+    /// The address to pay
+    pub receiver: String,
+    /// The amount to pay, in the currency of the agreed transfer
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+/// The response a custom module contract must return when queried with a `CustomHookMsg`
+pub struct CustomHookResponse {
+    /// If provided the action is rejected with the given reason
+    pub rejection: Option<String>,
+    /// Payments appended to an agreed transfer, paid for by the purchaser. Only used in response to `HookMsg::OnAgreedTransfer`.
+    pub payments: Vec<CustomPayment>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// A struct used to define a Custom module. Selected hooks are forwarded to an external contract implementing `CustomHookMsg`.
+pub struct CustomModule {
+    /// The address of the module contract
+    pub address: String,
+    /// The hooks forwarded to the module contract
+    pub hooks: Vec<CustomHook>,
+    /// The priority of the module's hooks, defaults to `PRIORITY_CUSTOM`
+    pub priority: Option<u64>,
+}
+
+impl CustomModule {
+    /// Forwards a hook to the module contract if it is one of the module's selected hooks.
+    /// Returns any sub-message generated for the hook and any payments returned by a queried contract.
+    /// Errors if a queried contract rejects the action.
+    fn forward(
+        &self,
+        deps: &DepsMut,
+        msg: HookMsg,
+    ) -> Result<(HookResponse, Vec<CustomPayment>), ContractError> {
+        let res = HookResponse::default();
+        let hook_type = msg.hook_type();
+        let forward = match self.hooks.iter().find(|h| h.hook == hook_type) {
+            Some(hook) => hook.forward.clone(),
+            None => return Ok((res, vec![])),
+        };
+
+        match forward {
+            HookForward::Query => {
+                let resp: CustomHookResponse = deps
+                    .querier
+                    .query_wasm_smart(self.address.clone(), &CustomHookMsg::Hook(msg))?;
+                if let Some(rejection) = resp.rejection {
+                    return Err(ContractError::CustomModuleRejection { reason: rejection });
+                }
+
+                Ok((res, resp.payments))
+            }
+            HookForward::Message => {
+                let msg = WasmMsg::Execute {
+                    contract_addr: self.address.clone(),
+                    msg: to_binary(&CustomHookMsg::Hook(msg))?,
+                    funds: vec![],
+                };
+
+                Ok((res.add_message(SubMsg::new(msg)), vec![]))
+            }
+        }
+    }
+}
+
+impl MessageHooks for CustomModule {
+    /// Validates the module's contract address when the module is instantiated or added to a contract
+    fn on_instantiate(
+        &self,
+        deps: &DepsMut,
+        _info: MessageInfo,
+        _env: Env,
+    ) -> Result<HookResponse, ContractError> {
+        deps.api
+            .addr_validate(&self.address)
+            .map_err(|_| ContractError::InvalidCustomModuleAddress {})?;
+
+        Ok(HookResponse::default())
+    }
+    fn on_execute(
+        &self,
+        deps: &DepsMut,
+        info: MessageInfo,
+        _env: Env,
+    ) -> Result<HookResponse, ContractError> {
+        let msg = HookMsg::OnExecute {
+            sender: info.sender.to_string(),
+        };
+        let (res, _) = self.forward(deps, msg)?;
+
+        Ok(res)
+    }
+    fn on_mint(
+        &self,
+        deps: &DepsMut,
+        info: MessageInfo,
+        _env: Env,
+        token_id: String,
+    ) -> Result<HookResponse, ContractError> {
+        let msg = HookMsg::OnMint {
+            sender: info.sender.to_string(),
+            token_id,
+        };
+        let (res, _) = self.forward(deps, msg)?;
+
+        Ok(res)
+    }
+    fn on_transfer(
+        &self,
+        deps: &DepsMut,
+        info: MessageInfo,
+        _env: Env,
+        recipient: String,
+        token_id: String,
+    ) -> Result<HookResponse, ContractError> {
+        let msg = HookMsg::OnTransfer {
+            sender: info.sender.to_string(),
+            recipient,
+            token_id,
+        };
+        let (res, _) = self.forward(deps, msg)?;
+
+        Ok(res)
+    }
+    fn on_send(
+        &self,
+        deps: &DepsMut,
+        info: MessageInfo,
+        _env: Env,
+        contract: String,
+        token_id: String,
+    ) -> Result<HookResponse, ContractError> {
+        let msg = HookMsg::OnSend {
+            sender: info.sender.to_string(),
+            contract,
+            token_id,
+        };
+        let (res, _) = self.forward(deps, msg)?;
+
+        Ok(res)
+    }
+    fn on_approve(
+        &self,
+        deps: &DepsMut,
+        info: MessageInfo,
+        _env: Env,
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    ) -> Result<HookResponse, ContractError> {
+        let msg = HookMsg::OnApprove {
+            sender: info.sender.to_string(),
+            spender,
+            token_id,
+            expires,
+        };
+        let (res, _) = self.forward(deps, msg)?;
+
+        Ok(res)
+    }
+    fn on_revoke(
+        &self,
+        deps: &DepsMut,
+        info: MessageInfo,
+        _env: Env,
+        spender: String,
+        token_id: String,
+    ) -> Result<HookResponse, ContractError> {
+        let msg = HookMsg::OnRevoke {
+            sender: info.sender.to_string(),
+            spender,
+            token_id,
+        };
+        let (res, _) = self.forward(deps, msg)?;
+
+        Ok(res)
+    }
+    fn on_approve_all(
+        &self,
+        deps: &DepsMut,
+        info: MessageInfo,
+        _env: Env,
+        operator: String,
+        expires: Option<Expiration>,
+    ) -> Result<HookResponse, ContractError> {
+        let msg = HookMsg::OnApproveAll {
+            sender: info.sender.to_string(),
+            operator,
+            expires,
+        };
+        let (res, _) = self.forward(deps, msg)?;
+
+        Ok(res)
+    }
+    fn on_revoke_all(
+        &self,
+        deps: &DepsMut,
+        info: MessageInfo,
+        _env: Env,
+        operator: String,
+    ) -> Result<HookResponse, ContractError> {
+        let msg = HookMsg::OnRevokeAll {
+            sender: info.sender.to_string(),
+            operator,
+        };
+        let (res, _) = self.forward(deps, msg)?;
+
+        Ok(res)
+    }
+    fn on_transfer_agreement(
+        &self,
+        deps: &DepsMut,
+        info: MessageInfo,
+        _env: Env,
+        token_id: String,
+        purchaser: String,
+        amount: u128,
+        denom: String,
+    ) -> Result<HookResponse, ContractError> {
+        let msg = HookMsg::OnTransferAgreement {
+            sender: info.sender.to_string(),
+            token_id,
+            purchaser,
+            amount: Uint128::from(amount),
+            denom,
+        };
+        let (res, _) = self.forward(deps, msg)?;
+
+        Ok(res)
+    }
+    fn on_burn(
+        &self,
+        deps: &DepsMut,
+        info: MessageInfo,
+        _env: Env,
+        token_id: String,
+    ) -> Result<HookResponse, ContractError> {
+        let msg = HookMsg::OnBurn {
+            sender: info.sender.to_string(),
+            token_id,
+        };
+        let (res, _) = self.forward(deps, msg)?;
+
+        Ok(res)
+    }
+    fn on_archive(
+        &self,
+        deps: &DepsMut,
+        info: MessageInfo,
+        _env: Env,
+        token_id: String,
+    ) -> Result<HookResponse, ContractError> {
+        let msg = HookMsg::OnArchive {
+            sender: info.sender.to_string(),
+            token_id,
+        };
+        let (res, _) = self.forward(deps, msg)?;
+
+        Ok(res)
+    }
+    /// Forwards the agreed transfer to the module contract, adding any payments returned by a queried contract.
+    /// **Any payments appended by the module contract are paid for by the purchaser, in the currency of the agreed transfer.**
+    fn on_agreed_transfer(
+        &self,
+        deps: &DepsMut,
+        info: MessageInfo,
+        _env: Env,
+        payments: &mut Vec<Payment>,
+        owner: String,
+        purchaser: String,
+        amount: Funds,
+    ) -> Result<HookResponse, ContractError> {
+        let msg = HookMsg::OnAgreedTransfer {
+            sender: info.sender.to_string(),
+            owner,
+            purchaser,
+            amount: amount.clone(),
+        };
+        let (mut res, custom_payments) = self.forward(deps, msg)?;
+
+        let mut event = Event::new(CUSTOM_EVENT_ID).add_attribute("contract", &self.address);
+        for payment in custom_payments {
+            if payment.amount.is_zero() {
+                continue;
+            }
+            let share = PaymentAttribute {
+                receiver: payment.receiver,
+                amount: amount.with_amount(payment.amount),
+            };
+            add_payment(payments, share.receiver.clone(), share.amount.clone());
+            event = event.add_attribute(ATTR_PAYMENT, share.to_string());
+        }
+        if event.attributes.len() > 1 {
+            res = res.add_event(event);
+        }
+
+        Ok(res)
+    }
+}
+
+impl Module for CustomModule {
+    /// Validates the custom module:
+    /// * Must include a contract address
+    /// * Must forward at least one hook, each hook at most once
+    /// * The execute hook may only be forwarded as a query
+    /// * The module's priority must differ from the priority of every other module
+    fn validate(&self, modules: Vec<ModuleDefinition>) -> Result<bool, ContractError> {
+        require(
+            !self.address.is_empty(),
+            ContractError::MissingCustomModuleAddress {},
+        )?;
+        require(!self.hooks.is_empty(), ContractError::NoCustomHooks {})?;
+        for (idx, hook) in self.hooks.iter().enumerate() {
+            require(
+                !self.hooks[idx + 1..].iter().any(|h| h.hook == hook.hook),
+                ContractError::DuplicateCustomHook {},
+            )?;
+            require(
+                hook.hook != HookType::Execute || hook.forward == HookForward::Query,
+                ContractError::InvalidExecuteHookForward {},
+            )?;
+        }
+        let priority = self.as_definition().priority();
+        require(
+            modules.iter().filter(|m| m.priority() == priority).count() <= 1,
+            ContractError::CustomModulePriorityConflict { priority },
+        )?;
+
+        Ok(true)
+    }
+    fn as_definition(&self) -> ModuleDefinition {
+        ModuleDefinition::Custom {
+            address: self.address.clone(),
+            hooks: self.hooks.clone(),
+            priority: self.priority,
+        }
+    }
+    fn get_contract_address(&self, _storage: &dyn Storage) -> Option<String> {
+        Some(self.address.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{
+        coin,
+        testing::{mock_env, mock_info},
+        BankMsg,
+    };
+
+    use super::*;
+    use crate::modules::{AddressWeight, Modules, Rate, PRIORITY_ROYALTY, PRIORITY_TAX};
+    use crate::testing::mock_querier::mock_dependencies_custom;
+
+    fn custom_module(hooks: Vec<(HookType, HookForward)>) -> CustomModule {
+        CustomModule {
+            address: String::from("custom_contract_address"),
+            hooks: hooks
+                .into_iter()
+                .map(|(hook, forward)| CustomHook { hook, forward })
+                .collect(),
+            priority: None,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let module = custom_module(vec![
+            (HookType::Execute, HookForward::Query),
+            (HookType::Transfer, HookForward::Message),
+        ]);
+        assert!(module.validate(vec![]).unwrap());
+
+        let no_address = CustomModule {
+            address: String::default(),
+            ..module.clone()
+        };
+        assert_eq!(
+            no_address.validate(vec![]).unwrap_err(),
+            ContractError::MissingCustomModuleAddress {}
+        );
+
+        let no_hooks = custom_module(vec![]);
+        assert_eq!(
+            no_hooks.validate(vec![]).unwrap_err(),
+            ContractError::NoCustomHooks {}
+        );
+
+        let duplicate = custom_module(vec![
+            (HookType::Transfer, HookForward::Query),
+            (HookType::Transfer, HookForward::Message),
+        ]);
+        assert_eq!(
+            duplicate.validate(vec![]).unwrap_err(),
+            ContractError::DuplicateCustomHook {}
+        );
+
+        let execute_message = custom_module(vec![(HookType::Execute, HookForward::Message)]);
+        assert_eq!(
+            execute_message.validate(vec![]).unwrap_err(),
+            ContractError::InvalidExecuteHookForward {}
+        );
+    }
+
+    #[test]
+    fn test_on_instantiate() {
+        let mut deps = mock_dependencies_custom(&[]);
+        let module = custom_module(vec![(HookType::Transfer, HookForward::Query)]);
+        module
+            .on_instantiate(&deps.as_mut(), mock_info("sender", &[]), mock_env())
+            .unwrap();
+
+        let invalid_address = CustomModule {
+            address: String::from("xy"),
+            ..module
+        };
+        assert_eq!(
+            invalid_address
+                .on_instantiate(&deps.as_mut(), mock_info("sender", &[]), mock_env())
+                .unwrap_err(),
+            ContractError::InvalidCustomModuleAddress {}
+        );
+    }
+
+    #[test]
+    fn test_priority() {
+        let tax = ModuleDefinition::Taxable {
+            rate: Rate::Percent(2),
+            receivers: vec![AddressWeight::new("recv", 1)],
+            description: None,
+            rounding: None,
+        };
+        let whitelist = ModuleDefinition::Whitelist {
+            address: Some(String::from("addresslist")),
+            code_id: None,
+            moderators: None,
+        };
+        let custom = custom_module(vec![(HookType::AgreedTransfer, HookForward::Query)]);
+        let late_custom = CustomModule {
+            priority: Some(PRIORITY_ROYALTY + 1),
+            ..custom.clone()
+        };
+
+        let modules = Modules::new(vec![
+            late_custom.as_definition(),
+            tax.clone(),
+            custom.as_definition(),
+            whitelist.clone(),
+        ]);
+        assert!(modules.validate().unwrap());
+        assert_eq!(modules.execution_order(), vec![3, 2, 1, 0]);
+
+        let conflicting = Modules::new(vec![
+            tax,
+            CustomModule {
+                priority: Some(PRIORITY_TAX),
+                ..custom.clone()
+            }
+            .as_definition(),
+        ]);
+        assert_eq!(
+            conflicting.validate().unwrap_err(),
+            ContractError::CustomModulePriorityConflict { priority: 30 }
+        );
+
+        let duplicate_default = Modules::new(vec![custom.as_definition(), custom.as_definition()]);
+        assert_eq!(
+            duplicate_default.validate().unwrap_err(),
+            ContractError::CustomModulePriorityConflict { priority: 20 }
+        );
+    }
+
+    #[test]
+    fn test_query_hook() {
+        let mut deps = mock_dependencies_custom(&[]);
+        let module = custom_module(vec![(HookType::Transfer, HookForward::Query)]);
+
+        let res = module
+            .on_transfer(
+                &deps.as_mut(),
+                mock_info("sender", &[]),
+                mock_env(),
+                String::from("recipient"),
+                String::from("token_id"),
+            )
+            .unwrap();
+        assert_eq!(res, HookResponse::default());
+
+        let err = module
+            .on_transfer(
+                &deps.as_mut(),
+                mock_info("sender", &[]),
+                mock_env(),
+                String::from("rejected_recipient"),
+                String::from("token_id"),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::CustomModuleRejection {
+                reason: String::from("Recipient rejected by custom module")
+            }
+        );
+    }
+
+    #[test]
+    fn test_message_hook() {
+        let mut deps = mock_dependencies_custom(&[]);
+        let module = custom_module(vec![(HookType::Burn, HookForward::Message)]);
+
+        let res = module
+            .on_burn(
+                &deps.as_mut(),
+                mock_info("sender", &[]),
+                mock_env(),
+                String::from("token_id"),
+            )
+            .unwrap();
+        let expected = SubMsg::new(WasmMsg::Execute {
+            contract_addr: String::from("custom_contract_address"),
+            msg: to_binary(&CustomHookMsg::Hook(HookMsg::OnBurn {
+                sender: String::from("sender"),
+                token_id: String::from("token_id"),
+            }))
+            .unwrap(),
+            funds: vec![],
+        });
+        assert_eq!(res.msgs, vec![expected]);
+
+        // Hooks that are not selected are not forwarded
+        let res = module
+            .on_archive(
+                &deps.as_mut(),
+                mock_info("sender", &[]),
+                mock_env(),
+                String::from("token_id"),
+            )
+            .unwrap();
+        assert_eq!(res, HookResponse::default());
+    }
+
+    #[test]
+    fn test_on_agreed_transfer() {
+        let mut deps = mock_dependencies_custom(&[]);
+        let module = custom_module(vec![(HookType::AgreedTransfer, HookForward::Query)]);
+        let mut payments = vec![];
+        add_payment(
+            &mut payments,
+            String::from("owner"),
+            Funds::Native(coin(100, "uluna")),
+        );
+
+        let res = module
+            .on_agreed_transfer(
+                &deps.as_mut(),
+                mock_info("purchaser", &[]),
+                mock_env(),
+                &mut payments,
+                String::from("owner"),
+                String::from("purchaser"),
+                Funds::Native(coin(100, "uluna")),
+            )
+            .unwrap();
+
+        assert_eq!(
+            payments,
+            vec![
+                Payment::Bank(BankMsg::Send {
+                    to_address: String::from("owner"),
+                    amount: vec![coin(100, "uluna")],
+                }),
+                Payment::Bank(BankMsg::Send {
+                    to_address: String::from("custom_receiver"),
+                    amount: vec![coin(10, "uluna")],
+                }),
+            ]
+        );
+        assert_eq!(
+            res.events,
+            vec![Event::new(CUSTOM_EVENT_ID)
+                .add_attribute("contract", "custom_contract_address")
+                .add_attribute(ATTR_PAYMENT, "custom_receiver<10uluna")]
+        );
+    }
+}
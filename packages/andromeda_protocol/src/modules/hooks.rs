@@ -1,6 +1,9 @@
-use cosmwasm_std::{BankMsg, Coin, DepsMut, Env, Event, MessageInfo, StdResult, SubMsg};
+use crate::error::ContractError;
+use cosmwasm_std::{DepsMut, Env, Event, MessageInfo, SubMsg};
 use cw721::Expiration;
 
+use crate::modules::common::{Funds, Payment};
+
 pub const ATTR_DESC: &str = "description";
 pub const ATTR_PAYMENT: &str = "payment";
 pub const ATTR_DEDUCTED: &str = "deducted";
@@ -47,10 +50,11 @@ impl HookResponse {
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
 /// An attribute struct used for any events that involve a payment
 pub struct PaymentAttribute {
     /// The amount paid
-    pub amount: Coin,
+    pub amount: Funds,
     /// The address the payment was made to
     pub receiver: String,
 }
@@ -73,7 +77,7 @@ pub trait MessageHooks {
         _deps: &DepsMut,
         _info: MessageInfo,
         _env: Env,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         Ok(HookResponse::default())
     }
     /// Called when any `ExecuteMsg` is received
@@ -82,7 +86,7 @@ pub trait MessageHooks {
         _deps: &DepsMut,
         _info: MessageInfo,
         _env: Env,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         Ok(HookResponse::default())
     }
     /// Called when an `ExecuteMsg::Mint` message is received
@@ -92,7 +96,7 @@ pub trait MessageHooks {
         _info: MessageInfo,
         _env: Env,
         _token_id: String,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         Ok(HookResponse::default())
     }
     /// Called when an `ExecuteMsg::Transfer` message is received
@@ -103,7 +107,7 @@ pub trait MessageHooks {
         _env: Env,
         _recipient: String,
         _token_id: String,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         Ok(HookResponse::default())
     }
     /// Called when an `ExecuteMsg::Send` message is received
@@ -114,7 +118,7 @@ pub trait MessageHooks {
         _env: Env,
         _contract: String,
         _token_id: String,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         Ok(HookResponse::default())
     }
     /// Called when an `ExecuteMsg::Approve` message is received
@@ -126,7 +130,7 @@ pub trait MessageHooks {
         _spender: String,
         _token_id: String,
         _expires: Option<Expiration>,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         Ok(HookResponse::default())
     }
     /// Called when an `ExecuteMsg::Revoke` message is received
@@ -137,7 +141,7 @@ pub trait MessageHooks {
         _env: Env,
         _sender: String,
         _token_id: String,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         Ok(HookResponse::default())
     }
     /// Called when an `ExecuteMsg::ApproveAll` message is received
@@ -148,7 +152,7 @@ pub trait MessageHooks {
         _env: Env,
         _operator: String,
         _expires: Option<Expiration>,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         Ok(HookResponse::default())
     }
     /// Called when an `ExecuteMsg::RevokeAll` message is received
@@ -158,7 +162,7 @@ pub trait MessageHooks {
         _info: MessageInfo,
         _env: Env,
         _operator: String,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         Ok(HookResponse::default())
     }
     /// Called when an `ExecuteMsg::TransferAgreement` message is received
@@ -171,7 +175,7 @@ pub trait MessageHooks {
         _purchaser: String,
         _amount: u128,
         _denom: String,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         Ok(HookResponse::default())
     }
     /// Called when an `ExecuteMsg::Burn` message is received
@@ -181,7 +185,7 @@ pub trait MessageHooks {
         _info: MessageInfo,
         _env: Env,
         _token_id: String,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         Ok(HookResponse::default())
     }
     /// Called when an `ExecuteMsg::Archive` message is received
@@ -191,7 +195,7 @@ pub trait MessageHooks {
         _info: MessageInfo,
         _env: Env,
         _token_id: String,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         Ok(HookResponse::default())
     }
     /// Called whenever an agreed transfer is taking place
@@ -200,11 +204,11 @@ pub trait MessageHooks {
         _deps: &DepsMut,
         _info: MessageInfo,
         _env: Env,
-        _payments: &mut Vec<BankMsg>,
+        _payments: &mut Vec<Payment>,
         _owner: String,
         _purchaser: String,
-        _amount: Coin,
-    ) -> StdResult<HookResponse> {
+        _amount: Funds,
+    ) -> Result<HookResponse, ContractError> {
         Ok(HookResponse::default())
     }
 }
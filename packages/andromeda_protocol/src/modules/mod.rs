@@ -1,18 +1,24 @@
 pub mod address_list;
+pub mod auction;
 pub mod common;
+pub mod custom;
 pub mod hooks;
 pub mod receipt;
 pub mod royalties;
 pub mod taxable;
 
+use crate::error::ContractError;
 use crate::modules::{
     address_list::AddressListModule,
+    auction::AuctionModule,
+    common::{Funds, Payment},
+    custom::{CustomHook, CustomModule},
     hooks::{HookResponse, MessageHooks},
     receipt::ReceiptModule,
     royalties::Royalty,
     taxable::Taxable,
 };
-use cosmwasm_std::{BankMsg, Coin, DepsMut, Env, MessageInfo, StdResult, Storage, Uint128};
+use cosmwasm_std::{from_slice, Decimal, DepsMut, Env, MessageInfo, StdResult, Storage, Uint128};
 use cw721::Expiration;
 use cw_storage_plus::Item;
 use schemars::JsonSchema;
@@ -20,6 +26,19 @@ use serde::{Deserialize, Serialize};
 
 pub const MODULES: Item<Modules> = Item::new("modules");
 
+/// Hook priority of address list modules, executed first so that unauthorized messages are rejected before any other hook
+pub const PRIORITY_ADDRESS_LIST: u64 = 0;
+/// Hook priority of the auction module
+pub const PRIORITY_AUCTION: u64 = 10;
+/// Default hook priority of custom modules
+pub const PRIORITY_CUSTOM: u64 = 20;
+/// Hook priority of tax modules, executed before royalties
+pub const PRIORITY_TAX: u64 = 30;
+/// Hook priority of the royalty module
+pub const PRIORITY_ROYALTY: u64 = 40;
+/// Hook priority of the receipt module, executed last
+pub const PRIORITY_RECEIPT: u64 = 100;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Eq)]
 #[serde(rename_all = "snake_case")]
 /// A struct used to define a flat rate fee
@@ -38,11 +57,45 @@ pub enum Rate {
     Flat(FlatRate),
     /// A percentage fee (integer)
     Percent(u64),
+    /// A fractional fee, e.g. `0.025` for a 2.5% fee. Allows for precision beyond whole percentages.
+    Decimal(Decimal),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema, Eq)]
+#[serde(rename_all = "snake_case")]
+/// An enum used to define how any remainder of a calculated fee is rounded
+pub enum Rounding {
+    /// Rounds any remainder up, in favour of the fee receiver
+    #[default]
+    Up,
+    /// Rounds any remainder down, in favour of the payer
+    Down,
+    /// Rounds to the nearest whole amount, rounding exact halves to the nearest even amount
+    HalfEven,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Eq)]
+/// A weighted receiver of a module fee. The fee is split amongst all receivers in proportion to their weights.
+pub struct AddressWeight {
+    /// The receiving address
+    pub addr: String,
+    /// The receiver's share of the fee relative to the other receivers
+    pub weight: Uint128,
+}
+
+impl AddressWeight {
+    pub fn new(addr: impl Into<String>, weight: u128) -> AddressWeight {
+        AddressWeight {
+            addr: addr.into(),
+            weight: Uint128::from(weight),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Eq)]
 #[serde(rename_all = "snake_case")]
-/// Definitions for each module, used in the `InstantiateMsg` for the token contract to define any modules assigned to the contract
+/// Definitions for each module, used in the `InstantiateMsg` for the token contract to define any modules assigned to the contract.
+/// Module hooks are executed in order of each module's priority, lowest first, see `ModuleDefinition::priority`.
 pub enum ModuleDefinition {
     /// A whitelist module
     Whitelist {
@@ -66,19 +119,23 @@ pub enum ModuleDefinition {
     Taxable {
         /// The tax rate
         rate: Rate,
-        /// The receiving addresses of the fee
-        receivers: Vec<String>,
+        /// The weighted receivers of the fee
+        receivers: Vec<AddressWeight>,
         /// An optional description of the fee
         description: Option<String>,
+        /// How any remainder of the fee is rounded, defaults to rounding up
+        rounding: Option<Rounding>,
     },
     /// A royalty module. Required payments are paid by the seller.
     Royalties {
         /// The royalty rate
         rate: Rate,
-        /// The receiving addresses of the fee
-        receivers: Vec<String>,
+        /// The weighted receivers of the fee
+        receivers: Vec<AddressWeight>,
         /// An optional description of the fee
         description: Option<String>,
+        /// How any remainder of the fee is rounded, defaults to rounding up
+        rounding: Option<Rounding>,
     },
     /// A receipt module
     Receipt {
@@ -89,10 +146,24 @@ pub enum ModuleDefinition {
         /// A vector of contract moderators. Used in combination with a valid `code_id` parameter
         moderators: Option<Vec<String>>,
     },
+    /// An auction module. Enables English and Dutch auctions of tokens.
+    Auction {
+        /// Bids placed on an English auction within this many seconds of its end extend the auction to this many seconds after the bid
+        extension_window: Option<u64>,
+    },
+    /// A custom module. Forwards the selected hooks to an external contract implementing `CustomHookMsg`.
+    Custom {
+        /// The address of the module contract
+        address: String,
+        /// The hooks forwarded to the module contract and how each is forwarded
+        hooks: Vec<CustomHook>,
+        /// The priority of the module's hooks, defaults to `PRIORITY_CUSTOM`. Must differ from the priority of every other module.
+        priority: Option<u64>,
+    },
 }
 
 pub trait Module: MessageHooks {
-    fn validate(&self, modules: Vec<ModuleDefinition>) -> StdResult<bool>;
+    fn validate(&self, modules: Vec<ModuleDefinition>) -> Result<bool, ContractError>;
     fn as_definition(&self) -> ModuleDefinition;
     fn get_contract_address(&self, _storage: &dyn Storage) -> Option<String> {
         None
@@ -107,8 +178,31 @@ impl ModuleDefinition {
             ModuleDefinition::Whitelist { .. } => "whitelist",
             ModuleDefinition::Blacklist { .. } => "blacklist",
             ModuleDefinition::Taxable { .. } => "tax",
+            ModuleDefinition::Auction { .. } => "auction",
+            ModuleDefinition::Custom { .. } => "custom",
         })
     }
+    /// The priority of the module's hooks. Hooks are executed in order of priority, lowest first:
+    /// 1. Whitelist/Blacklist (`PRIORITY_ADDRESS_LIST`)
+    /// 2. Auction (`PRIORITY_AUCTION`)
+    /// 3. Custom (`PRIORITY_CUSTOM`, unless a priority is provided)
+    /// 4. Taxable (`PRIORITY_TAX`)
+    /// 5. Royalties (`PRIORITY_ROYALTY`)
+    /// 6. Receipt (`PRIORITY_RECEIPT`)
+    ///
+    /// Modules of equal priority are executed in the order they are defined.
+    pub fn priority(&self) -> u64 {
+        match self {
+            ModuleDefinition::Whitelist { .. } | ModuleDefinition::Blacklist { .. } => {
+                PRIORITY_ADDRESS_LIST
+            }
+            ModuleDefinition::Auction { .. } => PRIORITY_AUCTION,
+            ModuleDefinition::Custom { priority, .. } => priority.unwrap_or(PRIORITY_CUSTOM),
+            ModuleDefinition::Taxable { .. } => PRIORITY_TAX,
+            ModuleDefinition::Royalties { .. } => PRIORITY_ROYALTY,
+            ModuleDefinition::Receipt { .. } => PRIORITY_RECEIPT,
+        }
+    }
     pub fn as_module(&self) -> Box<dyn Module> {
         match self {
             ModuleDefinition::Whitelist {
@@ -136,19 +230,23 @@ impl ModuleDefinition {
                 rate,
                 receivers,
                 description,
+                rounding,
             } => Box::from(Taxable {
                 rate: rate.clone(),
                 receivers: receivers.clone(),
                 description: description.clone(),
+                rounding: rounding.clone(),
             }),
             ModuleDefinition::Royalties {
                 rate,
                 receivers,
                 description,
+                rounding,
             } => Box::from(Royalty {
                 rate: rate.clone(),
                 receivers: receivers.to_vec(),
                 description: description.clone(),
+                rounding: rounding.clone(),
             }),
             ModuleDefinition::Receipt {
                 moderators,
@@ -159,6 +257,18 @@ impl ModuleDefinition {
                 address: address.clone(),
                 code_id: *code_id,
             }),
+            ModuleDefinition::Auction { extension_window } => Box::from(AuctionModule {
+                extension_window: *extension_window,
+            }),
+            ModuleDefinition::Custom {
+                address,
+                hooks,
+                priority,
+            } => Box::from(CustomModule {
+                address: address.clone(),
+                hooks: hooks.clone(),
+                priority: *priority,
+            }),
         }
     }
 }
@@ -179,14 +289,36 @@ impl Modules {
             module_defs: vec![],
         }
     }
+    /// Returns the modules in the order their hooks are executed
     pub fn to_modules(&self) -> Vec<Box<dyn Module>> {
-        self.module_defs
-            .to_vec()
+        self.execution_order()
             .into_iter()
-            .map(|d| d.as_module())
+            .map(|idx| self.module_defs[idx].as_module())
             .collect()
     }
-    pub fn validate(&self) -> StdResult<bool> {
+    /// Returns the indices of `module_defs` in the order their hooks are executed, see `ModuleDefinition::priority`
+    pub fn execution_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.module_defs.len()).collect();
+        // A stable sort, modules of equal priority keep the order they were defined in
+        order.sort_by_key(|idx| self.module_defs[*idx].priority());
+
+        order
+    }
+    /// Replaces any royalty module with the given royalty, adding it if no royalty module exists
+    pub fn with_royalty(mut self, royalty: Royalty) -> Modules {
+        let definition = royalty.as_definition();
+        match self
+            .module_defs
+            .iter()
+            .position(|d| matches!(d, ModuleDefinition::Royalties { .. }))
+        {
+            Some(index) => self.module_defs[index] = definition,
+            None => self.module_defs.push(definition),
+        }
+
+        self
+    }
+    pub fn validate(&self) -> Result<bool, ContractError> {
         for module in self.to_modules() {
             module.validate(self.module_defs.clone())?;
         }
@@ -198,7 +330,7 @@ impl Modules {
         deps: &DepsMut,
         info: MessageInfo,
         env: Env,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         let modules = self.to_modules();
         let mut resp = HookResponse::default();
         for module in modules {
@@ -208,7 +340,12 @@ impl Modules {
 
         Ok(resp)
     }
-    pub fn on_execute(&self, deps: &DepsMut, info: MessageInfo, env: Env) -> StdResult<()> {
+    pub fn on_execute(
+        &self,
+        deps: &DepsMut,
+        info: MessageInfo,
+        env: Env,
+    ) -> Result<(), ContractError> {
         let modules = self.to_modules();
         for module in modules {
             module.on_execute(&deps, info.clone(), env.clone())?;
@@ -222,7 +359,7 @@ impl Modules {
         info: MessageInfo,
         env: Env,
         token_id: String,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         let modules = self.to_modules();
         let mut resp = HookResponse::default();
         for module in modules {
@@ -239,7 +376,7 @@ impl Modules {
         env: Env,
         recipient: String,
         token_id: String,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         let modules = self.to_modules();
         let mut resp = HookResponse::default();
         for module in modules {
@@ -264,7 +401,7 @@ impl Modules {
         purchaser: String,
         amount: u128,
         denom: String,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         let modules = self.to_modules();
         let mut resp = HookResponse::default();
         for module in modules {
@@ -287,11 +424,11 @@ impl Modules {
         deps: &DepsMut,
         info: MessageInfo,
         env: Env,
-        payments: &mut Vec<BankMsg>,
+        payments: &mut Vec<Payment>,
         owner: String,
         purchaser: String,
-        amount: Coin,
-    ) -> StdResult<HookResponse> {
+        amount: Funds,
+    ) -> Result<HookResponse, ContractError> {
         let modules = self.to_modules();
         let mut resp = HookResponse::default();
         for module in modules {
@@ -317,7 +454,7 @@ impl Modules {
         env: Env,
         contract: String,
         token_id: String,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         let modules = self.to_modules();
         let mut resp = HookResponse::default();
         for module in modules {
@@ -341,7 +478,7 @@ impl Modules {
         sender: String,
         token_id: String,
         expires: Option<Expiration>,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         let modules = self.to_modules();
         let mut resp = HookResponse::default();
         for module in modules {
@@ -365,7 +502,7 @@ impl Modules {
         env: Env,
         sender: String,
         token_id: String,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         let modules = self.to_modules();
         let mut resp = HookResponse::default();
         for module in modules {
@@ -388,7 +525,7 @@ impl Modules {
         env: Env,
         operator: String,
         expires: Option<Expiration>,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         let modules = self.to_modules();
         let mut resp = HookResponse::default();
         for module in modules {
@@ -410,7 +547,7 @@ impl Modules {
         info: MessageInfo,
         env: Env,
         operator: String,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         let modules = self.to_modules();
         let mut resp = HookResponse::default();
         for module in modules {
@@ -427,7 +564,7 @@ impl Modules {
         info: MessageInfo,
         env: Env,
         token_id: String,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         let modules = self.to_modules();
         let mut resp = HookResponse::default();
         for module in modules {
@@ -443,11 +580,11 @@ impl Modules {
         info: MessageInfo,
         env: Env,
         token_id: String,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         let modules = self.to_modules();
         let mut resp = HookResponse::default();
         for module in modules {
-            let mod_res = module.on_burn(&deps, info.clone(), env.clone(), token_id.clone())?;
+            let mod_res = module.on_archive(&deps, info.clone(), env.clone(), token_id.clone())?;
             resp = resp.add_resp(mod_res);
         }
 
@@ -455,11 +592,133 @@ impl Modules {
     }
 }
 
-pub fn store_modules(storage: &mut dyn Storage, modules: Modules) -> StdResult<()> {
+pub fn store_modules(storage: &mut dyn Storage, modules: Modules) -> Result<(), ContractError> {
     //Validate each module before storing
     modules.validate()?;
 
-    MODULES.save(storage, &modules)
+    Ok(MODULES.save(storage, &modules)?)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// The stored format of a `ModuleDefinition` before fee receivers were weighted
+enum LegacyModuleDefinition {
+    Whitelist {
+        address: Option<String>,
+        code_id: Option<u64>,
+        moderators: Option<Vec<String>>,
+    },
+    Blacklist {
+        address: Option<String>,
+        code_id: Option<u64>,
+        moderators: Option<Vec<String>>,
+    },
+    Taxable {
+        rate: Rate,
+        receivers: Vec<String>,
+        description: Option<String>,
+    },
+    Royalties {
+        rate: Rate,
+        receivers: Vec<String>,
+        description: Option<String>,
+    },
+    Receipt {
+        address: Option<String>,
+        code_id: Option<u64>,
+        moderators: Option<Vec<String>>,
+    },
+    Auction {
+        extension_window: Option<u64>,
+    },
+}
+
+impl From<LegacyModuleDefinition> for ModuleDefinition {
+    fn from(def: LegacyModuleDefinition) -> ModuleDefinition {
+        let weighted = |receivers: Vec<String>| -> Vec<AddressWeight> {
+            receivers
+                .into_iter()
+                .map(|addr| AddressWeight::new(addr, 1))
+                .collect()
+        };
+        match def {
+            LegacyModuleDefinition::Whitelist {
+                address,
+                code_id,
+                moderators,
+            } => ModuleDefinition::Whitelist {
+                address,
+                code_id,
+                moderators,
+            },
+            LegacyModuleDefinition::Blacklist {
+                address,
+                code_id,
+                moderators,
+            } => ModuleDefinition::Blacklist {
+                address,
+                code_id,
+                moderators,
+            },
+            LegacyModuleDefinition::Taxable {
+                rate,
+                receivers,
+                description,
+            } => ModuleDefinition::Taxable {
+                rate,
+                receivers: weighted(receivers),
+                description,
+                rounding: None,
+            },
+            LegacyModuleDefinition::Royalties {
+                rate,
+                receivers,
+                description,
+            } => ModuleDefinition::Royalties {
+                rate,
+                receivers: weighted(receivers),
+                description,
+                rounding: None,
+            },
+            LegacyModuleDefinition::Receipt {
+                address,
+                code_id,
+                moderators,
+            } => ModuleDefinition::Receipt {
+                address,
+                code_id,
+                moderators,
+            },
+            LegacyModuleDefinition::Auction { extension_window } => {
+                ModuleDefinition::Auction { extension_window }
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LegacyModules {
+    module_defs: Vec<LegacyModuleDefinition>,
+}
+
+/// Migrates any modules stored with unweighted fee receivers, assigning each receiver an equal weight.
+/// Modules already stored in the current format are left unchanged.
+pub fn migrate_modules(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    if MODULES.may_load(storage).is_ok() {
+        return Ok(());
+    }
+
+    let legacy: LegacyModules = match storage.get(b"modules") {
+        Some(data) => from_slice(&data)?,
+        None => return Ok(()),
+    };
+    let module_defs = legacy
+        .module_defs
+        .into_iter()
+        .map(ModuleDefinition::from)
+        .collect();
+
+    Ok(MODULES.save(storage, &Modules::new(module_defs))?)
 }
 
 pub fn read_modules(storage: &dyn Storage) -> StdResult<Modules> {
@@ -479,7 +738,7 @@ pub fn generate_instantiate_msgs(
     info: MessageInfo,
     env: Env,
     modules: Vec<Option<impl Module>>,
-) -> StdResult<HookResponse> {
+) -> Result<HookResponse, ContractError> {
     let mut resp = HookResponse::default();
 
     for module_opt in modules {
@@ -1,6 +1,7 @@
+use crate::error::ContractError;
 use cosmwasm_std::{
-    to_binary, CosmosMsg, DepsMut, Env, Event, MessageInfo, Reply, ReplyOn, Response, StdError,
-    StdResult, Storage, SubMsg, WasmMsg,
+    to_binary, CosmosMsg, DepsMut, Env, Event, MessageInfo, Reply, ReplyOn, Response, Storage,
+    SubMsg, WasmMsg,
 };
 use cw_storage_plus::Item;
 use schemars::JsonSchema;
@@ -38,15 +39,13 @@ impl ReceiptModule {
         self,
         storage: &dyn Storage,
         events: Vec<Event>,
-    ) -> StdResult<CosmosMsg> {
+    ) -> Result<CosmosMsg, ContractError> {
         let receipt = Receipt { events };
 
         let contract_addr = self
             .get_contract_address(storage)
             // [REC-01] Replace ok_or with lazily ok_or_else to optimizr smart contract efficiency
-            .ok_or_else(|| {
-                StdError::generic_err("Receipt module does not have an assigned address")
-            })?;
+            .ok_or(ContractError::MissingReceiptContract {})?;
 
         Ok(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr,
@@ -60,17 +59,15 @@ impl Module for ReceiptModule {
     /// Validates the receipt module:
     /// * Must be unique
     /// * Must include either a contract address or a combination of a valid code id and an optional vector of moderating addresses
-    fn validate(&self, all_modules: Vec<ModuleDefinition>) -> StdResult<bool> {
+    fn validate(&self, all_modules: Vec<ModuleDefinition>) -> Result<bool, ContractError> {
         require(
             is_unique(self, &all_modules),
-            StdError::generic_err("The receipt module must be unique"),
+            ContractError::DuplicateReceiptModule {},
         )?;
 
         require(
             self.address.is_some() || (self.code_id.is_some() && self.moderators.is_some()),
-            StdError::generic_err(
-                "Receipt must include either a contract address or a code id and moderator list",
-            ),
+            ContractError::InvalidReceiptModule {},
         )?;
 
         Ok(true)
@@ -97,7 +94,7 @@ impl MessageHooks for ReceiptModule {
         _deps: &DepsMut,
         info: MessageInfo,
         _env: Env,
-    ) -> StdResult<HookResponse> {
+    ) -> Result<HookResponse, ContractError> {
         let mut res = HookResponse::default();
         if self.address.is_none() {
             let inst_msg = WasmMsg::Instantiate {
@@ -125,7 +122,7 @@ impl MessageHooks for ReceiptModule {
     }
 }
 
-pub fn on_receipt_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
+pub fn on_receipt_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
     let receipt_addr = get_reply_address(msg)?;
 
     RECEIPT_CONTRACT.save(deps.storage, &receipt_addr.to_string())?;
@@ -133,8 +130,22 @@ pub fn on_receipt_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
     Ok(Response::new())
 }
 
+/// Attaches a message minting a receipt of the response's events if the current contract contains a receipt module
+pub fn add_receipt_message(
+    storage: &dyn Storage,
+    res: Response,
+) -> Result<Response, ContractError> {
+    match get_receipt_module(storage)? {
+        Some(recpt_mod) => {
+            let recpt_msg = recpt_mod.generate_receipt_message(storage, res.events.clone())?;
+            Ok(res.add_message(recpt_msg))
+        }
+        None => Ok(res),
+    }
+}
+
 /// Searches the stored vector of Modules within the current contract for a receipt module
-pub fn get_receipt_module(storage: &dyn Storage) -> StdResult<Option<ReceiptModule>> {
+pub fn get_receipt_module(storage: &dyn Storage) -> Result<Option<ReceiptModule>, ContractError> {
     let modules = read_modules(storage)?;
     let receipt_def = modules.module_defs.iter().find(|m| match m {
         ModuleDefinition::Receipt { .. } => true,
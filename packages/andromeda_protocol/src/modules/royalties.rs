@@ -1,26 +1,38 @@
-use cosmwasm_std::{DepsMut, Env, Event, MessageInfo, StdError, StdResult};
+use crate::error::ContractError;
+use cosmwasm_std::{Decimal, DepsMut, Env, Event, MessageInfo, Storage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use crate::require;
 
 use super::{
-    common::{add_payment, calculate_fee, deduct_payment},
-    hooks::{HookResponse, MessageHooks, PaymentAttribute, ATTR_DEDUCTED, ATTR_DESC, ATTR_PAYMENT},
-    Module, ModuleDefinition, Rate,
+    common::{
+        add_payment, calculate_payment_fee, calculate_shares, deduct_payment, validate_receivers,
+        Funds, Payment,
+    },
+    hooks::{HookResponse, MessageHooks, ATTR_DEDUCTED, ATTR_DESC, ATTR_PAYMENT},
+    read_modules, AddressWeight, Module, ModuleDefinition, Rate, Rounding,
 };
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 /// Struct used to define a Royalty module
 /// This module will generate required payments upon an agreed transfer of any token.
+/// May also be assigned to an individual token, in which case it is used in place of the contract's royalty module for that token.
 pub struct Royalty {
     /// The rate of the royalty
     pub rate: Rate,
-    /// The receiving addresses of the royalty fee
-    pub receivers: Vec<String>,
+    /// The weighted receivers of the royalty fee
+    pub receivers: Vec<AddressWeight>,
     /// An optional description of the royalty fee
     pub description: Option<String>,
+    /// How any remainder of the royalty fee is rounded, defaults to rounding up
+    pub rounding: Option<Rounding>,
 }
 
 impl MessageHooks for Royalty {
-    /// Calculates the required royalty fee for an agreed transfer and returns the required `BankMsg` to send the fee payments.
+    /// Calculates the required royalty fee for an agreed transfer and adds the required payments of the fee.
+    /// The fee is split amongst the receivers according to their weights, receivers of a zero share are not paid.
+    /// Errors if a flat rate fee is not in the denomination of the agreed transfer.
     /// Generates a royalty payment event.
     /// **Any fees generated by this hook are paid for by the seller and are deducted from the agreed transfer payment sent with the message by the purchaser.**
     fn on_agreed_transfer(
@@ -28,12 +40,21 @@ impl MessageHooks for Royalty {
         _deps: &DepsMut,
         _info: MessageInfo,
         _env: Env,
-        payments: &mut Vec<cosmwasm_std::BankMsg>,
+        payments: &mut Vec<Payment>,
         owner: String,
         _purchaser: String,
-        amount: cosmwasm_std::Coin,
-    ) -> StdResult<HookResponse> {
-        let fee_payment = calculate_fee(self.rate.clone(), amount);
+        amount: Funds,
+    ) -> Result<HookResponse, ContractError> {
+        let denom = amount.denom();
+        let fee_payment = calculate_payment_fee(
+            self.rate.clone(),
+            amount,
+            self.rounding.clone().unwrap_or_default(),
+        )?;
+        require(
+            fee_payment.denom() == denom,
+            ContractError::RoyaltyDenomMismatch {},
+        )?;
         let mut resp = HookResponse::default();
         let mut event = Event::new("royalty");
 
@@ -44,19 +65,15 @@ impl MessageHooks for Royalty {
             None => {}
         }
 
-        for receiver in self.receivers.to_vec() {
-            // Deducts the required payment since it should be paid by token owner. [ROY-01]/[TAX-02]
-            deduct_payment(payments, owner.clone(), fee_payment.clone())?;
-            event = event.add_attribute(ATTR_DEDUCTED, fee_payment.to_string());
-            add_payment(payments, receiver.clone(), fee_payment.clone());
-            event = event.add_attribute(
-                ATTR_PAYMENT,
-                PaymentAttribute {
-                    receiver,
-                    amount: fee_payment.clone(),
-                }
-                .to_string(),
-            );
+        // Deducts the required payment since it should be paid by token owner. [ROY-01]/[TAX-02]
+        deduct_payment(payments, owner, fee_payment.clone())?;
+        event = event.add_attribute(ATTR_DEDUCTED, fee_payment.to_string());
+        for share in calculate_shares(fee_payment, &self.receivers)? {
+            if share.amount.amount().is_zero() {
+                continue;
+            }
+            add_payment(payments, share.receiver.clone(), share.amount.clone());
+            event = event.add_attribute(ATTR_PAYMENT, share.to_string());
         }
 
         resp = resp.add_event(event);
@@ -68,33 +85,34 @@ impl MessageHooks for Royalty {
 impl Module for Royalty {
     /// Validates the royalty module:
     /// * Royalty must have at least one receiver
-    /// * Royalty rate must be non-zero
+    /// * Receiver weights must be non-zero
+    /// * Royalty rate must be non-zero and may not exceed 100%
     /// * Any optional description provided cannot exceed 200 characters in length
-    fn validate(&self, _extensions: Vec<super::ModuleDefinition>) -> StdResult<bool> {
+    fn validate(&self, _extensions: Vec<super::ModuleDefinition>) -> Result<bool, ContractError> {
         require(
             self.receivers.len() > 0,
-            StdError::generic_err("Cannot apply a royalty with no receiving addresses"),
+            ContractError::NoRoyaltyReceivers {},
         )?;
+        validate_receivers(&self.receivers)?;
         // require(self.rate > 0, StdError::generic_err("Tax must be non-zero"))?;
         match self.rate.clone() {
             Rate::Flat(rate) => {
-                require(
-                    rate.amount.u128() > 0,
-                    StdError::generic_err("Royalty rate must be non-zero"),
-                )?;
+                require(rate.amount.u128() > 0, ContractError::InvalidRoyaltyRate {})?;
             }
             Rate::Percent(rate) => {
-                require(
-                    rate > 0,
-                    StdError::generic_err("Royalty rate must be non-zero"),
-                )?;
+                require(rate > 0, ContractError::InvalidRoyaltyRate {})?;
+                require(rate <= 100, ContractError::RateTooHigh {})?;
+            }
+            Rate::Decimal(rate) => {
+                require(!rate.is_zero(), ContractError::InvalidRoyaltyRate {})?;
+                require(rate <= Decimal::one(), ContractError::RateTooHigh {})?;
             }
         }
 
         if self.description.clone().is_some() {
             require(
                 self.description.clone().unwrap().len() <= 200,
-                StdError::generic_err("Module description can be at most 200 characters long"),
+                ContractError::DescriptionTooLong {},
             )?;
         }
 
@@ -105,19 +123,80 @@ impl Module for Royalty {
             rate: self.rate.clone(),
             receivers: self.receivers.to_vec(),
             description: self.description.clone(),
+            rounding: self.rounding.clone(),
         }
     }
 }
 
+/// Gets the royalty module of the current contract, if it exists
+pub fn get_royalty_module(storage: &dyn Storage) -> Result<Option<Royalty>, ContractError> {
+    let modules = read_modules(storage)?;
+    let royalty = modules.module_defs.iter().find_map(|m| match m {
+        ModuleDefinition::Royalties {
+            rate,
+            receivers,
+            description,
+            rounding,
+        } => Some(Royalty {
+            rate: rate.clone(),
+            receivers: receivers.to_vec(),
+            description: description.clone(),
+            rounding: rounding.clone(),
+        }),
+        _ => None,
+    });
+
+    Ok(royalty)
+}
+
 #[cfg(test)]
 mod tests {
     use cosmwasm_std::{
         coin,
         testing::{mock_dependencies, mock_env, mock_info},
-        BankMsg,
+        BankMsg, Uint128,
     };
 
     use super::*;
+    use crate::modules::{common::calculate_fee, hooks::PaymentAttribute, FlatRate};
+
+    #[test]
+    fn test_validate() {
+        let royalty = Royalty {
+            rate: Rate::Percent(100),
+            receivers: vec![AddressWeight::new("receiver", 1)],
+            description: None,
+            rounding: None,
+        };
+        assert!(royalty.validate(vec![]).unwrap());
+
+        let zero_rate = Royalty {
+            rate: Rate::Percent(0),
+            ..royalty.clone()
+        };
+        assert_eq!(
+            zero_rate.validate(vec![]).unwrap_err(),
+            ContractError::InvalidRoyaltyRate {}
+        );
+
+        let percent_too_high = Royalty {
+            rate: Rate::Percent(150),
+            ..royalty.clone()
+        };
+        assert_eq!(
+            percent_too_high.validate(vec![]).unwrap_err(),
+            ContractError::RateTooHigh {}
+        );
+
+        let decimal_too_high = Royalty {
+            rate: Rate::Decimal(Decimal::percent(101)),
+            ..royalty
+        };
+        assert_eq!(
+            decimal_too_high.validate(vec![]).unwrap_err(),
+            ContractError::RateTooHigh {}
+        );
+    }
 
     #[test]
     fn test_on_agreed_transfer() {
@@ -128,15 +207,20 @@ mod tests {
         let receiver_one = "receiverone";
         let receiver_two = "receivertwo";
         let agreed_amount = coin(100, "uluna");
-        let fee_amount = coin(2, "uluna");
-        let mut payments = vec![BankMsg::Send {
+        // A 2uluna royalty split evenly between the receivers
+        let fee_amount = coin(1, "uluna");
+        let mut payments = vec![Payment::Bank(BankMsg::Send {
             to_address: owner.to_string(),
             amount: vec![agreed_amount.clone()],
-        }];
+        })];
         let royalty = Royalty {
             rate: Rate::Percent(2),
-            receivers: vec![receiver_one.to_string(), receiver_two.to_string()],
+            receivers: vec![
+                AddressWeight::new(receiver_one, 1),
+                AddressWeight::new(receiver_two, 1),
+            ],
             description: None,
+            rounding: None,
         };
 
         royalty
@@ -147,29 +231,80 @@ mod tests {
                 &mut payments,
                 owner.to_string(),
                 String::default(),
-                agreed_amount.clone(),
+                Funds::Native(agreed_amount.clone()),
             )
             .unwrap();
 
         assert_eq!(payments.len(), 3);
-        let receiver_one_payment = BankMsg::Send {
+        let receiver_one_payment = Payment::Bank(BankMsg::Send {
             to_address: receiver_one.to_string(),
             amount: vec![fee_amount.clone()],
-        };
+        });
         assert_eq!(payments[1], receiver_one_payment);
-        let receiver_two_payment = BankMsg::Send {
+        let receiver_two_payment = Payment::Bank(BankMsg::Send {
             to_address: receiver_two.to_string(),
             amount: vec![fee_amount.clone()],
-        };
+        });
         assert_eq!(payments[2], receiver_two_payment);
         //Check that the royalty payments are deducted from the seller
-        let deducted_payment = BankMsg::Send {
+        let deducted_payment = Payment::Bank(BankMsg::Send {
             to_address: owner.to_string(),
-            amount: vec![coin(96, "uluna")],
-        };
+            amount: vec![coin(98, "uluna")],
+        });
         assert_eq!(payments[0], deducted_payment);
     }
 
+    #[test]
+    fn test_on_agreed_transfer_decimal() {
+        let mut deps = mock_dependencies(&[]);
+        let owner = "owner";
+        let agreed_amount = coin(1000, "uluna");
+        let mut payments = vec![Payment::Bank(BankMsg::Send {
+            to_address: owner.to_string(),
+            amount: vec![agreed_amount.clone()],
+        })];
+        let royalty = Royalty {
+            rate: Rate::Decimal(Decimal::permille(25)),
+            receivers: vec![
+                AddressWeight::new("receiverone", 1),
+                AddressWeight::new("receivertwo", 1),
+            ],
+            description: None,
+            rounding: None,
+        };
+        royalty.validate(vec![]).unwrap();
+
+        royalty
+            .on_agreed_transfer(
+                &deps.as_mut(),
+                mock_info("purchaser", &[]),
+                mock_env(),
+                &mut payments,
+                owner.to_string(),
+                String::default(),
+                Funds::Native(agreed_amount),
+            )
+            .unwrap();
+
+        assert_eq!(
+            payments,
+            vec![
+                Payment::Bank(BankMsg::Send {
+                    to_address: owner.to_string(),
+                    amount: vec![coin(975, "uluna")],
+                }),
+                Payment::Bank(BankMsg::Send {
+                    to_address: "receiverone".to_string(),
+                    amount: vec![coin(13, "uluna")],
+                }),
+                Payment::Bank(BankMsg::Send {
+                    to_address: "receivertwo".to_string(),
+                    amount: vec![coin(12, "uluna")],
+                }),
+            ]
+        );
+    }
+
     #[test]
     fn test_on_agreed_transfer_resp() {
         let mut deps = mock_dependencies(&[]);
@@ -180,14 +315,18 @@ mod tests {
         let receiver_one = "receiverone";
         let receiver_two = "receivertwo";
         let agreed_amount = coin(100, "uluna");
-        let mut payments = vec![BankMsg::Send {
+        let mut payments = vec![Payment::Bank(BankMsg::Send {
             to_address: owner.to_string(),
             amount: vec![agreed_amount.clone()],
-        }];
+        })];
         let royalty = Royalty {
             rate: Rate::Percent(2),
-            receivers: vec![receiver_one.to_string(), receiver_two.to_string()],
+            receivers: vec![
+                AddressWeight::new(receiver_one, 1),
+                AddressWeight::new(receiver_two, 1),
+            ],
             description: Some(desc.to_string()),
+            rounding: None,
         };
 
         let resp = royalty
@@ -198,31 +337,62 @@ mod tests {
                 &mut payments,
                 owner.to_string(),
                 String::default(),
-                agreed_amount.clone(),
+                Funds::Native(agreed_amount.clone()),
             )
             .unwrap();
 
         assert_eq!(resp.events.len(), 1);
         assert_eq!(resp.events[0].ty, "royalty");
-        assert_eq!(
-            resp.events[0].attributes.len(),
-            1 + (royalty.receivers.len() * 2)
-        );
+        assert_eq!(resp.events[0].attributes.len(), 2 + royalty.receivers.len());
         assert_eq!(resp.events[0].attributes[0].key, ATTR_DESC);
         assert_eq!(resp.events[0].attributes[0].value, desc.to_string());
         assert_eq!(resp.events[0].attributes[1].key, ATTR_DEDUCTED);
         assert_eq!(
             resp.events[0].attributes[1].value,
-            calculate_fee(royalty.rate.clone(), agreed_amount.clone()).to_string()
+            calculate_fee(royalty.rate.clone(), agreed_amount.clone())
+                .unwrap()
+                .to_string()
         );
         assert_eq!(resp.events[0].attributes[2].key, ATTR_PAYMENT);
         assert_eq!(
             resp.events[0].attributes[2].value,
             PaymentAttribute {
-                receiver: royalty.receivers[0].clone(),
-                amount: calculate_fee(royalty.rate.clone(), agreed_amount.clone())
+                receiver: receiver_one.to_string(),
+                amount: Funds::Native(coin(1, "uluna")),
             }
             .to_string()
         );
     }
+
+    #[test]
+    fn test_on_agreed_transfer_flat_rate_other_denom() {
+        let mut deps = mock_dependencies(&[]);
+        let owner = "owner";
+        let mut payments = vec![Payment::Bank(BankMsg::Send {
+            to_address: owner.to_string(),
+            amount: vec![coin(100, "uluna")],
+        })];
+        let royalty = Royalty {
+            rate: Rate::Flat(FlatRate {
+                amount: Uint128::from(10u128),
+                denom: "uusd".to_string(),
+            }),
+            receivers: vec![AddressWeight::new("receiver", 1)],
+            description: None,
+            rounding: None,
+        };
+
+        let err = royalty
+            .on_agreed_transfer(
+                &deps.as_mut(),
+                mock_info("purchaser", &[]),
+                mock_env(),
+                &mut payments,
+                owner.to_string(),
+                String::default(),
+                Funds::Native(coin(100, "uluna")),
+            )
+            .unwrap_err();
+        assert_eq!(err, ContractError::RoyaltyDenomMismatch {});
+    }
 }
@@ -1,10 +1,13 @@
-use cosmwasm_std::{Coin, DepsMut, Env, Event, MessageInfo, StdError, StdResult};
+use crate::error::ContractError;
+use cosmwasm_std::{Decimal, DepsMut, Env, Event, MessageInfo};
 
 use crate::{
-    modules::common::{add_payment, calculate_fee},
-    modules::hooks::{MessageHooks, PaymentAttribute},
+    modules::common::{
+        add_payment, calculate_payment_fee, calculate_shares, validate_receivers, Funds, Payment,
+    },
+    modules::hooks::MessageHooks,
     modules::Rate,
-    modules::{Module, ModuleDefinition},
+    modules::{AddressWeight, Module, ModuleDefinition, Rounding},
     require,
 };
 
@@ -17,14 +20,17 @@ pub const TAX_EVENT_ID: &str = "tax";
 pub struct Taxable {
     /// The rate of the tax
     pub rate: Rate,
-    /// The receiving addresses of the tax fee
-    pub receivers: Vec<String>,
+    /// The weighted receivers of the tax fee
+    pub receivers: Vec<AddressWeight>,
     /// An optional description of the tax fee
     pub description: Option<String>,
+    /// How any remainder of the tax fee is rounded, defaults to rounding up
+    pub rounding: Option<Rounding>,
 }
 
 impl MessageHooks for Taxable {
-    /// Calculates the required tax fee for an agreed transfer and returns the required `BankMsg` to send the fee payments.
+    /// Calculates the required tax fee for an agreed transfer and adds the required payments of the fee.
+    /// The fee is split amongst the receivers according to their weights.
     /// Generates a tax payment event.
     /// **Any fees generated by this hook are paid for by the purchaser and are required to be sent by the purchaser upon sending a transfer message.**
     fn on_agreed_transfer(
@@ -32,13 +38,17 @@ impl MessageHooks for Taxable {
         _deps: &DepsMut,
         _info: MessageInfo,
         env: Env,
-        payments: &mut Vec<cosmwasm_std::BankMsg>,
+        payments: &mut Vec<Payment>,
         _owner: String,
         _purchaser: String,
-        agreed_payment: Coin,
-    ) -> StdResult<HookResponse> {
+        agreed_payment: Funds,
+    ) -> Result<HookResponse, ContractError> {
         let _contract_addr = env.contract.address;
-        let tax_amount = calculate_fee(self.rate.clone(), agreed_payment);
+        let tax_amount = calculate_payment_fee(
+            self.rate.clone(),
+            agreed_payment,
+            self.rounding.clone().unwrap_or_default(),
+        )?;
 
         let mut resp = HookResponse::default();
         let mut event = Event::new(TAX_EVENT_ID);
@@ -50,16 +60,12 @@ impl MessageHooks for Taxable {
             None => {}
         }
         // No deduction of payment because the buyer pays the tax while royalties are paid by seller [ROY-01]/[TAX-02]
-        for receiver in self.receivers.to_vec() {
-            add_payment(payments, receiver.clone(), tax_amount.clone());
-            event = event.add_attribute(
-                ATTR_PAYMENT,
-                PaymentAttribute {
-                    receiver,
-                    amount: tax_amount.clone(),
-                }
-                .to_string(),
-            );
+        for share in calculate_shares(tax_amount, &self.receivers)? {
+            if share.amount.amount().is_zero() {
+                continue;
+            }
+            add_payment(payments, share.receiver.clone(), share.amount.clone());
+            event = event.add_attribute(ATTR_PAYMENT, share.to_string());
         }
         resp = resp.add_event(event);
 
@@ -70,35 +76,35 @@ impl MessageHooks for Taxable {
 impl Module for Taxable {
     /// Validates the tax module:
     /// * Tax must have at least one receiver
+    /// * Receiver weights must be non-zero
     /// * Tax rate must be non-zero
     /// * Any optional description provided cannot exceed 200 characters in length
-    fn validate(&self, _modules: Vec<crate::modules::ModuleDefinition>) -> StdResult<bool> {
-        require(
-            self.receivers.len() > 0,
-            StdError::generic_err("Cannot apply a tax with no receiving addresses"),
-        )?;
+    fn validate(
+        &self,
+        _modules: Vec<crate::modules::ModuleDefinition>,
+    ) -> Result<bool, ContractError> {
+        require(self.receivers.len() > 0, ContractError::NoTaxReceivers {})?;
+        validate_receivers(&self.receivers)?;
         // require(self.rate > 0, StdError::generic_err("Tax must be non-zero"))?;
         match self.rate.clone() {
             Rate::Flat(rate) => {
-                require(
-                    rate.amount.u128() > 0,
-                    StdError::generic_err("Tax rate must be non-zero"),
-                )?;
+                require(rate.amount.u128() > 0, ContractError::InvalidTaxRate {})?;
             }
             Rate::Percent(rate) => {
-                require(rate > 0, StdError::generic_err("Tax rate must be non-zero"))?;
+                require(rate > 0, ContractError::InvalidTaxRate {})?;
                 // Added rate checker to Taxable also [COM-03] and [TAX-01]
-                require(
-                    rate <= 100,
-                    StdError::generic_err("Rate must be less than 100%"),
-                )?;
+                require(rate <= 100, ContractError::RateTooHigh {})?;
+            }
+            Rate::Decimal(rate) => {
+                require(!rate.is_zero(), ContractError::InvalidTaxRate {})?;
+                require(rate <= Decimal::one(), ContractError::RateTooHigh {})?;
             }
         }
 
         if self.description.clone().is_some() {
             require(
                 self.description.clone().unwrap().len() <= 200,
-                StdError::generic_err("Module description can be at most 200 characters long"),
+                ContractError::DescriptionTooLong {},
             )?;
         }
 
@@ -109,6 +115,7 @@ impl Module for Taxable {
             rate: self.rate.clone(),
             receivers: self.receivers.clone(),
             description: None,
+            rounding: self.rounding.clone(),
         }
     }
 }
@@ -122,37 +129,74 @@ mod tests {
     };
 
     use super::*;
+    use crate::modules::common::calculate_fee;
 
     #[test]
     fn test_taxable_validate() {
         let t = Taxable {
             rate: Rate::Percent(2),
-            receivers: vec![String::default()],
+            receivers: vec![AddressWeight::new(String::default(), 1)],
             description: None,
+            rounding: None,
         };
 
         assert_eq!(t.validate(vec![]).unwrap(), true);
 
         let t_invalidtax = Taxable {
             rate: Rate::Percent(0),
-            receivers: vec![String::default()],
+            receivers: vec![AddressWeight::new(String::default(), 1)],
             description: None,
+            rounding: None,
         };
 
         assert_eq!(
             t_invalidtax.validate(vec![]).unwrap_err(),
-            StdError::generic_err("Tax rate must be non-zero")
+            ContractError::InvalidTaxRate {}
+        );
+
+        let t_decimal = Taxable {
+            rate: Rate::Decimal(Decimal::permille(25)),
+            receivers: vec![AddressWeight::new(String::default(), 1)],
+            description: None,
+            rounding: None,
+        };
+
+        assert_eq!(t_decimal.validate(vec![]).unwrap(), true);
+
+        let t_invalid_decimal = Taxable {
+            rate: Rate::Decimal(Decimal::percent(101)),
+            receivers: vec![AddressWeight::new(String::default(), 1)],
+            description: None,
+            rounding: None,
+        };
+
+        assert_eq!(
+            t_invalid_decimal.validate(vec![]).unwrap_err(),
+            ContractError::RateTooHigh {}
         );
 
         let t_invalidrecv = Taxable {
             rate: Rate::Percent(2),
             receivers: vec![],
             description: None,
+            rounding: None,
         };
 
         assert_eq!(
             t_invalidrecv.validate(vec![]).unwrap_err(),
-            StdError::generic_err("Cannot apply a tax with no receiving addresses")
+            ContractError::NoTaxReceivers {}
+        );
+
+        let t_invalidweight = Taxable {
+            rate: Rate::Percent(2),
+            receivers: vec![AddressWeight::new(String::default(), 0)],
+            description: None,
+            rounding: None,
+        };
+
+        assert_eq!(
+            t_invalidweight.validate(vec![]).unwrap_err(),
+            ContractError::InvalidReceiverWeight {}
         );
     }
 
@@ -162,15 +206,20 @@ mod tests {
         let mut deps = mock_dependencies(&[]);
         let info = mock_info("sender", &[]);
         let env = mock_env();
-        let receivers = vec![String::from("recv1"), String::from("recv2")];
+        let receivers = vec![
+            AddressWeight::new("recv1", 1),
+            AddressWeight::new("recv2", 1),
+        ];
         let t = Taxable {
             rate: Rate::Percent(3),
             receivers: receivers.clone(),
             description: None,
+            rounding: None,
         };
 
         let agreed_transfer_amount = coin(117, "uluna");
-        let tax_amount = 4;
+        // A 4uluna tax split evenly between the receivers
+        let tax_amount = 2;
         let owner = String::from("owner");
         let purchaser = String::from("purchaser");
         let mut payments = vec![];
@@ -182,20 +231,20 @@ mod tests {
             &mut payments,
             owner.clone(),
             purchaser.clone(),
-            agreed_transfer_amount.clone(),
+            Funds::Native(agreed_transfer_amount.clone()),
         )
         .unwrap();
 
         assert_eq!(payments.len(), 2);
 
-        let first_payment = BankMsg::Send {
+        let first_payment = Payment::Bank(BankMsg::Send {
             to_address: String::from("recv1"),
             amount: coins(tax_amount, &agreed_transfer_amount.denom.to_string()),
-        };
-        let second_payment = BankMsg::Send {
+        });
+        let second_payment = Payment::Bank(BankMsg::Send {
             to_address: String::from("recv2"),
             amount: coins(tax_amount, &agreed_transfer_amount.denom.to_string()),
-        };
+        });
 
         assert_eq!(payments[0], first_payment);
         assert_eq!(payments[1], second_payment);
@@ -208,11 +257,15 @@ mod tests {
         let info = mock_info("sender", &[]);
         let env = mock_env();
         let desc = "Some tax module";
-        let receivers = vec![String::from("recv1"), String::from("recv2")];
+        let receivers = vec![
+            AddressWeight::new("recv1", 1),
+            AddressWeight::new("recv2", 4),
+        ];
         let t = Taxable {
-            rate: Rate::Percent(1),
+            rate: Rate::Percent(10),
             receivers: receivers.clone(),
             description: Some(desc.to_string()),
+            rounding: None,
         };
 
         let agreed_transfer_amount = coin(100, "uluna");
@@ -228,7 +281,7 @@ mod tests {
                 &mut payments,
                 owner.clone(),
                 purchaser.clone(),
-                agreed_transfer_amount.clone(),
+                Funds::Native(agreed_transfer_amount.clone()),
             )
             .unwrap();
 
@@ -238,13 +291,44 @@ mod tests {
         assert_eq!(resp.events[0].attributes[0].key, ATTR_DESC);
         assert_eq!(resp.events[0].attributes[0].value, desc.to_string());
         assert_eq!(resp.events[0].attributes[1].key, ATTR_PAYMENT);
+        assert_eq!(resp.events[0].attributes[1].value, "recv1<2uluna");
+        assert_eq!(resp.events[0].attributes[2].key, ATTR_PAYMENT);
+        assert_eq!(resp.events[0].attributes[2].value, "recv2<8uluna");
         assert_eq!(
-            resp.events[0].attributes[1].value,
-            PaymentAttribute {
-                receiver: t.receivers[0].clone(),
-                amount: calculate_fee(t.rate, agreed_transfer_amount)
-            }
-            .to_string()
+            calculate_fee(t.rate, agreed_transfer_amount).unwrap(),
+            coin(10, "uluna")
+        );
+    }
+
+    #[test]
+    fn test_taxable_on_agreed_transfer_rounding() {
+        let mut deps = mock_dependencies(&[]);
+        let t = Taxable {
+            rate: Rate::Percent(3),
+            receivers: vec![AddressWeight::new("recv1", 1)],
+            description: None,
+            rounding: Some(Rounding::Down),
+        };
+        let mut payments = vec![];
+
+        // 3% of 117 is 3.51, rounded down
+        t.on_agreed_transfer(
+            &deps.as_mut(),
+            mock_info("sender", &[]),
+            mock_env(),
+            &mut payments,
+            String::from("owner"),
+            String::from("purchaser"),
+            Funds::Native(coin(117, "uluna")),
+        )
+        .unwrap();
+
+        assert_eq!(
+            payments,
+            vec![Payment::Bank(BankMsg::Send {
+                to_address: String::from("recv1"),
+                amount: coins(3, "uluna"),
+            })]
         );
     }
 }
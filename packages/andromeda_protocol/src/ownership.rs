@@ -1,4 +1,6 @@
-use cosmwasm_std::{attr, Deps, DepsMut, MessageInfo, Response, StdError, StdResult, Storage};
+use crate::error::ContractError;
+use cosmwasm_std::{attr, Deps, DepsMut, Env, MessageInfo, Response, Storage};
+use cw721::Expiration;
 use cw_storage_plus::Item;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -6,59 +8,162 @@ use serde::{Deserialize, Serialize};
 use crate::require;
 
 pub const CONTRACT_OWNER: Item<String> = Item::new("contractowner");
+/// The ownership proposal awaiting acceptance by the proposed owner, if any
+pub const PENDING_OWNER: Item<PendingOwner> = Item::new("pendingowner");
+
+/// A proposed transfer of contract ownership. Ownership only changes once the proposed owner accepts it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PendingOwner {
+    /// The address that may accept ownership of the contract
+    pub address: String,
+    /// When the proposal lapses, if ever
+    pub expiration: Option<Expiration>,
+}
 
 /// Helper function to query if a given address is the current contract owner.
 ///
-/// Returns a boolean value indicating if the given address is the contract owner.
-pub fn is_contract_owner(storage: &dyn Storage, addr: String) -> StdResult<bool> {
-    let owner = CONTRACT_OWNER.load(storage)?;
+/// Returns a boolean value indicating if the given address is the contract owner. Always false once ownership has been renounced.
+pub fn is_contract_owner(storage: &dyn Storage, addr: String) -> Result<bool, ContractError> {
+    let owner = CONTRACT_OWNER.may_load(storage)?;
 
-    Ok(addr.eq(&owner))
+    Ok(owner == Some(addr))
 }
 
-/// Updates the current contract owner. **Only executable by the current contract owner.**
-pub fn execute_update_owner(
+/// Proposes a new contract owner, replacing any existing proposal. **Only executable by the current contract owner.**
+///
+/// Ownership is not transferred until the proposed owner calls `execute_accept_ownership`.
+pub fn execute_propose_owner(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    new_owner: String,
-) -> StdResult<Response> {
+    address: String,
+    expiration: Option<Expiration>,
+) -> Result<Response, ContractError> {
     require(
         is_contract_owner(deps.storage, info.sender.to_string())?,
-        StdError::generic_err(
-            "Ownership of this contract can only be transferred by the current owner",
-        ),
+        ContractError::OwnershipTransferUnauthorized {},
+    )?;
+    let address = deps.api.addr_validate(&address)?.to_string();
+    if let Some(expiration) = expiration {
+        require(
+            !expiration.is_expired(&env.block),
+            ContractError::ExpirationInPast {},
+        )?;
+    }
+    PENDING_OWNER.save(
+        deps.storage,
+        &PendingOwner {
+            address: address.clone(),
+            expiration,
+        },
     )?;
-    //
-    let new_owner_addr = deps.api.addr_validate(&new_owner)?;
-    CONTRACT_OWNER.save(deps.storage, &new_owner_addr.to_string())?;
 
+    let expiration = expiration.map_or(String::from("none"), |e| e.to_string());
     Ok(Response::new().add_attributes(vec![
-        attr("action", "update_owner"),
-        attr("value", new_owner),
+        attr("action", "propose_owner"),
+        attr("value", address),
+        attr("expiration", expiration),
     ]))
 }
 
-pub fn query_contract_owner(deps: Deps) -> StdResult<ContractOwnerResponse> {
-    let owner = CONTRACT_OWNER.load(deps.storage)?;
+/// Accepts a pending ownership proposal. **Only executable by the proposed owner before the proposal expires.**
+pub fn execute_accept_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let pending = PENDING_OWNER
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingOwner {})?;
+    require(
+        pending.address == info.sender,
+        ContractError::NotPendingOwner {},
+    )?;
+    if let Some(expiration) = pending.expiration {
+        require(
+            !expiration.is_expired(&env.block),
+            ContractError::OwnershipProposalExpired {},
+        )?;
+    }
+    CONTRACT_OWNER.save(deps.storage, &pending.address)?;
+    PENDING_OWNER.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "accept_ownership"),
+        attr("value", pending.address),
+    ]))
+}
+
+/// Withdraws a pending ownership proposal. **Only executable by the current contract owner.**
+pub fn execute_cancel_ownership_proposal(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    require(
+        is_contract_owner(deps.storage, info.sender.to_string())?,
+        ContractError::OwnershipTransferUnauthorized {},
+    )?;
+    require(
+        PENDING_OWNER.may_load(deps.storage)?.is_some(),
+        ContractError::NoPendingOwner {},
+    )?;
+    PENDING_OWNER.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![attr("action", "cancel_ownership_proposal")]))
+}
+
+/// Permanently gives up ownership of the contract, leaving any owner-only functionality unusable.
+/// Also discards any pending ownership proposal. **Only executable by the current contract owner.**
+pub fn execute_renounce_ownership(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    require(
+        is_contract_owner(deps.storage, info.sender.to_string())?,
+        ContractError::OwnershipTransferUnauthorized {},
+    )?;
+    CONTRACT_OWNER.remove(deps.storage);
+    PENDING_OWNER.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![attr("action", "renounce_ownership")]))
+}
+
+/// Returns the current contract owner, or an empty string if ownership has been renounced.
+pub fn query_contract_owner(deps: Deps) -> Result<ContractOwnerResponse, ContractError> {
+    let owner = CONTRACT_OWNER.may_load(deps.storage)?.unwrap_or_default();
 
     Ok(ContractOwnerResponse { owner })
 }
 
+pub fn query_pending_owner(deps: Deps) -> Result<PendingOwnerResponse, ContractError> {
+    let pending_owner = PENDING_OWNER.may_load(deps.storage)?;
+
+    Ok(PendingOwnerResponse { pending_owner })
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct ContractOwnerResponse {
     pub owner: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PendingOwnerResponse {
+    pub pending_owner: Option<PendingOwner>,
+}
+
 #[cfg(test)]
 mod tests {
-    use cosmwasm_std::testing::{mock_dependencies, mock_info};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
 
     use super::*;
 
     #[test]
-    fn test_execute_update_owner() {
+    fn test_execute_propose_and_accept_owner() {
         let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
         let owner = String::from("owner");
         let new_owner = String::from("newowner");
 
@@ -68,25 +173,177 @@ mod tests {
 
         let unauth_info = mock_info("anyone", &[]);
 
-        let resp = execute_update_owner(deps.as_mut(), unauth_info.clone(), String::from("anyone"))
-            .unwrap_err();
-        let expected = StdError::generic_err(
-            "Ownership of this contract can only be transferred by the current owner",
-        );
+        let resp = execute_propose_owner(
+            deps.as_mut(),
+            env.clone(),
+            unauth_info.clone(),
+            String::from("anyone"),
+            None,
+        )
+        .unwrap_err();
+        let expected = ContractError::OwnershipTransferUnauthorized {};
         assert_eq!(resp, expected);
 
         let auth_info = mock_info(owner.as_str(), &[]);
 
-        let resp =
-            execute_update_owner(deps.as_mut(), auth_info.clone(), new_owner.clone()).unwrap();
+        let resp = execute_propose_owner(
+            deps.as_mut(),
+            env.clone(),
+            auth_info.clone(),
+            new_owner.clone(),
+            None,
+        )
+        .unwrap();
+        let expected = Response::new().add_attributes(vec![
+            attr("action", "propose_owner"),
+            attr("value", new_owner.clone()),
+            attr("expiration", "none"),
+        ]);
+        assert_eq!(resp, expected);
+
+        // Ownership does not change until the proposal is accepted
+        let query_resp = query_contract_owner(deps.as_ref()).unwrap();
+        assert_eq!(query_resp.owner, owner);
+        let query_resp = query_pending_owner(deps.as_ref()).unwrap();
+        assert_eq!(
+            query_resp.pending_owner,
+            Some(PendingOwner {
+                address: new_owner.clone(),
+                expiration: None
+            })
+        );
+
+        let resp = execute_accept_ownership(deps.as_mut(), env.clone(), unauth_info).unwrap_err();
+        assert_eq!(resp, ContractError::NotPendingOwner {});
+
+        let resp = execute_accept_ownership(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(new_owner.as_str(), &[]),
+        )
+        .unwrap();
         let expected = Response::new().add_attributes(vec![
-            attr("action", "update_owner"),
+            attr("action", "accept_ownership"),
             attr("value", new_owner.clone()),
         ]);
         assert_eq!(resp, expected);
 
         let query_resp = query_contract_owner(deps.as_ref()).unwrap();
+        assert_eq!(query_resp.owner, new_owner);
+        let query_resp = query_pending_owner(deps.as_ref()).unwrap();
+        assert_eq!(query_resp.pending_owner, None);
+
+        // The previous owner has no remaining rights
+        let resp = execute_propose_owner(deps.as_mut(), env, auth_info, owner, None).unwrap_err();
+        assert_eq!(resp, ContractError::OwnershipTransferUnauthorized {});
+    }
+
+    #[test]
+    fn test_ownership_proposal_expiration() {
+        let mut deps = mock_dependencies(&[]);
+        let mut env = mock_env();
+        let owner_info = mock_info("owner", &[]);
+        let new_owner_info = mock_info("newowner", &[]);
+
+        CONTRACT_OWNER
+            .save(deps.as_mut().storage, &String::from("owner"))
+            .unwrap();
+
+        let resp = execute_propose_owner(
+            deps.as_mut(),
+            env.clone(),
+            owner_info.clone(),
+            String::from("newowner"),
+            Some(Expiration::AtHeight(env.block.height)),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::ExpirationInPast {});
+
+        execute_propose_owner(
+            deps.as_mut(),
+            env.clone(),
+            owner_info,
+            String::from("newowner"),
+            Some(Expiration::AtHeight(env.block.height + 10)),
+        )
+        .unwrap();
+
+        env.block.height += 10;
+        let resp = execute_accept_ownership(deps.as_mut(), env, new_owner_info).unwrap_err();
+        assert_eq!(resp, ContractError::OwnershipProposalExpired {});
+    }
+
+    #[test]
+    fn test_cancel_ownership_proposal() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let owner_info = mock_info("owner", &[]);
+
+        CONTRACT_OWNER
+            .save(deps.as_mut().storage, &String::from("owner"))
+            .unwrap();
+
+        let resp =
+            execute_cancel_ownership_proposal(deps.as_mut(), owner_info.clone()).unwrap_err();
+        assert_eq!(resp, ContractError::NoPendingOwner {});
+
+        execute_propose_owner(
+            deps.as_mut(),
+            env.clone(),
+            owner_info.clone(),
+            String::from("newowner"),
+            None,
+        )
+        .unwrap();
+
+        let resp =
+            execute_cancel_ownership_proposal(deps.as_mut(), mock_info("anyone", &[])).unwrap_err();
+        assert_eq!(resp, ContractError::OwnershipTransferUnauthorized {});
+
+        let resp = execute_cancel_ownership_proposal(deps.as_mut(), owner_info).unwrap();
+        assert_eq!(
+            resp,
+            Response::new().add_attributes(vec![attr("action", "cancel_ownership_proposal")])
+        );
+
+        let resp =
+            execute_accept_ownership(deps.as_mut(), env, mock_info("newowner", &[])).unwrap_err();
+        assert_eq!(resp, ContractError::NoPendingOwner {});
+    }
 
-        assert_eq!(query_resp.owner, new_owner)
+    #[test]
+    fn test_renounce_ownership() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        let owner_info = mock_info("owner", &[]);
+
+        CONTRACT_OWNER
+            .save(deps.as_mut().storage, &String::from("owner"))
+            .unwrap();
+        execute_propose_owner(
+            deps.as_mut(),
+            env.clone(),
+            owner_info.clone(),
+            String::from("newowner"),
+            None,
+        )
+        .unwrap();
+
+        let resp = execute_renounce_ownership(deps.as_mut(), mock_info("anyone", &[])).unwrap_err();
+        assert_eq!(resp, ContractError::OwnershipTransferUnauthorized {});
+
+        let resp = execute_renounce_ownership(deps.as_mut(), owner_info.clone()).unwrap();
+        assert_eq!(
+            resp,
+            Response::new().add_attributes(vec![attr("action", "renounce_ownership")])
+        );
+
+        assert!(!is_contract_owner(deps.as_ref().storage, String::from("owner")).unwrap());
+        assert_eq!(query_contract_owner(deps.as_ref()).unwrap().owner, "");
+
+        // The pending proposal is discarded along with ownership
+        let resp =
+            execute_accept_ownership(deps.as_mut(), env, mock_info("newowner", &[])).unwrap_err();
+        assert_eq!(resp, ContractError::NoPendingOwner {});
     }
 }
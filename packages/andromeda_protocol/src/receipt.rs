@@ -1,13 +1,12 @@
 use cosmwasm_std::{Event, Uint128};
+use cw721::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
-    /// The address authorized to mint new receipts
+    /// The address authorized to mint new receipts. Moderators authorized to update receipts hold the `moderator` role.
     pub minter: String,
-    /// A list of moderating addresses authorized to update receipts
-    pub moderators: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -35,11 +34,29 @@ pub enum ExecuteMsg {
         receipt_id: Uint128,
         receipt: Receipt,
     },
-    /// Update ownership of the contract. Only executable by the current contract owner.
-    UpdateOwner {
-        /// The address of the new contract owner.
+    /// Propose a new owner of the contract, replacing any pending proposal. Only executable by the current contract owner.
+    ProposeOwner {
+        /// The address of the proposed contract owner.
+        address: String,
+        /// When the proposal lapses if it has not been accepted.
+        expiration: Option<Expiration>,
+    },
+    /// Accept a pending ownership proposal. Only executable by the proposed owner.
+    AcceptOwnership {},
+    /// Withdraw a pending ownership proposal. Only executable by the current contract owner.
+    CancelOwnershipProposal {},
+    /// Permanently give up ownership of the contract. Only executable by the current contract owner.
+    RenounceOwnership {},
+    /// Grant a role to an address, optionally until an expiration. Only executable by the current contract owner.
+    GrantRole {
+        /// The name of the role, such as "moderator", "minter" or "operator".
+        role: String,
         address: String,
+        /// When the role lapses. Defaults to never.
+        expiration: Option<Expiration>,
     },
+    /// Revoke a role from an address. Only executable by the current contract owner.
+    RevokeRole { role: String, address: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -51,8 +68,23 @@ pub enum QueryMsg {
     ContractInfo {},
     /// The current contract owner.
     ContractOwner {},
+    /// The pending ownership proposal, if any
+    PendingOwner {},
+    /// Whether an address currently holds a role
+    HasRole { role: String, address: String },
+    /// The addresses currently holding a role
+    RoleMembers {
+        role: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// The stored contract name and version
+    Version {},
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct ContractInfoResponse {
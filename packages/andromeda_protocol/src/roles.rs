@@ -0,0 +1,385 @@
+use crate::error::ContractError;
+use cosmwasm_std::{
+    attr, BlockInfo, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, Storage,
+};
+use cw721::Expiration;
+use cw_storage_plus::{Bound, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{ownership::is_contract_owner, require};
+
+/// May moderate the contents of a contract, such as the addresses in an address list or stored receipts
+pub const ROLE_MODERATOR: &str = "moderator";
+/// May mint on behalf of the contract
+pub const ROLE_MINTER: &str = "minter";
+/// May update the configuration of a contract alongside the contract owner
+pub const ROLE_OPERATOR: &str = "operator";
+
+/// Role grants keyed by role name and then address, mapped to when the grant lapses
+pub const ROLES: Map<(String, String), Expiration> = Map::new("roles");
+
+const MAX_ROLE_NAME_LENGTH: usize = 32;
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RoleMember {
+    pub address: String,
+    /// When the role lapses, `Never` if it does not expire
+    pub expiration: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct HasRoleResponse {
+    pub has_role: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RoleMembersResponse {
+    pub members: Vec<RoleMember>,
+}
+
+/// Role names must be between 1 and 32 characters and consist of lowercase letters, digits and underscores.
+fn validate_role_name(role: &str) -> Result<bool, ContractError> {
+    require(
+        !role.is_empty()
+            && role.len() <= MAX_ROLE_NAME_LENGTH
+            && role
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'),
+        ContractError::InvalidRoleName {},
+    )
+}
+
+/// Grants a role to an address, replacing any existing grant of the same role. Without an expiration the role never lapses.
+pub fn grant_role(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    role: &str,
+    address: &str,
+    expiration: Option<Expiration>,
+) -> Result<(), ContractError> {
+    validate_role_name(role)?;
+    let expiration = expiration.unwrap_or_default();
+    require(
+        !expiration.is_expired(block),
+        ContractError::ExpirationInPast {},
+    )?;
+
+    Ok(ROLES.save(
+        storage,
+        (role.to_string(), address.to_string()),
+        &expiration,
+    )?)
+}
+
+/// Revokes a role from an address. Errors if the address was never granted the role.
+pub fn revoke_role(
+    storage: &mut dyn Storage,
+    role: &str,
+    address: &str,
+) -> Result<(), ContractError> {
+    let key = (role.to_string(), address.to_string());
+    require(
+        ROLES.may_load(storage, key.clone())?.is_some(),
+        ContractError::RoleNotHeld {},
+    )?;
+    ROLES.remove(storage, key);
+
+    Ok(())
+}
+
+/// Whether an address currently holds a role. Expired grants are not counted.
+pub fn has_role(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    role: &str,
+    address: &str,
+) -> Result<bool, ContractError> {
+    let expiration = ROLES.may_load(storage, (role.to_string(), address.to_string()))?;
+
+    Ok(matches!(expiration, Some(expiration) if !expiration.is_expired(block)))
+}
+
+/// Whether an address is the contract owner or currently holds the operator role.
+pub fn is_owner_or_operator(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    address: &str,
+) -> Result<bool, ContractError> {
+    Ok(is_contract_owner(storage, address.to_string())?
+        || has_role(storage, block, ROLE_OPERATOR, address)?)
+}
+
+/// Lists the addresses currently holding a role, in ascending order.
+pub fn role_members(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    role: &str,
+    start_after: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<RoleMember>, ContractError> {
+    let start = start_after.map(Bound::exclusive);
+    let members = ROLES
+        .prefix(role.to_string())
+        .range(storage, start, None, Order::Ascending)
+        .filter(|r| r.is_err() || !r.as_ref().unwrap().1.is_expired(block))
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|r| {
+            let (k, expiration) = r?;
+            Ok(RoleMember {
+                address: String::from_utf8(k).map_err(StdError::from)?,
+                expiration,
+            })
+        })
+        .collect::<Result<Vec<RoleMember>, ContractError>>()?;
+
+    Ok(members)
+}
+
+/// Grants a role to an address. **Only executable by the contract owner.**
+pub fn execute_grant_role(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    role: String,
+    address: String,
+    expiration: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    require(
+        is_contract_owner(deps.storage, info.sender.to_string())?,
+        ContractError::Unauthorized {},
+    )?;
+    let address = deps.api.addr_validate(&address)?.to_string();
+    grant_role(deps.storage, &env.block, &role, &address, expiration)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "grant_role"),
+        attr("role", role),
+        attr("address", address),
+        attr("expiration", expiration.unwrap_or_default().to_string()),
+    ]))
+}
+
+/// Revokes a role from an address. **Only executable by the contract owner.**
+pub fn execute_revoke_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: String,
+    address: String,
+) -> Result<Response, ContractError> {
+    require(
+        is_contract_owner(deps.storage, info.sender.to_string())?,
+        ContractError::Unauthorized {},
+    )?;
+    revoke_role(deps.storage, &role, &address)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "revoke_role"),
+        attr("role", role),
+        attr("address", address),
+    ]))
+}
+
+pub fn query_has_role(
+    deps: Deps,
+    env: Env,
+    role: String,
+    address: String,
+) -> Result<HasRoleResponse, ContractError> {
+    Ok(HasRoleResponse {
+        has_role: has_role(deps.storage, &env.block, &role, &address)?,
+    })
+}
+
+pub fn query_role_members(
+    deps: Deps,
+    env: Env,
+    role: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<RoleMembersResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    Ok(RoleMembersResponse {
+        members: role_members(deps.storage, &env.block, &role, start_after, Some(limit))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+
+    use super::*;
+    use crate::ownership::CONTRACT_OWNER;
+
+    #[test]
+    fn test_grant_and_revoke_role() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+        CONTRACT_OWNER
+            .save(deps.as_mut().storage, &String::from("owner"))
+            .unwrap();
+
+        let resp = execute_grant_role(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            ROLE_MODERATOR.to_string(),
+            String::from("moderator"),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::Unauthorized {});
+
+        let resp = execute_grant_role(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            ROLE_MODERATOR.to_string(),
+            String::from("moderator"),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            resp,
+            Response::new().add_attributes(vec![
+                attr("action", "grant_role"),
+                attr("role", ROLE_MODERATOR),
+                attr("address", "moderator"),
+                attr("expiration", "expiration: never"),
+            ])
+        );
+
+        let resp = query_has_role(
+            deps.as_ref(),
+            env.clone(),
+            ROLE_MODERATOR.to_string(),
+            String::from("moderator"),
+        )
+        .unwrap();
+        assert!(resp.has_role);
+        // Roles are independent of one another
+        assert!(!has_role(&deps.storage, &env.block, ROLE_MINTER, "moderator").unwrap());
+
+        let resp = execute_revoke_role(
+            deps.as_mut(),
+            mock_info("anyone", &[]),
+            ROLE_MODERATOR.to_string(),
+            String::from("moderator"),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::Unauthorized {});
+
+        execute_revoke_role(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            ROLE_MODERATOR.to_string(),
+            String::from("moderator"),
+        )
+        .unwrap();
+        assert!(!has_role(&deps.storage, &env.block, ROLE_MODERATOR, "moderator").unwrap());
+
+        let resp = execute_revoke_role(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            ROLE_MODERATOR.to_string(),
+            String::from("moderator"),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::RoleNotHeld {});
+    }
+
+    #[test]
+    fn test_role_expiration() {
+        let mut deps = mock_dependencies(&[]);
+        let mut env = mock_env();
+
+        let resp = grant_role(
+            &mut deps.storage,
+            &env.block,
+            ROLE_OPERATOR,
+            "operator",
+            Some(Expiration::AtHeight(env.block.height)),
+        )
+        .unwrap_err();
+        assert_eq!(resp, ContractError::ExpirationInPast {});
+
+        grant_role(
+            &mut deps.storage,
+            &env.block,
+            ROLE_OPERATOR,
+            "operator",
+            Some(Expiration::AtHeight(env.block.height + 10)),
+        )
+        .unwrap();
+        assert!(has_role(&deps.storage, &env.block, ROLE_OPERATOR, "operator").unwrap());
+
+        env.block.height += 10;
+        assert!(!has_role(&deps.storage, &env.block, ROLE_OPERATOR, "operator").unwrap());
+        let members =
+            query_role_members(deps.as_ref(), env, ROLE_OPERATOR.to_string(), None, None).unwrap();
+        assert!(members.members.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_role_name() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+
+        for role in ["", "Moderator", "role name", "r".repeat(33).as_str()] {
+            let resp =
+                grant_role(&mut deps.storage, &env.block, role, "address", None).unwrap_err();
+            assert_eq!(resp, ContractError::InvalidRoleName {});
+        }
+    }
+
+    #[test]
+    fn test_query_role_members() {
+        let mut deps = mock_dependencies(&[]);
+        let env = mock_env();
+
+        for address in ["minter3", "minter1", "minter2"] {
+            grant_role(&mut deps.storage, &env.block, ROLE_MINTER, address, None).unwrap();
+        }
+        grant_role(
+            &mut deps.storage,
+            &env.block,
+            ROLE_MODERATOR,
+            "moderator",
+            None,
+        )
+        .unwrap();
+
+        let resp = query_role_members(
+            deps.as_ref(),
+            env.clone(),
+            ROLE_MINTER.to_string(),
+            None,
+            Some(2),
+        )
+        .unwrap();
+        let addresses: Vec<String> = resp.members.into_iter().map(|m| m.address).collect();
+        assert_eq!(addresses, vec!["minter1", "minter2"]);
+
+        let resp = query_role_members(
+            deps.as_ref(),
+            env,
+            ROLE_MINTER.to_string(),
+            Some(String::from("minter2")),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            resp.members,
+            vec![RoleMember {
+                address: String::from("minter3"),
+                expiration: Expiration::Never {},
+            }]
+        );
+    }
+}
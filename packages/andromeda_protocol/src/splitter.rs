@@ -1,12 +1,73 @@
+use crate::error::ContractError;
+use crate::modules::common::Funds;
 use crate::{modules::address_list::AddressListModule, require};
-use cosmwasm_std::{StdError, StdResult, Uint128};
+use cosmwasm_std::{Api, Binary, BlockInfo, Uint128};
+use cw20::Cw20ReceiveMsg;
+use cw721::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct AddressPercent {
     pub addr: String,
     pub percent: Uint128,
+    /// An optional `ExecuteMsg` executed on the recipient contract with the recipient's share of native funds attached, e.g. a timelock `HoldFunds`.
+    pub msg: Option<Binary>,
+    /// An optional message sent to the recipient contract's `Receive` handler with its share of CW20 tokens using `Cw20ExecuteMsg::Send`.
+    ///
+    /// Recipients with either message are contracts and are always paid directly, as a contract cannot be expected to withdraw its share.
+    /// Splitting funds to such a recipient errors if it has no message for the kind of funds being split.
+    pub cw20_msg: Option<Binary>,
+}
+
+impl AddressPercent {
+    /// Whether the recipient is a contract expecting a message with its share
+    pub fn is_contract(&self) -> bool {
+        self.msg.is_some() || self.cw20_msg.is_some()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// An enum used to define what happens to any funds a `Send` does not allocate to a recipient.
+/// This includes the share left over when recipient percentages total under 100 and any rounding dust.
+pub enum RemainderPolicy {
+    /// Returns the remainder to the sender of the funds
+    #[default]
+    RefundSender,
+    /// Sends the remainder to the given address
+    Address { address: String },
+    /// Credits the remainder to the contract owner's balance, to be claimed with `Withdraw`
+    KeepForOwner,
+    /// Adds the remainder to the share of the first recipient. Requires recipient percentages to total 100.
+    DustToFirst,
+    /// Adds the remainder to the share of the recipient with the largest percentage. Requires recipient percentages to total 100.
+    DustToLargest,
+}
+
+impl RemainderPolicy {
+    /// Validates the address of a `RemainderPolicy::Address` so that remainders are never sent to an invalid address
+    pub fn validate_address(&self, api: &dyn Api) -> Result<bool, ContractError> {
+        if let RemainderPolicy::Address { address } = self {
+            api.addr_validate(address)
+                .map_err(|_| ContractError::InvalidRemainderAddress {})?;
+        }
+
+        Ok(true)
+    }
+}
+
+impl fmt::Display for RemainderPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RemainderPolicy::RefundSender => write!(f, "refund_sender"),
+            RemainderPolicy::Address { address } => write!(f, "address:{}", address),
+            RemainderPolicy::KeepForOwner => write!(f, "keep_for_owner"),
+            RemainderPolicy::DustToFirst => write!(f, "dust_to_first"),
+            RemainderPolicy::DustToLargest => write!(f, "dust_to_largest"),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -16,8 +77,17 @@ pub struct Splitter {
     pub recipients: Vec<AddressPercent>,
     /// Whether or not the contract is currently locked. This restricts updating any config related fields.
     pub locked: bool,
+    /// When the lock lapses, if it was set with an expiration. A lock without an expiration lasts until it is removed.
+    #[serde(default)]
+    pub lock_expiration: Option<Expiration>,
     /// An optional address list to restrict access to the `Splitter` contract.     
     pub address_list: Option<AddressListModule>,
+    /// Whether `Send` credits each recipient's balance in the contract instead of sending funds directly. Credited funds are claimed with `Withdraw`.
+    #[serde(default)]
+    pub pull_payments: bool,
+    /// What happens to any funds not allocated to a recipient by a `Send`.
+    #[serde(default)]
+    pub remainder: RemainderPolicy,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -26,11 +96,26 @@ pub struct InstantiateMsg {
     pub recipients: Vec<AddressPercent>,
     /// An optional address list to restrict access to the `Splitter` contract.    
     pub address_list: Option<AddressListModule>,
+    /// Whether recipients must withdraw their share rather than being sent it directly. Defaults to false.
+    pub pull_payments: Option<bool>,
+    /// What happens to any funds not allocated to a recipient. Defaults to refunding the sender.
+    pub remainder: Option<RemainderPolicy>,
+}
+
+impl Splitter {
+    /// Whether the splitter is locked at the given block. Locks set with an expiration lapse once it is reached.
+    pub fn is_locked(&self, block: &BlockInfo) -> bool {
+        self.locked
+            && !matches!(&self.lock_expiration, Some(expiration) if expiration.is_expired(block))
+    }
 }
 
 impl InstantiateMsg {
-    pub fn validate(&self) -> StdResult<bool> {
-        validate_recipient_list(self.recipients.clone())?;
+    pub fn validate(&self) -> Result<bool, ContractError> {
+        validate_recipient_list(
+            self.recipients.clone(),
+            &self.remainder.clone().unwrap_or_default(),
+        )?;
         Ok(true)
     }
 }
@@ -38,21 +123,60 @@ impl InstantiateMsg {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    /// Update the recipients list. Only executable by the contract owner when the contract is not locked.
+    /// Update the recipients list. Only executable by the contract owner or an operator when the contract is not locked.
     UpdateRecipients { recipients: Vec<AddressPercent> },
-    /// Used to lock/unlock the contract allowing the config to be updated.
-    UpdateLock { lock: bool },
-    /// Update the optional address list module. Only executable by the contract owner when the contract is not locked.
+    /// Update the remainder policy. Only executable by the contract owner or an operator.
+    UpdateRemainder { remainder: RemainderPolicy },
+    /// Used to lock/unlock the contract allowing the config to be updated. Only executable by the contract owner or an operator.
+    /// A lock set with an expiration cannot be removed or replaced until it expires.
+    UpdateLock {
+        lock: bool,
+        /// When the lock lapses. Defaults to never, in which case the lock lasts until it is removed.
+        expiration: Option<Expiration>,
+    },
+    /// Update the optional address list module. Only executable by the contract owner or an operator when the contract is not locked.
     UpdateAddressList {
         address_list: Option<AddressListModule>,
     },
+    /// Switch between sending shares directly and crediting recipient balances. Only executable by the contract owner or an operator.
+    UpdatePullPayments { pull_payments: bool },
     /// Divides any attached funds to the message amongst the recipients list.
     Send {},
-    /// Update ownership of the contract. Only executable by the current contract owner.
-    UpdateOwner {
-        /// The address of the new contract owner.
+    /// Divides CW20 tokens sent to the contract amongst the recipients list, the attached message must be a `Cw20HookMsg`
+    Receive(Cw20ReceiveMsg),
+    /// Withdraw the sender's credited balance of the given denoms, or of every denom if none are given.
+    Withdraw { denoms: Option<Vec<String>> },
+    /// Propose a new owner of the contract, replacing any pending proposal. Only executable by the current contract owner.
+    ProposeOwner {
+        /// The address of the proposed contract owner.
         address: String,
+        /// When the proposal lapses if it has not been accepted.
+        expiration: Option<Expiration>,
     },
+    /// Accept a pending ownership proposal. Only executable by the proposed owner.
+    AcceptOwnership {},
+    /// Withdraw a pending ownership proposal. Only executable by the current contract owner.
+    CancelOwnershipProposal {},
+    /// Permanently give up ownership of the contract. Only executable by the current contract owner.
+    RenounceOwnership {},
+    /// Grant a role to an address, optionally until an expiration. Only executable by the current contract owner.
+    GrantRole {
+        /// The name of the role, such as "moderator", "minter" or "operator".
+        role: String,
+        address: String,
+        /// When the role lapses. Defaults to never.
+        expiration: Option<Expiration>,
+    },
+    /// Revoke a role from an address. Only executable by the current contract owner.
+    RevokeRole { role: String, address: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// Messages that may be attached to CW20 tokens sent to the contract
+pub enum Cw20HookMsg {
+    /// Divides the sent CW20 tokens amongst the recipients list
+    Send {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -62,8 +186,32 @@ pub enum QueryMsg {
     GetSplitterConfig {},
     /// The current contract owner.
     ContractOwner {},
+    /// The pending ownership proposal, if any
+    PendingOwner {},
+    /// Whether an address currently holds a role
+    HasRole { role: String, address: String },
+    /// The addresses currently holding a role
+    RoleMembers {
+        role: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Whether the splitter is currently locked and until when
+    LockStatus {},
+    /// The stored contract name and version
+    Version {},
+    /// The balance credited to an address and available to withdraw
+    Balance { address: String },
+    /// The balances credited to all addresses (paginated by address)
+    Balances {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 pub struct GetSplitterConfigResponse {
     pub config: Splitter,
@@ -71,15 +219,35 @@ pub struct GetSplitterConfigResponse {
     pub address_list_contract: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockStatusResponse {
+    pub locked: bool,
+    /// When the current lock lapses, `None` if unlocked or locked until removed
+    pub expiration: Option<Expiration>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BalanceResponse {
+    pub address: String,
+    pub balance: Vec<Funds>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BalancesResponse {
+    pub balances: Vec<BalanceResponse>,
+}
+
 /// Ensures that a given list of recipients for a `splitter` contract is valid:
 ///
 /// * Must include at least one recipient
 /// * The combined percentage of the recipients must not exceed 100
-pub fn validate_recipient_list(recipients: Vec<AddressPercent>) -> StdResult<bool> {
-    require(
-        recipients.len() > 0,
-        StdError::generic_err("The recipients list must include at least one recipient"),
-    )?;
+/// * The combined percentage must equal 100 if the remainder policy distributes dust to a recipient
+/// * The remainder address must not be empty if the remainder is sent to an address
+pub fn validate_recipient_list(
+    recipients: Vec<AddressPercent>,
+    remainder: &RemainderPolicy,
+) -> Result<bool, ContractError> {
+    require(recipients.len() > 0, ContractError::EmptyRecipientsList {})?;
 
     let mut percent_sum: Uint128 = Uint128::from(0_u128);
     for rec in recipients {
@@ -88,9 +256,21 @@ pub fn validate_recipient_list(recipients: Vec<AddressPercent>) -> StdResult<boo
 
     require(
         percent_sum <= Uint128::from(100u128),
-        StdError::generic_err("The amount received by the recipients should not exceed 100%"),
+        ContractError::InvalidRecipientPercent {},
     )?;
 
+    match remainder {
+        RemainderPolicy::DustToFirst | RemainderPolicy::DustToLargest => require(
+            percent_sum == Uint128::from(100u128),
+            ContractError::IncompleteSplitForDustPolicy {},
+        )?,
+        RemainderPolicy::Address { address } => require(
+            !address.is_empty(),
+            ContractError::InvalidRemainderAddress {},
+        )?,
+        RemainderPolicy::RefundSender | RemainderPolicy::KeepForOwner => true,
+    };
+
     Ok(true)
 }
 
@@ -101,34 +281,59 @@ mod tests {
     #[test]
     fn test_validate_recipient_list() {
         let empty_recipients = vec![];
-        let res = validate_recipient_list(empty_recipients).unwrap_err();
-        assert_eq!(
-            res,
-            StdError::generic_err("The recipients list must include at least one recipient")
-        );
+        let res =
+            validate_recipient_list(empty_recipients, &RemainderPolicy::RefundSender).unwrap_err();
+        assert_eq!(res, ContractError::EmptyRecipientsList {});
 
         let inadequate_recipients = vec![AddressPercent {
             addr: String::from("some address"),
             percent: Uint128::from(150_u128),
+            msg: None,
+            cw20_msg: None,
         }];
-        let res = validate_recipient_list(inadequate_recipients).unwrap_err();
-        assert_eq!(
-            res,
-            StdError::generic_err("The amount received by the recipients should not exceed 100%")
-        );
+        let res = validate_recipient_list(inadequate_recipients, &RemainderPolicy::RefundSender)
+            .unwrap_err();
+        assert_eq!(res, ContractError::InvalidRecipientPercent {});
 
         let valid_recipients = vec![
             AddressPercent {
                 addr: String::from("some address"),
                 percent: Uint128::from(50_u128),
+                msg: None,
+                cw20_msg: None,
             },
             AddressPercent {
                 addr: String::from("some address"),
                 percent: Uint128::from(50_u128),
+                msg: None,
+                cw20_msg: None,
             },
         ];
 
-        let res = validate_recipient_list(valid_recipients).unwrap();
+        let res =
+            validate_recipient_list(valid_recipients.clone(), &RemainderPolicy::DustToLargest)
+                .unwrap();
         assert_eq!(true, res);
+
+        let partial_recipients = vec![AddressPercent {
+            addr: String::from("some address"),
+            percent: Uint128::from(50_u128),
+            msg: None,
+            cw20_msg: None,
+        }];
+        let res =
+            validate_recipient_list(partial_recipients.clone(), &RemainderPolicy::DustToFirst)
+                .unwrap_err();
+        assert_eq!(res, ContractError::IncompleteSplitForDustPolicy {});
+        validate_recipient_list(partial_recipients, &RemainderPolicy::KeepForOwner).unwrap();
+
+        let res = validate_recipient_list(
+            valid_recipients,
+            &RemainderPolicy::Address {
+                address: String::new(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::InvalidRemainderAddress {});
     }
 }
@@ -1,6 +1,10 @@
-use crate::{address_list::IncludesAddressResponse, ownership::ContractOwnerResponse};
+use crate::{
+    address_list::{IncludesAddressResponse, QueryMsg as AddressListQueryMsg},
+    modules::custom::{CustomHookMsg, CustomHookResponse, CustomPayment, HookMsg},
+    ownership::ContractOwnerResponse,
+};
 use cosmwasm_std::{
-    from_slice,
+    from_binary, from_slice,
     testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR},
     to_binary, Addr, Coin, ContractResult, OwnedDeps, Querier, QuerierResult, QueryRequest,
     SystemError, SystemResult, WasmQuery,
@@ -43,27 +47,57 @@ impl Querier for WasmMockQuerier {
 impl WasmMockQuerier {
     pub fn handle_query(&self, request: &QueryRequest<TerraQueryWrapper>) -> QuerierResult {
         match &request {
-            QueryRequest::Wasm(WasmQuery::Smart {
-                contract_addr,
-                msg: _,
-            }) => {
-                if contract_addr == &Addr::unchecked("addresslist_contract_address1") {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                if contract_addr == &Addr::unchecked("custom_contract_address") {
+                    let msg_response = self.handle_custom_hook(from_binary(msg).unwrap());
+                    SystemResult::Ok(ContractResult::Ok(to_binary(&msg_response).unwrap()))
+                } else if contract_addr == &Addr::unchecked("addresslist_contract_address1") {
                     let msg_response = IncludesAddressResponse { included: true };
-                    return SystemResult::Ok(ContractResult::Ok(to_binary(&msg_response).unwrap()));
+                    SystemResult::Ok(ContractResult::Ok(to_binary(&msg_response).unwrap()))
+                } else if contract_addr == &Addr::unchecked("addresslist_contract_address2") {
+                    // Only includes "purchaser"
+                    let included = match from_binary(msg).unwrap() {
+                        AddressListQueryMsg::IncludesAddress { address } => address == "purchaser",
+                        _ => false,
+                    };
+                    let msg_response = IncludesAddressResponse { included };
+                    SystemResult::Ok(ContractResult::Ok(to_binary(&msg_response).unwrap()))
                 } else if contract_addr == &Addr::unchecked("factory_address") {
                     let msg_response = ContractOwnerResponse {
                         owner: String::from("creator"),
                     };
-                    return SystemResult::Ok(ContractResult::Ok(to_binary(&msg_response).unwrap()));
+                    SystemResult::Ok(ContractResult::Ok(to_binary(&msg_response).unwrap()))
                 } else {
                     let msg_response = IncludesAddressResponse { included: false };
-                    return SystemResult::Ok(ContractResult::Ok(to_binary(&msg_response).unwrap()));
+                    SystemResult::Ok(ContractResult::Ok(to_binary(&msg_response).unwrap()))
                 }
             }
             _ => self.base.handle_query(request),
         }
     }
 
+    /// Rejects transfers to "rejected_recipient" and appends a 10% payment to "custom_receiver" for agreed transfers
+    fn handle_custom_hook(&self, msg: CustomHookMsg) -> CustomHookResponse {
+        match msg {
+            CustomHookMsg::Hook(HookMsg::OnTransfer { recipient, .. })
+                if recipient == "rejected_recipient" =>
+            {
+                CustomHookResponse {
+                    rejection: Some(String::from("Recipient rejected by custom module")),
+                    payments: vec![],
+                }
+            }
+            CustomHookMsg::Hook(HookMsg::OnAgreedTransfer { amount, .. }) => CustomHookResponse {
+                rejection: None,
+                payments: vec![CustomPayment {
+                    receiver: String::from("custom_receiver"),
+                    amount: amount.amount().multiply_ratio(1u128, 10u128),
+                }],
+            },
+            _ => CustomHookResponse::default(),
+        }
+    }
+
     pub fn new(base: MockQuerier<TerraQueryWrapper>) -> Self {
         WasmMockQuerier { base }
     }
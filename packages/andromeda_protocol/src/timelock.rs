@@ -1,4 +1,5 @@
-use cosmwasm_std::{Api, BlockInfo, Coin, StdError, StdResult, Storage};
+use crate::error::ContractError;
+use cosmwasm_std::{Api, BlockInfo, Coin, StdResult, Storage};
 use cw721::Expiration;
 use cw_storage_plus::Map;
 use schemars::JsonSchema;
@@ -25,14 +26,11 @@ impl Escrow {
     /// * Escrowed funds cannot be empty
     /// * The Escrow recipient must be a valid address
     /// * Expiration cannot be "Never" or before current time/block
-    pub fn validate(self, api: &dyn Api, block: &BlockInfo) -> StdResult<bool> {
-        require(
-            self.coins.len() > 0,
-            StdError::generic_err("Cannot escrow empty funds"),
-        )?;
+    pub fn validate(self, api: &dyn Api, block: &BlockInfo) -> Result<bool, ContractError> {
+        require(self.coins.len() > 0, ContractError::EmptyEscrow {})?;
         require(
             api.addr_validate(&self.recipient.clone()).is_ok(),
-            StdError::generic_err("Escrow recipient must be a valid address"),
+            ContractError::InvalidEscrowRecipient {},
         )?;
 
         if self.expiration.is_some() {
@@ -40,13 +38,11 @@ impl Escrow {
                 //ACK-01 Change (Check before deleting comment)
                 Expiration::AtTime(time) => {
                     if time < block.time {
-                        return Err(StdError::generic_err("Cannot set expiration in the past"));
+                        return Err(ContractError::ExpirationInPast {});
                     }
                 }
                 Expiration::Never {} => {
-                    return Err(StdError::generic_err(
-                        "Cannot escrow funds with no expiration",
-                    ));
+                    return Err(ContractError::MissingEscrowExpiration {});
                 }
                 _ => {}
             }
@@ -70,17 +66,35 @@ pub enum ExecuteMsg {
         expiration: Option<Expiration>,
         recipient: Option<String>,
     },
-    /// Update the optional address list module
+    /// Update the optional address list module. Only executable by the contract owner or an operator.
     UpdateAddressList {
         address_list: Option<AddressListModule>,
     },
     /// Release funds held in Escrow
     ReleaseFunds {},
-    /// Update ownership of the contract. Only executable by the current contract owner.
-    UpdateOwner {
-        /// The address of the new contract owner.
+    /// Propose a new owner of the contract, replacing any pending proposal. Only executable by the current contract owner.
+    ProposeOwner {
+        /// The address of the proposed contract owner.
         address: String,
+        /// When the proposal lapses if it has not been accepted.
+        expiration: Option<Expiration>,
     },
+    /// Accept a pending ownership proposal. Only executable by the proposed owner.
+    AcceptOwnership {},
+    /// Withdraw a pending ownership proposal. Only executable by the current contract owner.
+    CancelOwnershipProposal {},
+    /// Permanently give up ownership of the contract. Only executable by the current contract owner.
+    RenounceOwnership {},
+    /// Grant a role to an address, optionally until an expiration. Only executable by the current contract owner.
+    GrantRole {
+        /// The name of the role, such as "moderator", "minter" or "operator".
+        role: String,
+        address: String,
+        /// When the role lapses. Defaults to never.
+        expiration: Option<Expiration>,
+    },
+    /// Revoke a role from an address. Only executable by the current contract owner.
+    RevokeRole { role: String, address: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -92,8 +106,23 @@ pub enum QueryMsg {
     GetTimelockConfig {},
     /// The current owner of the contract
     ContractOwner {},
+    /// The pending ownership proposal, if any
+    PendingOwner {},
+    /// Whether an address currently holds a role
+    HasRole { role: String, address: String },
+    /// The addresses currently holding a role
+    RoleMembers {
+        role: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// The stored contract name and version
+    Version {},
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct GetLockedFundsResponse {
@@ -108,23 +137,27 @@ pub struct GetTimelockConfigResponse {
 }
 
 /// Stores an Escrow struct for a given address. Used to store funds from an address.
-pub fn hold_funds(funds: Escrow, storage: &mut dyn Storage, addr: String) -> StdResult<()> {
+pub fn hold_funds(
+    funds: Escrow,
+    storage: &mut dyn Storage,
+    addr: String,
+) -> Result<(), ContractError> {
     require(
         // Makes sure that HELD_FUNDS is empty before allowing writing into HELD_FUNDS.
         //Decided to use unwrap instead of unwrap_or_else (correct me if wrong)
         HELD_FUNDS.may_load(storage, addr.clone()).unwrap() == None,
-        StdError::generic_err("Cannot overwrite Held Funds"),
+        ContractError::FundsAlreadyHeld {},
     )?;
-    HELD_FUNDS.save(storage, addr, &funds)
+    Ok(HELD_FUNDS.save(storage, addr, &funds)?)
 }
 
 /// Removes the stored Escrow struct for a given address.
-pub fn release_funds(storage: &mut dyn Storage, addr: String) -> StdResult<()> {
+pub fn release_funds(storage: &mut dyn Storage, addr: String) -> Result<(), ContractError> {
     require(
         // Makes sure that HELD_FUNDS is NOT empty before allowing removing into HELD_FUNDS.
         //Decided to use unwrap instead of unwrap_or_else (correct me if wrong)
         HELD_FUNDS.may_load(storage, addr.clone()).unwrap() != None,
-        StdError::generic_err("Cannot overwrite Held Funds"),
+        ContractError::FundsAlreadyHeld {},
     )?;
     HELD_FUNDS.remove(storage, addr);
     Ok(())
@@ -184,10 +217,7 @@ mod tests {
         let resp = invalid_recipient_escrow
             .validate(deps.as_ref().api, &block)
             .unwrap_err();
-        assert_eq!(
-            StdError::generic_err("Escrow recipient must be a valid address"),
-            resp
-        );
+        assert_eq!(ContractError::InvalidEscrowRecipient {}, resp);
 
         let invalid_coins_escrow = Escrow {
             recipient: recipient.clone(),
@@ -198,7 +228,7 @@ mod tests {
         let resp = invalid_coins_escrow
             .validate(deps.as_ref().api, &block)
             .unwrap_err();
-        assert_eq!(StdError::generic_err("Cannot escrow empty funds"), resp);
+        assert_eq!(ContractError::EmptyEscrow {}, resp);
 
         let invalid_expiration_escrow = Escrow {
             recipient: recipient.clone(),
@@ -209,9 +239,6 @@ mod tests {
         let resp = invalid_expiration_escrow
             .validate(deps.as_ref().api, &block)
             .unwrap_err();
-        assert_eq!(
-            StdError::generic_err("Cannot escrow funds with no expiration"),
-            resp
-        );
+        assert_eq!(ContractError::MissingEscrowExpiration {}, resp);
     }
 }
@@ -1,11 +1,18 @@
+use crate::error::ContractError;
 use crate::modules::{
-    common::calculate_fee, read_modules, receipt::get_receipt_module, ModuleDefinition, Rate,
+    auction::{Auction, AuctionType},
+    common::{add_payment, calculate_payment_fee, deduct_payment, Funds, Payment},
+    hooks::HookResponse,
+    read_modules,
+    receipt::add_receipt_message,
+    royalties::Royalty,
+    ModuleDefinition, Rate, Rounding,
 };
 use crate::require;
 use cosmwasm_std::{
-    attr, Addr, BankMsg, Binary, BlockInfo, Coin, DepsMut, Env, Event, MessageInfo, Response,
-    StdError, StdResult, Uint128,
+    attr, Addr, Binary, BlockInfo, Coin, DepsMut, Env, Event, MessageInfo, Response, Uint128,
 };
+use cw20::Cw20ReceiveMsg;
 use cw721::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -96,6 +103,8 @@ pub struct Token {
     pub image: Option<String>,
     /// The current price listing for the token
     pub pricing: Option<Coin>,
+    /// A royalty applied to agreed transfers of the token in place of the contract's royalty module
+    pub royalty: Option<Royalty>,
 }
 
 impl Token {
@@ -113,26 +122,46 @@ impl Token {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 /// A struct used to represent an agreed transfer of a token. The `purchaser` may use the `Transfer` message for this token as long as funds are provided equalling the `amount` defined in the agreement.
 pub struct TransferAgreement {
-    /// The amount required for the purchaser to transfer ownership of the token
-    pub amount: Coin,
+    /// The amount required for the purchaser to transfer ownership of the token, in native funds or a CW20 token
+    pub amount: Funds,
     /// The address of the purchaser
     pub purchaser: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// An offer to purchase a token. The offered funds are held in escrow by the token contract until the offer is accepted or cancelled.
+pub struct Offer {
+    /// The address making the offer
+    pub purchaser: String,
+    /// The amount offered for the token
+    pub amount: Coin,
+    /// When the offer expires
+    pub expires: Expiration,
+}
+
+impl Offer {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires.is_expired(block)
+    }
+}
+
 impl TransferAgreement {
-    /// Generates a `BankMsg` for the amount defined in the transfer agreement to the provided address
-    pub fn generate_payment(&self, to_address: String) -> BankMsg {
-        BankMsg::Send {
-            to_address,
-            amount: vec![self.amount.clone()],
-        }
+    /// Generates a `Payment` for the amount defined in the transfer agreement to the provided address
+    pub fn generate_payment(&self, to_address: String) -> Payment {
+        let mut payments = vec![];
+        add_payment(&mut payments, to_address, self.amount.clone());
+        payments.remove(0)
     }
-    /// Generates a `BankMsg` for a given `Rate` to a given address
-    pub fn generate_fee_payment(&self, to_address: String, rate: Rate) -> BankMsg {
-        BankMsg::Send {
-            to_address,
-            amount: vec![calculate_fee(rate, self.amount.clone())],
-        }
+    /// Generates a `Payment` for a given `Rate` to a given address
+    pub fn generate_fee_payment(
+        &self,
+        to_address: String,
+        rate: Rate,
+    ) -> Result<Payment, ContractError> {
+        let fee = calculate_payment_fee(rate, self.amount.clone(), Rounding::Up)?;
+        let mut payments = vec![];
+        add_payment(&mut payments, to_address, fee);
+        Ok(payments.remove(0))
     }
     /// Generates an event related to the agreed transfer of a token
     pub fn generate_event(self) -> Event {
@@ -142,20 +171,103 @@ impl TransferAgreement {
         ])
     }
     /// Generates payment messages for any fees required by the current contracts Modules. Generates a receipt for the agreed transfer if the current contract contains a `Receipt` module.
+    /// A provided `royalty` is used in place of the contract's royalty module.
     pub fn on_transfer(
         self,
         deps: &DepsMut,
         info: &MessageInfo,
         env: &Env,
         owner: String,
+        royalty: Option<Royalty>,
         res_in: Response,
-    ) -> StdResult<Response> {
-        let mut res = res_in;
-        let modules = read_modules(deps.storage)?;
+    ) -> Result<Response, ContractError> {
+        let res = self.generate_transfer_response(deps, info, env, owner, royalty, res_in)?;
+
+        add_receipt_message(deps.storage, res)
+    }
+    /// Generates payment messages for any fees required by the current contracts Modules for an agreed transfer where the agreed amount is held in escrow by the contract.
+    /// As only the agreed amount is available, any fees usually paid by the purchaser are deducted from the owner's payment.
+    /// Errors if any payment is not in the escrowed denomination, as it could only be paid from other escrowed funds.
+    /// Generates a receipt for the agreed transfer if the current contract contains a `Receipt` module.
+    pub fn on_escrowed_transfer(
+        self,
+        deps: &DepsMut,
+        info: &MessageInfo,
+        env: &Env,
+        owner: String,
+        royalty: Option<Royalty>,
+        res_in: Response,
+    ) -> Result<Response, ContractError> {
+        let (mut payments, mod_resp) =
+            self.generate_payments(deps, info, env, owner.clone(), royalty)?;
+        require(
+            payments.iter().all(|p| p.is_denominated_in(&self.amount)),
+            ContractError::InvalidEscrowedPayment {},
+        )?;
+
+        let total: Uint128 = payments.iter().map(|p| p.amount_of(&self.amount)).sum();
+        if total > self.amount.amount() {
+            deduct_payment(
+                &mut payments,
+                owner,
+                self.amount.with_amount(total - self.amount.amount()),
+            )?;
+        }
+
+        let res = self.add_payments(res_in, payments, mod_resp)?;
+
+        add_receipt_message(deps.storage, res)
+    }
+    /// Generates payment messages and events for any fees required by the current contracts Modules without generating a receipt.
+    pub fn generate_transfer_response(
+        self,
+        deps: &DepsMut,
+        info: &MessageInfo,
+        env: &Env,
+        owner: String,
+        royalty: Option<Royalty>,
+        res_in: Response,
+    ) -> Result<Response, ContractError> {
+        let (payments, mod_resp) = self.generate_payments(deps, info, env, owner, royalty)?;
+
+        self.add_payments(res_in, payments, mod_resp)
+    }
+    /// Calculates the total amount the purchaser must provide for the agreed transfer, including any fees required by the current contracts Modules.
+    /// Errors if any payment is not in the agreed denomination, as the purchaser only provides the agreed funds.
+    pub fn total_payments(
+        &self,
+        deps: &DepsMut,
+        info: &MessageInfo,
+        env: &Env,
+        owner: String,
+        royalty: Option<Royalty>,
+    ) -> Result<Uint128, ContractError> {
+        let (payments, _) = self.generate_payments(deps, info, env, owner, royalty)?;
+        require(
+            payments.iter().all(|p| p.is_denominated_in(&self.amount)),
+            ContractError::InvalidAgreedPayment {},
+        )?;
+
+        Ok(payments.iter().map(|p| p.amount_of(&self.amount)).sum())
+    }
+    /// Generates the payment to the owner along with any payments required by the current contracts Modules.
+    /// A provided `royalty` replaces the contract's royalty module for this transfer.
+    fn generate_payments(
+        &self,
+        deps: &DepsMut,
+        info: &MessageInfo,
+        env: &Env,
+        owner: String,
+        royalty: Option<Royalty>,
+    ) -> Result<(Vec<Payment>, HookResponse), ContractError> {
+        let modules = match royalty {
+            Some(royalty) => read_modules(deps.storage)?.with_royalty(royalty),
+            None => read_modules(deps.storage)?,
+        };
         let payment_message = self.generate_payment(owner.clone());
         let mut payments = vec![payment_message];
         let mod_resp = modules.on_agreed_transfer(
-            &deps,
+            deps,
             info.clone(),
             env.clone(),
             &mut payments,
@@ -164,25 +276,26 @@ impl TransferAgreement {
             self.amount.clone(),
         )?;
 
+        Ok((payments, mod_resp))
+    }
+    /// Adds the given payments and any module messages and events to a response, followed by the agreed transfer event
+    fn add_payments(
+        self,
+        res_in: Response,
+        payments: Vec<Payment>,
+        mod_resp: HookResponse,
+    ) -> Result<Response, ContractError> {
+        let mut res = res_in;
         for payment in payments {
-            res = res.add_message(payment);
+            res = res.add_message(payment.to_msg()?);
         }
+        res = res.add_submessages(mod_resp.msgs);
 
         for event in &mod_resp.events {
             res = res.add_event(event.clone());
         }
         res = res.add_event(self.generate_event());
 
-        let recpt_opt = get_receipt_module(deps.storage)?;
-        match recpt_opt {
-            Some(recpt_mod) => {
-                let recpt_msg =
-                    recpt_mod.generate_receipt_message(deps.storage, res.events.clone())?;
-                res = res.add_message(recpt_msg);
-            }
-            None => {}
-        }
-
         Ok(res)
     }
 }
@@ -202,9 +315,18 @@ pub struct InstantiateMsg {
 
     //The attached Andromeda modules
     pub modules: Vec<ModuleDefinition>,
+
+    /// The maximum number of tokens that may ever be minted, burnt tokens included
+    pub max_supply: Option<u64>,
+    /// Minting is disabled until this has expired
+    pub mint_start: Option<Expiration>,
+    /// Minting is disabled once this has expired
+    pub mint_end: Option<Expiration>,
+    /// Who may update a token's metadata, defaults to `MetadataUpdatePolicy::Publisher`
+    pub metadata_update_policy: Option<MetadataUpdatePolicy>,
 }
 impl InstantiateMsg {
-    pub fn validate(&self) -> StdResult<bool> {
+    pub fn validate(&self) -> Result<bool, ContractError> {
         // [TOK-01] Add illegal names symbols as much as you want
         let illegal_names = vec![
             "Bitcoin".to_string(),
@@ -227,21 +349,55 @@ impl InstantiateMsg {
         let blacklist = vec!["blacklisted".to_string()];
         require(
             !illegal_names.contains(&self.name),
-            StdError::generic_err("Name is illegal to be initialized."),
+            ContractError::IllegalName {},
         )?;
         require(
             !illegal_symbols.contains(&self.symbol),
-            StdError::generic_err("Symbol is illegal to be used."),
+            ContractError::IllegalSymbol {},
         )?;
 
         require(
             !blacklist.contains(&self.minter),
-            StdError::generic_err("Minter is on blacklist. Not allowed to mint."),
+            ContractError::MinterBlacklisted {},
+        )?;
+        require(
+            self.max_supply != Some(0),
+            ContractError::InvalidMaxSupply {},
         )?;
+        if let (Some(mint_start), Some(mint_end)) = (self.mint_start, self.mint_end) {
+            require(mint_start < mint_end, ContractError::InvalidMintWindow {})?;
+        }
 
         Ok(true)
     }
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+/// Defines who may update the metadata of a token
+pub enum MetadataUpdatePolicy {
+    /// Only the original publisher of the token
+    #[default]
+    Publisher,
+    /// Only the current owner of the token
+    Owner,
+    /// Either the original publisher or the current owner of the token
+    PublisherOrOwner,
+}
+
+impl MetadataUpdatePolicy {
+    /// Whether the given address may update the metadata of the given token
+    pub fn is_authorized(&self, token: &Token, addr: &str) -> bool {
+        match self {
+            MetadataUpdatePolicy::Publisher => token.publisher == addr,
+            MetadataUpdatePolicy::Owner => token.owner == addr,
+            MetadataUpdatePolicy::PublisherOrOwner => {
+                token.publisher == addr || token.owner == addr
+            }
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MintMsg {
     /// The ID of the token
@@ -258,6 +414,8 @@ pub struct MintMsg {
     pub metadata: Option<TokenMetadata>,
     /// The listing price of the token
     pub pricing: Option<Coin>,
+    /// A royalty applied to agreed transfers of the token in place of the contract's royalty module
+    pub royalty: Option<Royalty>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -265,8 +423,17 @@ pub struct MintMsg {
 pub enum ExecuteMsg {
     /// Mints a token
     Mint(MintMsg),
+    /// Mints several tokens at once, generating a single receipt for the batch
+    BatchMint { tokens: Vec<MintMsg> },
     /// Transfers ownership of a token
     TransferNft { recipient: String, token_id: String },
+    /// Receives CW20 tokens sent as payment for an agreed transfer, the attached message must be a `Cw20HookMsg`
+    Receive(Cw20ReceiveMsg),
+    /// Transfers ownership of several tokens to the same recipient, generating a single receipt for the batch
+    BatchTransferNft {
+        recipient: String,
+        token_ids: Vec<String>,
+    },
     /// Sends a token to another contract
     SendNft {
         contract: String,
@@ -291,13 +458,14 @@ pub enum ExecuteMsg {
     RevokeAll { operator: String },
     /// Burns a token, removing all data related to it. The ID of the token is still reserved.
     Burn { token_id: String },
+    /// Burns several tokens at once, generating a single receipt for the batch
+    BatchBurn { token_ids: Vec<String> },
     /// Archives a token, causing it to be immutable but readable
     Archive { token_id: String },
     /// Assigns a `TransferAgreement` for a token
     TransferAgreement {
         token_id: String,
-        denom: String,
-        amount: Uint128,
+        amount: Funds,
         purchaser: String,
     },
     /// Updates the pricing of a token
@@ -305,11 +473,95 @@ pub enum ExecuteMsg {
         token_id: String,
         price: Option<Coin>,
     },
-    /// Update ownership of the contract. Only executable by the current contract owner.
-    UpdateOwner {
-        /// The address of the new contract owner.
+    /// Updates the metadata of a token, any field left as `None` is unchanged.
+    /// Permitted addresses are defined by the contract's `MetadataUpdatePolicy`.
+    UpdateMetadata {
+        token_id: String,
+        name: Option<String>,
+        description: Option<String>,
+        image: Option<String>,
+        metadata: Option<TokenMetadata>,
+    },
+    /// Places an offer for a token, the sent funds are held in escrow until the offer is accepted or cancelled
+    PlaceOffer {
+        token_id: String,
+        expires: Expiration,
+    },
+    /// Accepts an offer for a token, transferring the token to the purchaser. Only executable by the token owner.
+    AcceptOffer { token_id: String, purchaser: String },
+    /// Cancels the sender's offer for a token, refunding the escrowed funds
+    CancelOffer { token_id: String },
+    /// Starts an auction for a token, ending `duration` seconds from now. Requires the contract to have an `Auction` module.
+    /// Only executable by the token owner.
+    StartAuction {
+        token_id: String,
+        auction_type: AuctionType,
+        denom: String,
+        duration: u64,
+    },
+    /// Places a bid on an auctioned token, the sent funds are held in escrow.
+    /// A bid matching the current price of a Dutch auction immediately wins the token.
+    PlaceBid { token_id: String },
+    /// Settles an ended auction, transferring the token to the highest bidder if there is one
+    SettleAuction { token_id: String },
+    /// Cancels an auction without any bids. Only executable by the seller.
+    CancelAuction { token_id: String },
+    /// Permanently freezes the metadata of a token, or of the whole collection if no `token_id` is provided.
+    /// Freezing the collection is only executable by the current contract owner.
+    FreezeMetadata { token_id: Option<String> },
+    /// Propose a new owner of the contract, replacing any pending proposal. Only executable by the current contract owner.
+    ProposeOwner {
+        /// The address of the proposed contract owner.
         address: String,
+        /// When the proposal lapses if it has not been accepted.
+        expiration: Option<Expiration>,
     },
+    /// Accept a pending ownership proposal. Only executable by the proposed owner.
+    AcceptOwnership {},
+    /// Withdraw a pending ownership proposal. Only executable by the current contract owner.
+    CancelOwnershipProposal {},
+    /// Permanently give up ownership of the contract. Only executable by the current contract owner.
+    RenounceOwnership {},
+    /// Grant a role to an address, optionally until an expiration. Only executable by the current contract owner.
+    GrantRole {
+        /// The name of the role, such as "moderator", "minter" or "operator".
+        role: String,
+        address: String,
+        /// When the role lapses. Defaults to never.
+        expiration: Option<Expiration>,
+    },
+    /// Revoke a role from an address. Only executable by the current contract owner.
+    RevokeRole { role: String, address: String },
+    /// Replaces the minter of the contract. Only executable by the current contract owner.
+    UpdateMinter {
+        /// The address of the new minter.
+        address: String,
+    },
+    /// Adds an additional minter by granting it the `minter` role. Only executable by the current contract owner.
+    AddMinter { address: String },
+    /// Removes an additional minter by revoking its `minter` role. Only executable by the current contract owner.
+    RemoveMinter { address: String },
+    /// Adds a module to the contract, instantiating its module contract if required. Only executable by the current contract owner.
+    AddModule { module: ModuleDefinition },
+    /// Removes the module at the given index of the contract's modules. Only executable by the current contract owner.
+    RemoveModule { module_idx: u64 },
+    /// Replaces the module at the given index of the contract's modules, instantiating its module contract if required. Only executable by the current contract owner.
+    UpdateModule {
+        module_idx: u64,
+        module: ModuleDefinition,
+    },
+    /// Continues a pending migration of tokens from the legacy format, migrating up to `limit` tokens.
+    /// All other messages are rejected until the migration is complete. Only executable by the current contract owner.
+    MigrateTokens { limit: Option<u32> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// Messages that may be attached to CW20 tokens sent to the contract
+pub enum Cw20HookMsg {
+    /// Transfers ownership of a token whose transfer agreement is priced in the sent CW20 token.
+    /// The sender of the CW20 tokens must be the purchaser defined in the agreement, any excess tokens are refunded.
+    TransferNft { recipient: String, token_id: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -330,18 +582,109 @@ pub enum QueryMsg {
     NftInfo { token_id: String },
     /// The data of a token and any approvals assigned to it
     AllNftInfo { token_id: String },
+    /// IDs of all tokens owned by the given address (paginated)
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// IDs of all tokens held by the contract (paginated)
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     /// Info of any modules assigned to the contract
     ModuleInfo {},
     /// The current config of the contract
     ContractInfo {},
     /// The current owner of the contract
     ContractOwner {},
+    /// The pending ownership proposal, if any
+    PendingOwner {},
+    /// Whether an address currently holds a role
+    HasRole { role: String, address: String },
+    /// The addresses currently holding a role
+    RoleMembers {
+        role: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// The minter and any additional minters of the contract (additional minters paginated by address)
+    Minters {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// The royalty payments required for a sale of the given token at the given price
+    RoyaltyInfo { token_id: String, sale_price: Coin },
+    /// Whether royalties apply to agreed transfers of the given token, from either the token's own royalty or the contract's royalty module
+    CheckRoyalties { token_id: String },
+    /// The current auction of the given token
+    Auction { token_id: String },
+    /// Any offers placed for the given token (paginated by purchaser)
+    Offers {
+        token_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// The stored contract name and version
+    Version {},
 }
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ArchivedResponse {
     pub archived: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractInfoResponse {
+    pub name: String,
+    pub symbol: String,
+    /// The maximum number of tokens that may ever be minted, burnt tokens included
+    pub max_supply: Option<u64>,
+    /// Minting is disabled until this has expired
+    pub mint_start: Option<Expiration>,
+    /// Minting is disabled once this has expired
+    pub mint_end: Option<Expiration>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltyPayment {
+    /// The receiver of the royalty payment
+    pub receiver: String,
+    /// The amount to be paid to the receiver
+    pub amount: Coin,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltyInfoResponse {
+    /// The royalty payments required, deducted from the seller's payment
+    pub royalties: Vec<RoyaltyPayment>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CheckRoyaltiesResponse {
+    pub royalty_payments: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuctionResponse {
+    pub auction: Auction,
+    /// The minimum amount required for a bid to be accepted
+    pub current_price: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OffersResponse {
+    pub offers: Vec<Offer>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MintersResponse {
+    /// The minter assigned at instantiation (or by `UpdateMinter`)
+    pub minter: String,
+    /// Any additional addresses allowed to mint, i.e. those holding the `minter` role
+    pub additional_minters: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ModulesResponse {
     pub modules: Vec<ModuleDefinition>,
@@ -351,6 +694,8 @@ pub struct ModulesResponse {
 pub struct NftInfoResponseExtension {
     pub metadata: Option<TokenMetadata>,
     pub archived: bool,
+    /// Whether the token's metadata can no longer be updated
+    pub metadata_frozen: bool,
     pub pricing: Option<Coin>,
     pub transfer_agreement: Option<TransferAgreement>,
 }
@@ -359,6 +704,19 @@ pub struct NftInfoResponseExtension {
 pub struct ModuleInfoResponse {
     pub modules: Vec<ModuleDefinition>,
     pub contracts: Vec<ModuleContract>,
+    /// The modules in the order their hooks are executed
+    pub execution_order: Vec<ModuleOrder>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// A module's position in the order in which module hooks are executed
+pub struct ModuleOrder {
+    /// The index of the module within the contract's modules
+    pub module_idx: u64,
+    /// The module name
+    pub module: String,
+    /// The priority of the module's hooks
+    pub priority: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -371,4 +729,8 @@ pub struct ModuleContract {
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    /// The maximum number of tokens migrated from the legacy format by the migration itself.
+    /// Any remaining tokens are migrated with `ExecuteMsg::MigrateTokens`.
+    pub limit: Option<u32>,
+}
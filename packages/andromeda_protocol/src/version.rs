@@ -0,0 +1,165 @@
+use crate::error::ContractError;
+use cosmwasm_std::{attr, Deps, Response, Storage};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Stored under the same key as the `cw2` specification so that tooling can read it
+pub const CONTRACT_INFO: Item<ContractVersion> = Item::new("contract_info");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractVersion {
+    /// The crate name of the contract, e.g. `crates.io:andromeda-token`
+    pub contract: String,
+    /// The semantic version of the contract, e.g. `0.1.0`
+    pub version: String,
+}
+
+/// Records the name and version of the contract. Called at instantiation and after a successful migration.
+pub fn set_contract_version(
+    storage: &mut dyn Storage,
+    contract: &str,
+    version: &str,
+) -> Result<(), ContractError> {
+    Ok(CONTRACT_INFO.save(
+        storage,
+        &ContractVersion {
+            contract: contract.to_string(),
+            version: version.to_string(),
+        },
+    )?)
+}
+
+/// Parses a `major.minor.patch` version, ignoring any pre-release or build suffix on the patch number.
+fn parse_version(version: &str) -> Result<(u64, u64, u64), ContractError> {
+    let parts: Vec<&str> = version.splitn(3, '.').collect();
+    if parts.len() != 3 {
+        return Err(ContractError::InvalidContractVersion {
+            version: version.to_string(),
+        });
+    }
+    let parse = |part: &str| {
+        part.split(['-', '+'])
+            .next()
+            .and_then(|number| number.parse::<u64>().ok())
+            .ok_or(ContractError::InvalidContractVersion {
+                version: version.to_string(),
+            })
+    };
+
+    Ok((parse(parts[0])?, parse(parts[1])?, parse(parts[2])?))
+}
+
+/// Validates a migration and records the new contract version.
+///
+/// Errors if the stored contract name differs from `contract` or if the stored version is newer than `version`.
+/// Contracts instantiated before versioning was introduced have no stored version and may always be migrated.
+///
+/// Returns the previously stored version, if any, so that storage migrations can be run conditionally.
+pub fn migrate_version(
+    storage: &mut dyn Storage,
+    contract: &str,
+    version: &str,
+) -> Result<Option<ContractVersion>, ContractError> {
+    let stored = CONTRACT_INFO.may_load(storage)?;
+    if let Some(stored) = &stored {
+        if stored.contract != contract {
+            return Err(ContractError::ContractNameMismatch {
+                expected: contract.to_string(),
+                found: stored.contract.clone(),
+            });
+        }
+        if parse_version(&stored.version)? > parse_version(version)? {
+            return Err(ContractError::CannotMigrateToOlderVersion {
+                current: stored.version.clone(),
+                new: version.to_string(),
+            });
+        }
+    }
+    set_contract_version(storage, contract, version)?;
+
+    Ok(stored)
+}
+
+/// Standard response for a successful migration.
+pub fn migrate_response(previous: Option<ContractVersion>, version: &str) -> Response {
+    let previous = previous.map_or(String::from("none"), |v| v.version);
+    Response::new().add_attributes(vec![
+        attr("action", "migrate"),
+        attr("from_version", previous),
+        attr("to_version", version),
+    ])
+}
+
+pub fn query_version(deps: Deps) -> Result<ContractVersion, ContractError> {
+    Ok(CONTRACT_INFO.load(deps.storage)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::mock_dependencies;
+
+    use super::*;
+
+    const CONTRACT: &str = "crates.io:andromeda-test";
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("0.1.0").unwrap(), (0, 1, 0));
+        assert_eq!(parse_version("1.12.3-beta.1").unwrap(), (1, 12, 3));
+        assert_eq!(
+            parse_version("1.0").unwrap_err(),
+            ContractError::InvalidContractVersion {
+                version: String::from("1.0")
+            }
+        );
+        assert_eq!(
+            parse_version("1.x.0").unwrap_err(),
+            ContractError::InvalidContractVersion {
+                version: String::from("1.x.0")
+            }
+        );
+    }
+
+    #[test]
+    fn test_migrate_version() {
+        let mut deps = mock_dependencies(&[]);
+
+        // Contracts without a stored version can always be migrated
+        let previous = migrate_version(deps.as_mut().storage, CONTRACT, "0.1.0").unwrap();
+        assert_eq!(previous, None);
+        assert_eq!(
+            query_version(deps.as_ref()).unwrap(),
+            ContractVersion {
+                contract: CONTRACT.to_string(),
+                version: String::from("0.1.0"),
+            }
+        );
+
+        let previous = migrate_version(deps.as_mut().storage, CONTRACT, "0.2.0").unwrap();
+        assert_eq!(previous.unwrap().version, "0.1.0");
+
+        // Re-running a migration for the same version is permitted
+        migrate_version(deps.as_mut().storage, CONTRACT, "0.2.0").unwrap();
+
+        let err = migrate_version(deps.as_mut().storage, CONTRACT, "0.1.9").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::CannotMigrateToOlderVersion {
+                current: String::from("0.2.0"),
+                new: String::from("0.1.9"),
+            }
+        );
+
+        let err = migrate_version(deps.as_mut().storage, "crates.io:andromeda-other", "0.3.0")
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ContractNameMismatch {
+                expected: String::from("crates.io:andromeda-other"),
+                found: CONTRACT.to_string(),
+            }
+        );
+        assert_eq!(query_version(deps.as_ref()).unwrap().version, "0.2.0");
+    }
+}